@@ -33,6 +33,8 @@ pub enum Error {
     ColumnLookup(String),
     #[error("failed to cast tensor of type {from:?} to type {to:?}")]
     Cast { to: TensorType, from: TensorType },
+    #[error("column contains null values but was cast to non-nullable type {0:?}")]
+    UnexpectedNull(TensorType),
     #[error("unknown extension type {0}")]
     UnknownExtension(String),
     #[error("missing metadata for extension type {0}")]
@@ -96,6 +98,24 @@ pub enum ShapeError {
     Broadcast(Vec<usize>, Vec<usize>),
     #[error("array with {0} elements is incompatible with shape {1:?}")]
     ArraySize(usize, Vec<usize>),
+    #[error("cannot squeeze axis {axis} with length {len}")]
+    Squeeze { axis: usize, len: usize },
+    #[error("shapes {0:?} and {1:?} are incompatible for matrix multiplication")]
+    MatMul(Vec<usize>, Vec<usize>),
+    #[error("cannot split axis with length {len} into parts with total length {total}")]
+    Split { len: usize, total: usize },
+    #[error("axis order {0:?} is not a permutation of {1} axes")]
+    Permutation(Vec<usize>, usize),
+    #[error("cannot infer shape {0:?} for array with {1} elements")]
+    InferShape(Vec<isize>, usize),
+    #[error("padding of {width} is too wide for axis {axis} with length {len}")]
+    PadWidth {
+        axis: usize,
+        len: usize,
+        width: usize,
+    },
+    #[error("cannot flatten axes {start}..={end}")]
+    FlattenAxes { start: usize, end: usize },
 }
 
 impl ShapeError {
@@ -110,6 +130,38 @@ impl ShapeError {
     pub fn broadcast(lhs: &[usize], rhs: &[usize]) -> Self {
         Self::Broadcast(lhs.to_vec(), rhs.to_vec())
     }
+
+    pub fn array_size(size: usize, shape: &[usize]) -> Self {
+        Self::ArraySize(size, shape.to_vec())
+    }
+
+    pub fn squeeze(axis: usize, len: usize) -> Self {
+        Self::Squeeze { axis, len }
+    }
+
+    pub fn matmul(lhs: &[usize], rhs: &[usize]) -> Self {
+        Self::MatMul(lhs.to_vec(), rhs.to_vec())
+    }
+
+    pub fn split(len: usize, total: usize) -> Self {
+        Self::Split { len, total }
+    }
+
+    pub fn permutation(order: &[usize], ndim: usize) -> Self {
+        Self::Permutation(order.to_vec(), ndim)
+    }
+
+    pub fn infer_shape(shape: &[isize], size: usize) -> Self {
+        Self::InferShape(shape.to_vec(), size)
+    }
+
+    pub fn pad_width(axis: usize, len: usize, width: usize) -> Self {
+        Self::PadWidth { axis, len, width }
+    }
+
+    pub fn flatten_axes(start: usize, end: usize) -> Self {
+        Self::FlattenAxes { start, end }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -152,6 +204,20 @@ pub enum EngineError {
     TableKind { expected: String, actual: String },
     #[error("{0}")]
     InvalidIndex(String),
+    #[error("invalid import: {0}")]
+    InvalidImport(String),
+    #[error("manifest version {0} not found")]
+    VersionNotFound(String),
+    #[error("datastore is read-only")]
+    ReadOnly,
+    #[error("column {0} contains NaN or infinite values")]
+    NonFinite(String),
+    #[error("{0} is managed by the engine and cannot be modified")]
+    SystemTable(String),
+    #[error("invalid table name {0}")]
+    InvalidTableName(String),
+    #[error("results are incomplete: storage became unavailable after {rows} rows: {error}")]
+    PartialResults { rows: usize, error: String },
 }
 
 impl EngineError {
@@ -204,6 +270,8 @@ pub enum ServerError {
     Token(String),
     #[error("invalid server secret")]
     InvalidSecret,
+    #[error("table {0} has an access control list but the server has no credential store")]
+    UnverifiedAcl(String),
 }
 
 #[cfg(feature = "flight")]
@@ -220,6 +288,7 @@ impl ServerError {
 #[cfg(feature = "flight")]
 impl From<Error> for tonic::Status {
     fn from(e: Error) -> Self {
+        use datafusion::arrow::error::ArrowError;
         use tonic::Status;
         use ServerError::*;
 
@@ -227,6 +296,25 @@ impl From<Error> for tonic::Status {
             Error::Server(InvalidTicket(_)) | Error::Server(InvalidPrepareQuery(_)) => {
                 Status::invalid_argument(format!("{}", e))
             }
+            Error::Arrow(
+                err @ (ArrowError::SchemaError(_)
+                | ArrowError::CastError(_)
+                | ArrowError::InvalidArgumentError(_)),
+            ) => Status::invalid_argument(format!("{}", err)),
+            Error::Client(ClientError::InvalidIpc(_)) => Status::invalid_argument(format!("{}", e)),
+            Error::Engine(EngineError::TableNotFound(_)) => Status::not_found(format!("{}", e)),
+            Error::Engine(EngineError::TableQueueFull) => {
+                Status::resource_exhausted(format!("{}", e))
+            }
+            Error::Engine(EngineError::TableClosed) => Status::unavailable(format!("{}", e)),
+            Error::Engine(EngineError::ReadOnly) => Status::failed_precondition(format!("{}", e)),
+            Error::Engine(EngineError::SystemTable(_)) => {
+                Status::permission_denied(format!("{}", e))
+            }
+            Error::Engine(EngineError::NonFinite(_)) => Status::invalid_argument(format!("{}", e)),
+            Error::Engine(EngineError::InvalidTableName(_)) => {
+                Status::invalid_argument(format!("{}", e))
+            }
             _ => Status::internal(format!("{:?}", e)),
         }
     }
@@ -236,7 +324,9 @@ impl From<Error> for tonic::Status {
 #[derive(Debug, thiserror::Error)]
 pub enum ClientError {
     #[error("server error: {0}")]
-    Server(#[from] tonic::Status),
+    Server(tonic::Status),
+    #[error("server is read-only")]
+    ReadOnly,
     #[error("topic sink closed unexpectedly")]
     TopicClosed,
     #[error("no flight ticket in server response")]
@@ -247,4 +337,71 @@ pub enum ClientError {
     InvalidUri(String),
     #[error("authorization token is not a valid string")]
     InvalidToken,
+    #[error("invalid arrow IPC stream: {0}")]
+    InvalidIpc(String),
+    #[error("invalid prepared statement parameters: {0}")]
+    InvalidParameters(String),
+    #[error(transparent)]
+    Publish(#[from] PublishError),
+}
+
+#[cfg(feature = "flight")]
+impl From<tonic::Status> for ClientError {
+    fn from(status: tonic::Status) -> Self {
+        // The server reports read-only violations with the message of `EngineError::ReadOnly`
+        if status.code() == tonic::Code::FailedPrecondition
+            && status.message() == EngineError::ReadOnly.to_string()
+        {
+            Self::ReadOnly
+        } else {
+            Self::Server(status)
+        }
+    }
+}
+
+/// Error returned when data published to the server is not acknowledged.
+#[cfg(feature = "flight")]
+#[derive(Debug, thiserror::Error)]
+pub enum PublishError {
+    /// The server refused the data, e.g. because its schema doesn't match the table.
+    #[error("server rejected published data: {}", .0.message())]
+    Rejected(tonic::Status),
+    /// The connection failed before the server acknowledged the data.
+    #[error("transport error while publishing: {}", .0.message())]
+    Transport(tonic::Status),
+}
+
+#[cfg(feature = "flight")]
+impl PublishError {
+    /// Whether publishing the same data again may succeed.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Transport(_))
+    }
+
+    pub fn status(&self) -> &tonic::Status {
+        match self {
+            Self::Rejected(status) | Self::Transport(status) => status,
+        }
+    }
+}
+
+#[cfg(feature = "flight")]
+impl From<tonic::Status> for PublishError {
+    fn from(status: tonic::Status) -> Self {
+        use std::error::Error;
+        use tonic::Code;
+
+        // Statuses created by the client from a connection error carry the underlying error,
+        // while statuses returned by the server do not.
+        let transport = status.source().is_some()
+            || matches!(
+                status.code(),
+                Code::Unavailable | Code::Cancelled | Code::DeadlineExceeded
+            );
+        if transport {
+            Self::Transport(status)
+        } else {
+            Self::Rejected(status)
+        }
+    }
 }
@@ -267,11 +267,12 @@ impl Axis {
     where
         S: Shape,
     {
-        let mut idx = self.0.abs_index(shape.ndim());
+        // Negative axes count from the end of the larger shape
         if self.0 < 0 {
-            idx += 1;
+            self.0.abs_index(shape.ndim() + 1)
+        } else {
+            self.0 as usize
         }
-        idx
     }
 }
 
@@ -84,6 +84,23 @@ impl EllaConfigBuilder {
 pub struct EngineConfig {
     serve_metrics: Option<SocketAddr>,
     maintenance_interval: Duration,
+    compaction_concurrency: usize,
+    auto_compaction: bool,
+    compaction_min_files: usize,
+    compaction_small_file_percent: Option<u8>,
+    auto_create_namespaces: bool,
+    batch_size: usize,
+    best_effort_reads: bool,
+    max_concurrent_queries: Option<usize>,
+    target_partitions: Option<usize>,
+    round_robin_repartition: bool,
+    repartition_joins: bool,
+    repartition_aggregations: bool,
+    parquet_pushdown_filters: bool,
+    max_message_size: usize,
+    partitioned_results: bool,
+    ticket_ttl: Duration,
+    read_only: bool,
 }
 
 impl Default for EngineConfig {
@@ -91,6 +108,23 @@ impl Default for EngineConfig {
         Self {
             serve_metrics: None,
             maintenance_interval: Duration::seconds(30),
+            compaction_concurrency: 1,
+            auto_compaction: true,
+            compaction_min_files: 2,
+            compaction_small_file_percent: None,
+            auto_create_namespaces: false,
+            batch_size: 8192,
+            best_effort_reads: false,
+            max_concurrent_queries: None,
+            target_partitions: None,
+            round_robin_repartition: false,
+            repartition_joins: true,
+            repartition_aggregations: true,
+            parquet_pushdown_filters: false,
+            max_message_size: 2 * 1024 * 1024,
+            partitioned_results: false,
+            ticket_ttl: Duration::minutes(10),
+            read_only: false,
         }
     }
 }
@@ -112,6 +146,113 @@ impl EngineConfig {
         self.maintenance_interval
     }
 
+    /// Maximum number of tables compacted at the same time.
+    pub fn compaction_concurrency(&self) -> usize {
+        self.compaction_concurrency
+    }
+
+    /// Whether topics are compacted by the background maintenance task.
+    pub fn auto_compaction(&self) -> bool {
+        self.auto_compaction
+    }
+
+    /// Number of shards smaller than the target shard size that triggers a background compaction.
+    pub fn compaction_min_files(&self) -> usize {
+        self.compaction_min_files
+    }
+
+    /// Percentage of a topic's shards that must be small to trigger a background compaction,
+    /// regardless of the number of small shards.
+    pub fn compaction_small_file_percent(&self) -> Option<u8> {
+        self.compaction_small_file_percent
+    }
+
+    /// Whether a topic with `small` undersized shards out of `total` shards should be compacted
+    /// by the background maintenance task.
+    pub(crate) fn should_compact(&self, small: usize, total: usize) -> bool {
+        if !self.auto_compaction || small < 2 {
+            return false;
+        }
+        let ratio = self
+            .compaction_small_file_percent
+            .map_or(false, |percent| small * 100 >= percent as usize * total);
+        small >= self.compaction_min_files || ratio
+    }
+
+    /// Whether missing catalogs and schemas are created when first referenced.
+    pub fn auto_create_namespaces(&self) -> bool {
+        self.auto_create_namespaces
+    }
+
+    /// Maximum number of rows in each batch returned by a query.
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// Whether queries that lose access to storage after some rows have been read end with a
+    /// [`PartialResults`](crate::EngineError::PartialResults) error instead of the storage
+    /// error, telling callers that the rows they already received are valid but incomplete.
+    pub fn best_effort_reads(&self) -> bool {
+        self.best_effort_reads
+    }
+
+    /// Maximum number of queries executed at the same time.
+    ///
+    /// Additional queries wait in arrival order until a running query finishes. Unbounded
+    /// queries over live topics are not counted. The limit is read when the datastore is opened.
+    pub fn max_concurrent_queries(&self) -> Option<usize> {
+        self.max_concurrent_queries
+    }
+
+    /// Number of partitions used by the query planner, or the number of CPUs if `None`.
+    pub fn target_partitions(&self) -> Option<usize> {
+        self.target_partitions
+    }
+
+    /// Whether the planner may repartition plans to increase parallelism.
+    pub fn round_robin_repartition(&self) -> bool {
+        self.round_robin_repartition
+    }
+
+    /// Whether joins are repartitioned to run in parallel.
+    pub fn repartition_joins(&self) -> bool {
+        self.repartition_joins
+    }
+
+    /// Whether aggregations are repartitioned to run in parallel.
+    pub fn repartition_aggregations(&self) -> bool {
+        self.repartition_aggregations
+    }
+
+    /// Whether filters are evaluated while decoding parquet files.
+    pub fn parquet_pushdown_filters(&self) -> bool {
+        self.parquet_pushdown_filters
+    }
+
+    /// Target maximum size in bytes of each Flight message containing query results.
+    ///
+    /// Batches are split by rows to stay under the limit, so a single row larger than the limit
+    /// is still sent in one message.
+    pub fn max_message_size(&self) -> usize {
+        self.max_message_size
+    }
+
+    /// Whether Flight SQL queries return one endpoint per output partition so that clients can
+    /// fetch the partitions in parallel.
+    pub fn partitioned_results(&self) -> bool {
+        self.partitioned_results
+    }
+
+    /// How long a query plan issued to a Flight client is kept before it must be fetched.
+    pub fn ticket_ttl(&self) -> Duration {
+        self.ticket_ttl
+    }
+
+    /// Whether writes, DDL statements and compaction are rejected with `EngineError::ReadOnly`.
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
     pub fn into_builder(self) -> EngineConfigBuilder {
         EngineConfigBuilder(self)
     }
@@ -131,6 +272,91 @@ impl EngineConfigBuilder {
         self
     }
 
+    pub fn compaction_concurrency(mut self, concurrency: usize) -> Self {
+        self.0.compaction_concurrency = concurrency.max(1);
+        self
+    }
+
+    pub fn auto_compaction(mut self, enabled: bool) -> Self {
+        self.0.auto_compaction = enabled;
+        self
+    }
+
+    pub fn compaction_min_files(mut self, files: usize) -> Self {
+        self.0.compaction_min_files = files.max(2);
+        self
+    }
+
+    pub fn compaction_small_file_percent(mut self, percent: u8) -> Self {
+        self.0.compaction_small_file_percent = Some(percent.min(100));
+        self
+    }
+
+    pub fn auto_create_namespaces(mut self, enabled: bool) -> Self {
+        self.0.auto_create_namespaces = enabled;
+        self
+    }
+
+    pub fn batch_size(mut self, rows: usize) -> Self {
+        self.0.batch_size = rows.max(1);
+        self
+    }
+
+    pub fn best_effort_reads(mut self, enabled: bool) -> Self {
+        self.0.best_effort_reads = enabled;
+        self
+    }
+
+    pub fn max_concurrent_queries(mut self, limit: usize) -> Self {
+        self.0.max_concurrent_queries = Some(limit.max(1));
+        self
+    }
+
+    pub fn target_partitions(mut self, partitions: usize) -> Self {
+        self.0.target_partitions = Some(partitions.max(1));
+        self
+    }
+
+    pub fn round_robin_repartition(mut self, enabled: bool) -> Self {
+        self.0.round_robin_repartition = enabled;
+        self
+    }
+
+    pub fn repartition_joins(mut self, enabled: bool) -> Self {
+        self.0.repartition_joins = enabled;
+        self
+    }
+
+    pub fn repartition_aggregations(mut self, enabled: bool) -> Self {
+        self.0.repartition_aggregations = enabled;
+        self
+    }
+
+    pub fn parquet_pushdown_filters(mut self, enabled: bool) -> Self {
+        self.0.parquet_pushdown_filters = enabled;
+        self
+    }
+
+    pub fn max_message_size(mut self, bytes: usize) -> Self {
+        self.0.max_message_size = bytes.max(1);
+        self
+    }
+
+    pub fn partitioned_results(mut self, enabled: bool) -> Self {
+        self.0.partitioned_results = enabled;
+        self
+    }
+
+    pub fn ticket_ttl(mut self, ttl: Duration) -> Self {
+        self.0.ticket_ttl = ttl;
+        self
+    }
+
+    pub fn read_only(mut self, enabled: bool) -> Self {
+        self.0.read_only = enabled;
+        self
+    }
+
     pub fn build(self) -> EngineConfig {
         self.0
     }
@@ -1,8 +1,16 @@
+mod audit;
 mod context;
+mod ingest;
+mod queue;
 mod state;
 
+pub use audit::{AuditAction, AUDIT_TABLE, SYSTEM_SCHEMA};
 pub use context::EllaContext;
+pub use ingest::{IngestFormat, IngestOptions};
 pub use state::EllaState;
+pub(crate) use state::SessionOptions;
+
+pub use crate::util::VacuumReport;
 
 use std::{fmt::Debug, sync::Arc};
 
@@ -0,0 +1,62 @@
+use std::{fmt::Display, sync::Arc};
+
+use datafusion::arrow::{
+    array::{ArrayRef, StringArray, TimestampNanosecondArray},
+    record_batch::RecordBatch,
+};
+use ella_common::TensorType;
+
+use crate::table::{info::TopicInfo, Column};
+
+/// Schema containing the engine's system tables.
+pub const SYSTEM_SCHEMA: &str = "system";
+/// Name of the audit log topic in the system schema.
+pub const AUDIT_TABLE: &str = "audit";
+
+/// Kind of operation recorded in the audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    Create,
+    Drop,
+    Delete,
+    Update,
+}
+
+impl Display for AuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Create => write!(f, "CREATE"),
+            Self::Drop => write!(f, "DROP"),
+            Self::Delete => write!(f, "DELETE"),
+            Self::Update => write!(f, "UPDATE"),
+        }
+    }
+}
+
+pub(crate) fn topic_info() -> TopicInfo {
+    TopicInfo::builder()
+        .column(Column::new("principal", TensorType::String))
+        .column(Column::builder("action", TensorType::String).required())
+        .column(Column::builder("object", TensorType::String).required())
+        .column(Column::builder("statement", TensorType::String).required())
+        .build()
+}
+
+pub(crate) fn record(
+    info: &TopicInfo,
+    principal: Option<&str>,
+    action: AuditAction,
+    object: &str,
+    statement: &str,
+) -> crate::Result<RecordBatch> {
+    let time = TimestampNanosecondArray::from(vec![ella_common::now().timestamp()])
+        .with_timezone("+00:00");
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(time),
+        Arc::new(StringArray::from(vec![principal])),
+        Arc::new(StringArray::from(vec![action.to_string()])),
+        Arc::new(StringArray::from(vec![object])),
+        Arc::new(StringArray::from(vec![statement])),
+    ];
+    Ok(RecordBatch::try_new(info.arrow_schema(), columns)?)
+}
@@ -1,5 +1,8 @@
 use std::{fmt::Debug, ops::DerefMut, sync::Arc};
 
+use datafusion::arrow::record_batch::RecordBatch;
+use ella_common::Duration;
+use futures::stream::BoxStream;
 use tokio::sync::Mutex;
 
 use crate::{
@@ -8,15 +11,17 @@ use crate::{
     config::EllaConfig,
     engine::EllaState,
     lazy::Lazy,
-    registry::{Id, SchemaRef, TableRef},
+    registry::{Id, SchemaRef, TableRef, TransactionId, VersionInfo},
     schema::EllaSchema,
     table::{
         info::{TableInfo, TopicInfo, ViewInfo},
+        topic::{read_changes, ImportOptions, ShardInfo},
         EllaTable, EllaTopic, EllaView,
     },
+    util::{compact_tables, vacuum_table},
 };
 
-use super::Engine;
+use super::{Engine, IngestOptions, VacuumReport};
 
 #[derive(Clone)]
 pub struct EllaContext {
@@ -39,12 +44,18 @@ impl EllaContext {
         Ok(Self { state, engine })
     }
 
+    /// Set the default catalog.
+    ///
+    /// Returns an error if the catalog doesn't exist, unless namespace auto-creation is enabled,
+    /// in which case the catalog is created when it's first written to.
     pub fn use_catalog<'a>(mut self, catalog: impl Into<Id<'a>>) -> crate::Result<Self> {
         let catalog: Id<'static> = catalog.into().into_owned();
 
-        self.cluster()
-            .catalog(catalog.as_ref())
-            .ok_or_else(|| crate::EngineError::CatalogNotFound(catalog.to_string()))?;
+        if !self.config().engine_config().auto_create_namespaces() {
+            self.cluster()
+                .catalog(catalog.as_ref())
+                .ok_or_else(|| crate::EngineError::CatalogNotFound(catalog.to_string()))?;
+        }
 
         let config = self
             .state
@@ -57,14 +68,22 @@ impl EllaContext {
         Ok(self)
     }
 
+    /// Set the default schema.
+    ///
+    /// Returns an error if the schema doesn't exist, unless namespace auto-creation is enabled,
+    /// in which case the schema is created when it's first written to.
     pub fn use_schema<'a>(mut self, schema: impl Into<Id<'a>>) -> crate::Result<Self> {
         let schema: Id<'static> = schema.into().into_owned();
 
-        self.cluster()
-            .catalog(self.default_catalog())
-            .ok_or_else(|| crate::EngineError::CatalogNotFound(self.default_catalog().to_string()))?
-            .schema(schema.as_ref())
-            .ok_or_else(|| crate::EngineError::SchemaNotFound(schema.to_string()))?;
+        if !self.config().engine_config().auto_create_namespaces() {
+            self.cluster()
+                .catalog(self.default_catalog())
+                .ok_or_else(|| {
+                    crate::EngineError::CatalogNotFound(self.default_catalog().to_string())
+                })?
+                .schema(schema.as_ref())
+                .ok_or_else(|| crate::EngineError::SchemaNotFound(schema.to_string()))?;
+        }
 
         let config = self
             .state
@@ -77,6 +96,37 @@ impl EllaContext {
         Ok(self)
     }
 
+    /// Make queries from this context see every row published before they started.
+    ///
+    /// Only this context is affected; other contexts opened on the same datastore keep reading
+    /// rows once they have been buffered.
+    pub fn read_your_writes(mut self, enabled: bool) -> Self {
+        self.state.with_read_your_writes(enabled);
+        self
+    }
+
+    /// Reject writes, DDL statements and compaction made through this context with
+    /// `EngineError::ReadOnly`.
+    ///
+    /// Only this context is affected. A context on a read-only datastore can't be made writable.
+    pub fn read_only(mut self, enabled: bool) -> Self {
+        let engine = self
+            .config()
+            .engine_config()
+            .clone()
+            .into_builder()
+            .read_only(enabled)
+            .build();
+        let config = self
+            .config()
+            .clone()
+            .into_builder()
+            .engine_config(engine)
+            .build();
+        self.state.with_config(config);
+        self
+    }
+
     pub async fn query(&self, sql: impl AsRef<str>) -> crate::Result<Lazy> {
         self.state.query(sql).await
     }
@@ -153,10 +203,139 @@ impl EllaContext {
         self.state.create_catalog(catalog, if_not_exists).await
     }
 
+    /// Create a topic backed by the existing parquet files under `prefix` without rewriting them.
+    pub async fn import_parquet<'a>(
+        &self,
+        table: impl Into<TableRef<'a>>,
+        prefix: &str,
+        options: ImportOptions,
+    ) -> crate::Result<Arc<EllaTopic>> {
+        self.state
+            .import_parquet(self.state.resolve(table.into()), prefix.parse()?, options)
+            .await
+    }
+
+    /// Publish the rows of a CSV or JSON file to an existing topic.
+    pub async fn ingest_file<'a>(
+        &self,
+        table: impl Into<TableRef<'a>>,
+        path: &str,
+        options: IngestOptions,
+    ) -> crate::Result<u64> {
+        self.state
+            .ingest_file(self.state.resolve(table.into()), path, options)
+            .await
+    }
+
     pub fn table<'a>(&self, table: impl Into<TableRef<'a>>) -> Option<Arc<EllaTable>> {
         self.state.table(self.state.resolve(table.into()))
     }
 
+    pub fn has_pending_writes<'a>(&self, table: impl Into<TableRef<'a>>) -> crate::Result<bool> {
+        Ok(self
+            .require_table(table)?
+            .as_topic()
+            .map_or(false, |topic| topic.has_pending_writes()))
+    }
+
+    pub async fn flush<'a>(&self, table: impl Into<TableRef<'a>>) -> crate::Result<()> {
+        if let Some(topic) = self.require_table(table)?.as_topic() {
+            topic.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Compact the shards of every topic, using up to the configured compaction concurrency.
+    pub async fn compact(&self) -> crate::Result<()> {
+        self.state.check_writable()?;
+        let tables = self
+            .cluster()
+            .catalogs()
+            .into_iter()
+            .flat_map(|c| c.schemas())
+            .flat_map(|s| s.tables())
+            .collect::<Vec<_>>();
+        compact_tables(
+            Arc::new(self.state.clone()),
+            tables,
+            self.config().engine_config().compaction_concurrency(),
+        )
+        .await
+    }
+
+    /// Delete orphaned files that were last modified more than `older_than` ago.
+    ///
+    /// A file is orphaned if it isn't part of any shard of its topic, including shards that are
+    /// still being written, and isn't readable at any retained manifest version. Topics that
+    /// write to an external location are skipped.
+    pub async fn vacuum(&self, older_than: Duration) -> crate::Result<VacuumReport> {
+        self.state.check_writable()?;
+        let cutoff = (ella_common::now() - older_than).timestamp() / 1_000_000_000;
+        let mut report = VacuumReport::default();
+        for table in self
+            .cluster()
+            .catalogs()
+            .into_iter()
+            .flat_map(|c| c.schemas())
+            .flat_map(|s| s.tables())
+        {
+            let table_report = vacuum_table(&table, &self.state, cutoff).await?;
+            report.files += table_report.files;
+            report.bytes += table_report.bytes;
+        }
+        Ok(report)
+    }
+
+    /// List the versions of a topic's manifest, oldest first.
+    pub async fn manifest_versions<'a>(
+        &self,
+        table: impl Into<TableRef<'a>>,
+    ) -> crate::Result<Vec<VersionInfo>> {
+        let id = self.require_table(table)?.id().clone();
+        self.state.log().versions(&id).await
+    }
+
+    /// Get the shards that made up a topic at a given manifest version.
+    pub async fn manifest_at<'a>(
+        &self,
+        table: impl Into<TableRef<'a>>,
+        version: TransactionId,
+    ) -> crate::Result<Vec<ShardInfo>> {
+        let id = self.require_table(table)?.id().clone();
+        self.state.log().manifest(&id, version).await
+    }
+
+    /// Stream the rows written to a topic since manifest version `version`.
+    ///
+    /// Each batch has an additional [`OPERATION_COLUMN`](crate::table::topic::OPERATION_COLUMN)
+    /// describing the change. Returns an error if shards written since `version` have been compacted.
+    pub async fn changes_since<'a>(
+        &self,
+        table: impl Into<TableRef<'a>>,
+        version: TransactionId,
+    ) -> crate::Result<BoxStream<'static, crate::Result<RecordBatch>>> {
+        let table = self.require_table(table)?;
+        let topic = table
+            .as_topic()
+            .ok_or_else(|| crate::EngineError::TableKind {
+                expected: "topic".to_string(),
+                actual: "view".to_string(),
+            })?;
+        let shards = self.state.log().shards_since(table.id(), version).await?;
+        Ok(read_changes(
+            self.state.store().clone(),
+            shards,
+            topic.info().arrow_schema(),
+        ))
+    }
+
+    fn require_table<'a>(&self, table: impl Into<TableRef<'a>>) -> crate::Result<Arc<EllaTable>> {
+        let id = self.state.resolve(table.into());
+        self.state
+            .table(id.clone())
+            .ok_or_else(|| crate::EngineError::TableNotFound(id.to_string()).into())
+    }
+
     pub async fn shutdown(self) -> crate::Result<()> {
         if let Some(engine) = std::mem::take(self.engine.lock_owned().await.deref_mut()) {
             engine.shutdown().await?;
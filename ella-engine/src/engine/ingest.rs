@@ -0,0 +1,180 @@
+use std::{collections::HashMap, sync::Arc};
+
+use arrow_schema::{Field, Schema, SchemaRef};
+use datafusion::{
+    common::{Column, ScalarValue},
+    datasource::{
+        file_format::{csv::CsvFormat, json::JsonFormat, FileFormat},
+        listing::{ListingOptions, ListingTable, ListingTableConfig, ListingTableUrl},
+    },
+    execution::context::SessionState,
+    logical_expr::{cast, lit, Expr},
+};
+use ella_common::TensorType;
+use futures::TryStreamExt;
+
+/// Format of a file passed to `ingest_file`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IngestFormat {
+    Csv {
+        has_header: bool,
+        delimiter: u8,
+    },
+    /// Newline-delimited JSON
+    Json,
+}
+
+impl IngestFormat {
+    pub fn csv() -> Self {
+        Self::Csv {
+            has_header: true,
+            delimiter: b',',
+        }
+    }
+
+    pub fn json() -> Self {
+        Self::Json
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv { .. } => ".csv",
+            Self::Json => ".json",
+        }
+    }
+}
+
+/// Options controlling how CSV and JSON files are read by `ingest_file`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IngestOptions {
+    format: IngestFormat,
+    infer_rows: Option<usize>,
+    overrides: HashMap<String, TensorType>,
+}
+
+impl IngestOptions {
+    const DEFAULT_INFER_ROWS: usize = 1000;
+
+    pub fn new(format: IngestFormat) -> Self {
+        Self {
+            format,
+            infer_rows: Some(Self::DEFAULT_INFER_ROWS),
+            overrides: HashMap::new(),
+        }
+    }
+
+    pub fn csv() -> Self {
+        Self::new(IngestFormat::csv())
+    }
+
+    pub fn json() -> Self {
+        Self::new(IngestFormat::json())
+    }
+
+    /// Infer column types from the first `rows` rows of the file.
+    pub fn infer_rows(mut self, rows: usize) -> Self {
+        self.infer_rows = Some(rows);
+        self
+    }
+
+    /// Infer column types from every row of the file.
+    pub fn infer_all(mut self) -> Self {
+        self.infer_rows = None;
+        self
+    }
+
+    /// Read `column` as `dtype` instead of inferring its type.
+    pub fn column_type(mut self, column: impl Into<String>, dtype: TensorType) -> Self {
+        self.overrides.insert(column.into(), dtype);
+        self
+    }
+
+    pub fn format(&self) -> &IngestFormat {
+        &self.format
+    }
+
+    fn file_format(&self) -> Arc<dyn FileFormat> {
+        match self.format {
+            IngestFormat::Csv {
+                has_header,
+                delimiter,
+            } => Arc::new(
+                CsvFormat::default()
+                    .with_has_header(has_header)
+                    .with_delimiter(delimiter)
+                    .with_schema_infer_max_rec(self.infer_rows),
+            ),
+            IngestFormat::Json => {
+                Arc::new(JsonFormat::default().with_schema_infer_max_rec(self.infer_rows))
+            }
+        }
+    }
+
+    /// Create a table over the file at `path`, inferring its schema and applying any type overrides.
+    ///
+    /// Returns an error if no file at `path` has the extension of the format.
+    pub(crate) async fn listing_table(
+        &self,
+        path: &str,
+        state: &SessionState,
+    ) -> crate::Result<ListingTable> {
+        let extension = self.format.extension();
+        let url = ListingTableUrl::parse(path)?;
+
+        // The listing table silently skips files with other extensions
+        let store = state.runtime_env().object_store(&url)?;
+        let files = url
+            .list_all_files(store.as_ref(), extension)
+            .try_collect::<Vec<_>>()
+            .await?;
+        if files.is_empty() {
+            return Err(crate::EngineError::InvalidImport(format!(
+                "no files with extension {} found at {}",
+                extension, path
+            ))
+            .into());
+        }
+
+        let options = ListingOptions::new(self.file_format()).with_file_extension(extension);
+        let config = ListingTableConfig::new(url)
+            .with_listing_options(options)
+            .infer_schema(state)
+            .await?;
+
+        let inferred = config
+            .file_schema
+            .clone()
+            .unwrap_or_else(|| Arc::new(Schema::empty()));
+        let fields = inferred
+            .fields()
+            .iter()
+            .map(|f| match self.overrides.get(f.name()) {
+                Some(dtype) => Field::new(f.name(), dtype.to_arrow(), true),
+                None => (**f).clone(),
+            })
+            .collect::<Vec<_>>();
+        let schema = Arc::new(Schema::new_with_metadata(
+            fields,
+            inferred.metadata().clone(),
+        ));
+        Ok(ListingTable::try_new(config.with_schema(schema))?)
+    }
+}
+
+/// Expressions that cast columns of a file with schema `source` to the topic schema `target`.
+///
+/// Columns missing from the file are filled with nulls.
+pub(crate) fn cast_to_schema(source: &Schema, target: &SchemaRef) -> crate::Result<Vec<Expr>> {
+    target
+        .fields()
+        .iter()
+        .map(|field| {
+            let expr = if source.field_with_name(field.name()).is_ok() {
+                Expr::Column(Column::from_name(field.name()))
+            } else {
+                lit(ScalarValue::try_from(field.data_type())?)
+            };
+            Ok(cast(expr, field.data_type().clone()).alias(field.name()))
+        })
+        .collect()
+}
@@ -0,0 +1,89 @@
+use std::{pin::Pin, sync::Arc, task::Poll};
+
+use datafusion::{
+    error::DataFusionError,
+    physical_plan::{ExecutionPlan, RecordBatchStream, SendableRecordBatchStream},
+};
+use futures::{Stream, StreamExt};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Admission queue that limits the number of queries executing at the same time.
+///
+/// Queries are admitted in the order they arrive. A query holds its slot until its result
+/// stream ends or is dropped. Unbounded queries, such as subscriptions to live topics, never
+/// finish and so are not limited by the queue.
+#[derive(Debug, Clone)]
+pub(crate) struct QueryQueue {
+    permits: Option<Arc<Semaphore>>,
+}
+
+impl QueryQueue {
+    pub fn new(max_concurrent: Option<usize>) -> Self {
+        Self {
+            permits: max_concurrent.map(|n| Arc::new(Semaphore::new(n.max(1)))),
+        }
+    }
+
+    /// Wait until the query `plan` can be admitted.
+    pub async fn admit(&self, plan: &Arc<dyn ExecutionPlan>) -> crate::Result<QueryPermit> {
+        let permits = match &self.permits {
+            Some(permits) if !is_unbounded(plan) => permits,
+            _ => return Ok(QueryPermit(None)),
+        };
+        let permit = permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| DataFusionError::Execution("query queue closed".to_string()))?;
+        Ok(QueryPermit(Some(permit)))
+    }
+}
+
+fn is_unbounded(plan: &Arc<dyn ExecutionPlan>) -> bool {
+    let children = plan.children().iter().map(is_unbounded).collect::<Vec<_>>();
+    plan.unbounded_output(&children).unwrap_or(true)
+}
+
+/// Slot in the [`QueryQueue`] held by an executing query.
+#[derive(Debug)]
+pub(crate) struct QueryPermit(Option<OwnedSemaphorePermit>);
+
+impl QueryPermit {
+    /// Hold the permit until `stream` ends or is dropped.
+    pub fn attach(self, stream: SendableRecordBatchStream) -> SendableRecordBatchStream {
+        if self.0.is_none() {
+            return stream;
+        }
+        Box::pin(PermitStream {
+            inner: stream,
+            permit: self.0,
+        })
+    }
+}
+
+struct PermitStream {
+    inner: SendableRecordBatchStream,
+    permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Stream for PermitStream {
+    type Item = <SendableRecordBatchStream as Stream>::Item;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let item = futures::ready!(self.inner.poll_next_unpin(cx));
+        if item.is_none() {
+            // Let the next query start while the consumer still holds the stream
+            self.permit = None;
+        }
+        Poll::Ready(item)
+    }
+}
+
+impl RecordBatchStream for PermitStream {
+    fn schema(&self) -> datafusion::arrow::datatypes::SchemaRef {
+        self.inner.schema()
+    }
+}
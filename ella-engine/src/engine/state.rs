@@ -1,22 +1,35 @@
-use std::{fmt::Debug, sync::Arc};
+use std::{
+    fmt::{Debug, Display},
+    sync::Arc,
+};
 
 use datafusion::{
+    datasource::{provider_as_source, TableProvider},
     error::DataFusionError,
     execution::{context::SessionState, runtime_env::RuntimeEnv},
+    logical_expr::{LogicalPlanBuilder, UNNAMED_TABLE},
+    physical_plan::{execute_stream, insert::DataSink},
     prelude::SessionConfig,
 };
+use futures::SinkExt;
 use object_store::ObjectStore;
 
+use super::{
+    audit::{self, AuditAction, AUDIT_TABLE, SYSTEM_SCHEMA},
+    ingest::{cast_to_schema, IngestOptions},
+    queue::QueryQueue,
+};
 use crate::{
     catalog::EllaCatalog,
     cluster::EllaCluster,
     codec::EllaExtensionCodec,
     config::EllaConfig,
     lazy::{Lazy, LocalBackend},
-    registry::{Id, SchemaRef, TableId, TableRef, TransactionLog},
+    registry::{Id, SchemaId, SchemaRef, TableId, TableRef, TransactionLog},
     schema::EllaSchema,
     table::{
         info::{TableInfo, TopicInfo, ViewInfo},
+        topic::{scan_parquet_files, ImportOptions},
         EllaTable, EllaTopic, EllaView,
     },
     Path, Plan,
@@ -30,6 +43,18 @@ pub struct EllaState {
     cluster: Arc<EllaCluster>,
     session: SessionState,
     config: EllaConfig,
+    principal: Option<String>,
+    // SQL text of the statement being executed, recorded in the audit log when known
+    statement: Option<String>,
+    options: SessionOptions,
+    queries: QueryQueue,
+}
+
+/// Settings that apply to a single session rather than the whole datastore.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SessionOptions {
+    /// Whether scans wait for rows that have been published but not yet buffered.
+    pub read_your_writes: bool,
 }
 
 impl Debug for EllaState {
@@ -38,6 +63,8 @@ impl Debug for EllaState {
             .field("log", &self.log)
             .field("cluster", &self.cluster)
             .field("config", &self.config)
+            .field("principal", &self.principal)
+            .field("options", &self.options)
             .finish_non_exhaustive()
     }
 }
@@ -53,7 +80,9 @@ impl EllaState {
 
         let config = log.load_config().await?;
         let cluster = Arc::new(EllaCluster::new(log.clone(), root.clone()));
-        let session = Self::make_session(cluster.clone(), env, &config);
+        let options = SessionOptions::default();
+        let session = Self::make_session(cluster.clone(), env, &config, &options);
+        let queries = QueryQueue::new(config.engine_config().max_concurrent_queries());
 
         let this = Self {
             root,
@@ -62,6 +91,10 @@ impl EllaState {
             cluster,
             session,
             config,
+            principal: None,
+            statement: None,
+            options,
+            queries,
         };
         this.restore().await?;
         Ok(this)
@@ -89,7 +122,9 @@ impl EllaState {
         };
 
         let cluster = Arc::new(EllaCluster::new(log.clone(), root.clone()));
-        let session = Self::make_session(cluster.clone(), env, &config);
+        let options = SessionOptions::default();
+        let session = Self::make_session(cluster.clone(), env, &config, &options);
+        let queries = QueryQueue::new(config.engine_config().max_concurrent_queries());
 
         let this = Self {
             root,
@@ -98,38 +133,85 @@ impl EllaState {
             cluster,
             session,
             config,
+            principal: None,
+            statement: None,
+            options,
+            queries,
         };
         this.restore().await?;
         Ok(this)
     }
 
-    pub fn with_config(&mut self, config: EllaConfig) {
+    pub fn with_config(&mut self, mut config: EllaConfig) {
+        // A read-only datastore can't be made writable by changing the config of a connection
+        if self.config.engine_config().read_only() {
+            config.engine_config = config.engine_config.into_builder().read_only(true).build();
+        }
         self.session = Self::make_session(
             self.cluster.clone(),
             self.session.runtime_env().clone(),
             &config,
+            &self.options,
         );
         self.config = config;
     }
 
+    /// Make scans in this session wait for rows that have been published but not yet buffered,
+    /// so that they see every row published before the query started.
+    pub fn with_read_your_writes(&mut self, enabled: bool) {
+        self.options.read_your_writes = enabled;
+        self.session = Self::make_session(
+            self.cluster.clone(),
+            self.session.runtime_env().clone(),
+            &self.config,
+            &self.options,
+        );
+    }
+
+    /// Set the principal recorded in the audit log for operations performed through this state.
+    pub fn with_principal(&mut self, principal: Option<String>) {
+        self.principal = principal;
+    }
+
+    /// Copy of this state that records `statement` in the audit log instead of a generated
+    /// description of each operation.
+    pub(crate) fn with_statement(&self, statement: String) -> Self {
+        let mut this = self.clone();
+        this.statement = Some(statement);
+        this
+    }
+
     fn make_session(
         cluster: Arc<EllaCluster>,
         runtime: Arc<RuntimeEnv>,
         config: &EllaConfig,
+        options: &SessionOptions,
     ) -> SessionState {
-        let config = SessionConfig::new()
+        let engine = config.engine_config();
+        let mut session = SessionConfig::new()
             .with_information_schema(true)
             .with_create_default_catalog_and_schema(false)
             .with_default_catalog_and_schema(
                 config.default_catalog().to_string(),
                 config.default_schema().to_string(),
             )
-            // TODO: support repartitioning
-            .with_round_robin_repartition(false)
+            .with_round_robin_repartition(engine.round_robin_repartition())
+            .with_repartition_joins(engine.repartition_joins())
+            .with_repartition_aggregations(engine.repartition_aggregations())
+            .set_bool(
+                "datafusion.execution.parquet.pushdown_filters",
+                engine.parquet_pushdown_filters(),
+            )
             // TODO: support batches
-            .with_coalesce_batches(false);
+            .with_coalesce_batches(false)
+            .with_batch_size(engine.batch_size())
+            .with_extension(Arc::new(config.clone()))
+            .with_extension(Arc::new(options.clone()));
+        if let Some(partitions) = engine.target_partitions() {
+            session = session.with_target_partitions(partitions);
+        }
 
-        SessionState::with_config_rt_and_catalog_list(config, runtime, cluster)
+        SessionState::with_config_rt_and_catalog_list(session, runtime, cluster)
     }
 
     async fn restore(&self) -> crate::Result<()> {
@@ -145,12 +227,26 @@ impl EllaState {
             .create_schema(self.config().default_schema().clone(), true)
             .await?;
 
+        catalog.create_schema(SYSTEM_SCHEMA, true).await?;
+        self.create_topic(self.audit_table(), audit::topic_info(), true, false)
+            .await?;
+
         Ok(())
     }
 
+    /// Return `EngineError::ReadOnly` if the datastore is read-only.
+    pub fn check_writable(&self) -> crate::Result<()> {
+        if self.config.engine_config().read_only() {
+            Err(crate::EngineError::ReadOnly.into())
+        } else {
+            Ok(())
+        }
+    }
+
     pub async fn query(&self, sql: impl AsRef<str>) -> crate::Result<Lazy> {
         let plan = self.session.create_logical_plan(sql.as_ref()).await?;
-        Ok(Lazy::new(Plan::from_plan(plan), Arc::new(self.backend())))
+        let plan = Plan::from_plan(plan).with_definition(sql.as_ref().to_string());
+        Ok(Lazy::new(plan, Arc::new(self.backend())))
     }
 
     pub async fn create_topic(
@@ -160,12 +256,7 @@ impl EllaState {
         if_not_exists: bool,
         or_replace: bool,
     ) -> crate::Result<Arc<EllaTopic>> {
-        let schema = self
-            .cluster()
-            .catalog(&id.catalog)
-            .ok_or_else(|| crate::EngineError::CatalogNotFound(id.catalog.to_string()))?
-            .schema(&id.schema)
-            .ok_or_else(|| crate::EngineError::SchemaNotFound(id.schema.to_string()))?;
+        let schema = self.require_schema(&id.catalog, &id.schema).await?;
 
         let table = self.table((&id).into());
         match (if_not_exists, or_replace, table) {
@@ -180,12 +271,17 @@ impl EllaState {
             },
             // table exists, replace table
             (false, true, Some(_)) => {
+                self.check_writable()?;
+                self.check_user_table(&id)?;
                 let topic = Arc::new(EllaTopic::new(id.clone(), info, self)?);
                 schema.drop_table(&id.table, true).await?;
 
                 schema
                     .register(id.table, Arc::new(topic.clone().into()))
                     .await?;
+                let statement = format!("CREATE OR REPLACE TOPIC {}", topic.table());
+                self.audit(AuditAction::Create, topic.table(), &statement)
+                    .await;
                 Ok(topic)
             }
             (true, true, Some(_)) => Err(DataFusionError::Execution(
@@ -194,10 +290,14 @@ impl EllaState {
             .into()),
             // create table
             (_, _, None) => {
+                self.check_writable()?;
                 let topic = Arc::new(EllaTopic::new(id.clone(), info, self)?);
                 schema
                     .register(id.table, Arc::new(topic.clone().into()))
                     .await?;
+                let statement = format!("CREATE TOPIC {}", topic.table());
+                self.audit(AuditAction::Create, topic.table(), &statement)
+                    .await;
                 Ok(topic)
             }
             // table exists
@@ -212,12 +312,12 @@ impl EllaState {
         if_not_exists: bool,
         or_replace: bool,
     ) -> crate::Result<Arc<EllaView>> {
-        let schema = self
-            .cluster()
-            .catalog(&id.catalog)
-            .ok_or_else(|| crate::EngineError::CatalogNotFound(id.catalog.to_string()))?
-            .schema(&id.schema)
-            .ok_or_else(|| crate::EngineError::SchemaNotFound(id.schema.to_string()))?;
+        let statement = match info.definition() {
+            Some(definition) => definition.to_string(),
+            None if or_replace => format!("CREATE OR REPLACE VIEW {}", id),
+            None => format!("CREATE VIEW {}", id),
+        };
+        let schema = self.require_schema(&id.catalog, &id.schema).await?;
 
         let table = self.table((&id).into());
         match (if_not_exists, or_replace, table) {
@@ -232,12 +332,16 @@ impl EllaState {
             },
             // table exists, replace table
             (false, true, Some(_)) => {
+                self.check_writable()?;
+                self.check_user_table(&id)?;
                 let view = Arc::new(EllaView::new(id.clone(), info, self, true)?);
                 schema.drop_table(&id.table, true).await?;
 
                 schema
                     .register(id.table, Arc::new(view.clone().into()))
                     .await?;
+                self.audit(AuditAction::Create, view.table(), &statement)
+                    .await;
                 Ok(view)
             }
             (true, true, Some(_)) => Err(DataFusionError::Execution(
@@ -246,10 +350,13 @@ impl EllaState {
             .into()),
             // create table
             (_, _, None) => {
+                self.check_writable()?;
                 let view = Arc::new(EllaView::new(id.clone(), info, self, true)?);
                 schema
                     .register(id.table, Arc::new(view.clone().into()))
                     .await?;
+                self.audit(AuditAction::Create, view.table(), &statement)
+                    .await;
                 Ok(view)
             }
             // table exists
@@ -278,6 +385,94 @@ impl EllaState {
         }
     }
 
+    /// Create a topic from the existing parquet files under `prefix`.
+    ///
+    /// The files are registered as external shards of the new topic in place, so they must share
+    /// the object store of the datastore. The topic never compacts or deletes external shards.
+    ///
+    /// If any file fails to register the topic is dropped again, leaving no partial import.
+    pub async fn import_parquet(
+        &self,
+        id: TableId<'static>,
+        prefix: Path,
+        options: ImportOptions,
+    ) -> crate::Result<Arc<EllaTopic>> {
+        self.check_writable()?;
+        let mut info = options.info().clone();
+        if info.temporary() {
+            return Err(crate::EngineError::InvalidImport(
+                "cannot import files into a temporary topic".to_string(),
+            )
+            .into());
+        }
+        let table_info = info.table_info(id.clone(), self)?;
+        let file_schema = table_info
+            .parquet_schema()
+            .cloned()
+            .unwrap_or_else(|| table_info.arrow_schema().clone());
+        let time = &info.index()[0].column;
+
+        let files = scan_parquet_files(self.store(), &prefix, &options, &file_schema, time).await?;
+        if files.is_empty() {
+            return Err(crate::EngineError::InvalidImport(format!(
+                "no parquet files found in {}",
+                prefix
+            ))
+            .into());
+        }
+
+        if options.is_append() {
+            info = info.with_location(prefix);
+        }
+        let topic = self.create_topic(id.clone(), info, false, false).await?;
+        let shards = topic
+            .shards()
+            .expect("non-temporary topics should have a shard set");
+        for file in files {
+            let res = shards
+                .import_shard(file_schema.clone(), file.path, file.rows)
+                .await;
+            if let Err(error) = res {
+                if let Err(error) = self.drop_table(id, true).await {
+                    tracing::error!(?error, "failed to drop partially imported topic");
+                }
+                return Err(error);
+            }
+        }
+        Ok(topic)
+    }
+
+    /// Read a CSV or JSON file and publish its rows to an existing topic.
+    ///
+    /// Columns are cast to the types of the topic's columns. Returns the number of rows written.
+    pub async fn ingest_file(
+        &self,
+        id: TableId<'static>,
+        path: &str,
+        options: IngestOptions,
+    ) -> crate::Result<u64> {
+        self.check_writable()?;
+        let topic = self
+            .table(id.clone())
+            .ok_or_else(|| crate::EngineError::TableNotFound(id.to_string()))?
+            .as_topic()
+            .ok_or_else(|| crate::EngineError::table_kind("topic", "view"))?;
+
+        let source = options.listing_table(path, &self.session).await?;
+        let exprs = cast_to_schema(&source.schema(), &topic.info().arrow_schema())?;
+        let plan =
+            LogicalPlanBuilder::scan(UNNAMED_TABLE, provider_as_source(Arc::new(source)), None)?
+                .project(exprs)?
+                .build()?;
+        let plan = self.session.create_physical_plan(&plan).await?;
+        let task = self.session.task_ctx();
+        let rows = topic
+            .publish()
+            .write_all(execute_stream(plan, task.clone())?, &task)
+            .await?;
+        Ok(rows)
+    }
+
     pub fn table(&self, table: TableId<'_>) -> Option<Arc<EllaTable>> {
         self.cluster
             .catalog(table.catalog)?
@@ -290,7 +485,21 @@ impl EllaState {
         catalog: impl Into<Id<'a>>,
         if_not_exists: bool,
     ) -> crate::Result<Arc<EllaCatalog>> {
-        self.cluster().create_catalog(catalog, if_not_exists).await
+        let catalog: Id<'a> = catalog.into();
+        if if_not_exists {
+            if let Some(catalog) = self.cluster().catalog(catalog.clone()) {
+                return Ok(catalog);
+            }
+        }
+        self.check_writable()?;
+        let catalog = self
+            .cluster()
+            .create_catalog(catalog, if_not_exists)
+            .await?;
+        let statement = format!("CREATE CATALOG {}", catalog.id());
+        self.audit(AuditAction::Create, catalog.id(), &statement)
+            .await;
+        Ok(catalog)
     }
 
     pub async fn create_schema<'a>(
@@ -304,13 +513,180 @@ impl EllaState {
             .cluster
             .catalog(&schema.catalog)
             .ok_or_else(|| crate::EngineError::CatalogNotFound(schema.catalog.to_string()))?;
-        catalog.create_schema(schema.schema, if_not_exists).await
+        if if_not_exists {
+            if let Some(schema) = catalog.schema(&schema.schema) {
+                return Ok(schema);
+            }
+        }
+        self.check_writable()?;
+        let schema = catalog.create_schema(schema.schema, if_not_exists).await?;
+        let statement = format!("CREATE SCHEMA {}", schema.id());
+        self.audit(AuditAction::Create, schema.id(), &statement)
+            .await;
+        Ok(schema)
+    }
+
+    /// Drop the table `id`, deleting the data of topics.
+    pub async fn drop_table(&self, id: TableId<'static>, if_exists: bool) -> crate::Result<()> {
+        self.check_writable()?;
+        self.check_user_table(&id)?;
+        let schema = self
+            .cluster
+            .catalog(&id.catalog)
+            .and_then(|catalog| catalog.schema(&id.schema));
+        match (if_exists, schema) {
+            (_, Some(schema)) => {
+                schema.drop_table(&id.table, if_exists).await?;
+                let statement = format!("DROP TABLE {}", id);
+                self.audit(AuditAction::Drop, &id, &statement).await;
+                Ok(())
+            }
+            (true, None) => Ok(()),
+            (false, None) => Err(crate::EngineError::TableNotFound(id.to_string()).into()),
+        }
+    }
+
+    /// Drop a schema. Unless `cascade` is set the schema must not contain any tables.
+    pub async fn drop_schema<'a>(
+        &self,
+        schema: impl Into<SchemaRef<'a>>,
+        if_exists: bool,
+        cascade: bool,
+    ) -> crate::Result<()> {
+        let schema: SchemaRef<'a> = schema.into();
+        let id = schema.resolve(self.default_catalog());
+        self.check_writable()?;
+        self.check_user_schema(&id)?;
+        match (if_exists, self.cluster.catalog(&id.catalog)) {
+            (_, Some(catalog)) => {
+                catalog.deregister(&id.schema, if_exists, cascade).await?;
+                let statement = if cascade {
+                    format!("DROP SCHEMA {} CASCADE", id)
+                } else {
+                    format!("DROP SCHEMA {}", id)
+                };
+                self.audit(AuditAction::Drop, &id, &statement).await;
+                Ok(())
+            }
+            (true, None) => Ok(()),
+            (false, None) => {
+                Err(crate::EngineError::CatalogNotFound(id.catalog.to_string()).into())
+            }
+        }
+    }
+
+    /// Drop a catalog. Unless `cascade` is set the catalog must not contain any schemas.
+    pub async fn drop_catalog<'a>(
+        &self,
+        catalog: impl Into<Id<'a>>,
+        if_exists: bool,
+        cascade: bool,
+    ) -> crate::Result<()> {
+        let catalog: Id<'a> = catalog.into();
+        if if_exists && self.cluster.catalog(catalog.clone()).is_none() {
+            return Ok(());
+        }
+        self.check_writable()?;
+        if &catalog == self.default_catalog() {
+            return Err(crate::EngineError::SystemTable(self.audit_table().to_string()).into());
+        }
+        self.cluster.deregister(catalog.clone(), cascade).await?;
+        let statement = if cascade {
+            format!("DROP CATALOG {} CASCADE", catalog)
+        } else {
+            format!("DROP CATALOG {}", catalog)
+        };
+        self.audit(AuditAction::Drop, &catalog, &statement).await;
+        Ok(())
+    }
+
+    /// Get a catalog, creating it if it doesn't exist and namespace auto-creation is enabled.
+    pub(crate) async fn require_catalog(&self, id: &Id<'_>) -> crate::Result<Arc<EllaCatalog>> {
+        match self.cluster.catalog(id.clone()) {
+            Some(catalog) => Ok(catalog),
+            None if self.config.engine_config().auto_create_namespaces() => {
+                self.create_catalog(id.clone(), true).await
+            }
+            None => Err(crate::EngineError::CatalogNotFound(id.to_string()).into()),
+        }
+    }
+
+    /// Get a schema, creating it and its catalog if they don't exist and namespace
+    /// auto-creation is enabled.
+    pub(crate) async fn require_schema(
+        &self,
+        catalog: &Id<'_>,
+        schema: &Id<'_>,
+    ) -> crate::Result<Arc<EllaSchema>> {
+        let catalog = self.require_catalog(catalog).await?;
+        match catalog.schema(schema.clone()) {
+            Some(schema) => Ok(schema),
+            None if self.config.engine_config().auto_create_namespaces() => {
+                let id = SchemaId {
+                    catalog: catalog.id().0.clone(),
+                    schema: schema.clone().into_owned(),
+                };
+                self.create_schema(id, true).await
+            }
+            None => Err(crate::EngineError::SchemaNotFound(schema.to_string()).into()),
+        }
     }
 
     pub fn resolve(&self, table: TableRef<'_>) -> TableId<'static> {
         table.resolve(self.default_catalog(), self.default_schema())
     }
 
+    /// ID of the audit log topic.
+    pub fn audit_table(&self) -> TableId<'static> {
+        TableId::from((self.default_catalog().clone(), SYSTEM_SCHEMA, AUDIT_TABLE))
+    }
+
+    /// Return `EngineError::SystemTable` if `id` is a table managed by the engine.
+    pub fn check_user_table(&self, id: &TableId<'_>) -> crate::Result<()> {
+        if id.catalog == *self.default_catalog() && id.schema == Id::from(SYSTEM_SCHEMA) {
+            Err(crate::EngineError::SystemTable(id.to_string()).into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Return `EngineError::SystemTable` if `id` is the schema containing the system tables.
+    pub fn check_user_schema(&self, id: &SchemaId<'_>) -> crate::Result<()> {
+        if id.catalog == *self.default_catalog() && id.schema == Id::from(SYSTEM_SCHEMA) {
+            Err(crate::EngineError::SystemTable(id.to_string()).into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Append an entry to the audit log and wait until it has been written to a shard.
+    ///
+    /// `statement` describes the operation if the SQL it was requested with isn't known.
+    /// The operation has already happened when it's audited, so failures are logged rather than
+    /// returned.
+    pub(crate) async fn audit(&self, action: AuditAction, object: impl Display, statement: &str) {
+        let topic = match self.table(self.audit_table()).and_then(|t| t.as_topic()) {
+            Some(topic) => topic,
+            None => return,
+        };
+        let object = object.to_string();
+        let res: crate::Result<()> = async {
+            let batch = audit::record(
+                topic.info(),
+                self.principal(),
+                action,
+                &object,
+                self.statement.as_deref().unwrap_or(statement),
+            )?;
+            topic.publish().send(batch).await?;
+            topic.flush().await
+        }
+        .await;
+        if let Err(error) = res {
+            tracing::error!(?error, %action, %object, "failed to write audit log entry");
+        }
+    }
+
     pub fn log(&self) -> &Arc<TransactionLog> {
         &self.log
     }
@@ -335,6 +711,18 @@ impl EllaState {
         &self.config
     }
 
+    pub fn principal(&self) -> Option<&str> {
+        self.principal.as_deref()
+    }
+
+    pub fn read_your_writes(&self) -> bool {
+        self.options.read_your_writes
+    }
+
+    pub(crate) fn queries(&self) -> &QueryQueue {
+        &self.queries
+    }
+
     pub fn default_catalog(&self) -> &Id<'static> {
         self.config.default_catalog()
     }
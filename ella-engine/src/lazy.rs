@@ -1,17 +1,26 @@
 mod backend;
+mod cache;
+#[cfg(feature = "polars")]
+mod polars;
+mod properties;
 mod view;
 
 use crate::{registry::TableRef, Plan};
 
+pub use self::properties::ResultProperties;
 pub use self::view::LazyToView;
 pub use backend::LazyBackend;
 pub(crate) use backend::LocalBackend;
+use cache::CachedBackend;
 
 use std::{fmt::Debug, marker::PhantomData, pin::Pin, sync::Arc, task::Poll};
 
 use arrow_schema::SchemaRef;
 use datafusion::{
-    logical_expr::LogicalPlanBuilder, physical_plan::SendableRecordBatchStream, prelude::Expr,
+    datasource::{provider_as_source, MemTable},
+    logical_expr::{LogicalPlanBuilder, UNNAMED_TABLE},
+    physical_plan::SendableRecordBatchStream,
+    prelude::Expr,
 };
 use ella_common::{
     row::{RowFormat, RowStream},
@@ -43,6 +52,21 @@ impl Lazy {
         Ok(self.stream().await?.rows())
     }
 
+    /// Execute the query once and return a `Lazy` that reads the materialized results.
+    ///
+    /// The results are held in memory until every `Lazy` derived from the cache is dropped.
+    pub async fn cache(&self) -> crate::Result<Lazy> {
+        let stream = self.backend.stream(&self.plan).await?;
+        let schema = stream.schema();
+        let batches = stream.try_collect::<Vec<_>>().await?;
+        let table = MemTable::try_new(schema, vec![batches])?;
+        let plan =
+            LogicalPlanBuilder::scan(UNNAMED_TABLE, provider_as_source(Arc::new(table)), None)?
+                .build()?;
+        let backend = CachedBackend::new(self.backend.state().cloned());
+        Ok(Lazy::new(Plan::from_plan(plan), Arc::new(backend)))
+    }
+
     pub fn limit(mut self, limit: usize) -> crate::Result<Self> {
         self.plan = self.plan.try_map(|plan| {
             LogicalPlanBuilder::from(plan)
@@ -59,6 +83,23 @@ impl Lazy {
         Ok(self)
     }
 
+    /// Append the results of `other` to the results of this query.
+    ///
+    /// Duplicate rows are removed unless `all` is true. Both queries must have the same number
+    /// of columns with compatible types and should come from the same datastore.
+    pub fn union(mut self, other: Lazy, all: bool) -> crate::Result<Self> {
+        let other = other.plan.stub().clone();
+        self.plan = self.plan.try_map(|plan| {
+            let builder = LogicalPlanBuilder::from(plan);
+            if all {
+                builder.union(other)?.build()
+            } else {
+                builder.union_distinct(other)?.build()
+            }
+        })?;
+        Ok(self)
+    }
+
     pub fn col<T, S>(&self, col: &str) -> crate::Result<Column<T, S>>
     where
         T: TensorValue,
@@ -99,6 +140,23 @@ impl Lazy {
     pub fn plan(&self) -> &Plan {
         &self.plan
     }
+
+    /// Properties of the query's results, derived from its plan without executing it.
+    pub fn properties(&self) -> ResultProperties {
+        ResultProperties::new(self.plan.stub())
+    }
+
+    /// Execute the query and collect the results into a Polars `DataFrame`.
+    ///
+    /// Columns are shared with Polars without copying where possible. Returns an error if the
+    /// result contains tensor columns, which have no Polars equivalent.
+    #[cfg(feature = "polars")]
+    pub async fn collect_polars(&self) -> crate::Result<::polars::frame::DataFrame> {
+        let stream = self.clone().stream().await?.into_inner();
+        let schema = stream.schema();
+        let batches = stream.try_collect::<Vec<_>>().await?;
+        polars::to_polars(&schema, &batches)
+    }
 }
 
 pub struct LazyStream(SendableRecordBatchStream);
@@ -1,22 +1,31 @@
-use std::{fmt::Debug, pin::Pin, sync::Arc};
+use std::{
+    fmt::Debug,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
 use arrow_schema::Schema;
 use datafusion::{
     arrow::compute::concat_batches,
     datasource::provider_as_source,
-    logical_expr::{DdlStatement, LogicalPlan, LogicalPlanBuilder},
+    error::DataFusionError,
+    logical_expr::{DdlStatement, LogicalPlan, LogicalPlanBuilder, WriteOp},
     physical_plan::{
-        execute_stream, stream::RecordBatchStreamAdapter, RecordBatchStream,
+        execute_stream, stream::RecordBatchStreamAdapter, ExecutionPlan, RecordBatchStream,
         SendableRecordBatchStream,
     },
 };
 use ella_tensor::DataFrame;
-use futures::TryStreamExt;
+use futures::{StreamExt, TryStreamExt};
 
 use crate::{
-    engine::EllaState,
-    registry::{SchemaId, TableRef},
+    engine::{AuditAction, EllaState},
+    registry::{SchemaId, TableId, TableRef},
     table::info::{ViewBuilder, ViewInfo},
+    util::{best_effort, split_batches},
     Plan,
 };
 
@@ -32,6 +41,11 @@ pub trait LazyBackend: Debug + Send + Sync {
         or_replace: bool,
     ) -> crate::Result<Plan>;
 
+    /// The datastore that plans run by this backend are executed against, if it is local.
+    fn state(&self) -> Option<&EllaState> {
+        None
+    }
+
     async fn execute(&self, plan: &Plan) -> crate::Result<DataFrame> {
         let stream = self.stream(plan).await?;
         let schema = stream.schema();
@@ -50,6 +64,88 @@ impl LocalBackend {
     pub(crate) fn new(state: EllaState) -> Self {
         Self { state }
     }
+
+    /// Number of partitions that the results of `plan` are produced in.
+    ///
+    /// Statements other than queries always have a single partition.
+    pub async fn output_partitions(&self, plan: &Plan) -> crate::Result<usize> {
+        let plan = plan.resolve(&self.state)?;
+        if !is_query(&plan) {
+            return Ok(1);
+        }
+        let plan = self.state.session().create_physical_plan(&plan).await?;
+        Ok(plan.output_partitioning().partition_count())
+    }
+
+    /// Execute a single output partition of the query `plan`.
+    pub async fn stream_partition(
+        &self,
+        plan: &Plan,
+        partition: usize,
+    ) -> crate::Result<SendableRecordBatchStream> {
+        let plan = plan.resolve(&self.state)?;
+        if !is_query(&plan) {
+            return Err(DataFusionError::Plan(
+                "only queries can be executed by partition".to_string(),
+            )
+            .into());
+        }
+        let plan = self.state.session().create_physical_plan(&plan).await?;
+        let permit = self.state.queries().admit(&plan).await?;
+        let partitions = plan.output_partitioning().partition_count();
+        if partition >= partitions {
+            return Err(DataFusionError::Plan(format!(
+                "partition {} out of range for query with {} partitions",
+                partition, partitions
+            ))
+            .into());
+        }
+
+        let config = self.state.config().engine_config();
+        let mut stream = plan.execute(partition, self.state.session().task_ctx())?;
+        if config.best_effort_reads() {
+            stream = best_effort(stream);
+        }
+        Ok(permit.attach(split_batches(stream, config.batch_size())))
+    }
+}
+
+fn is_query(plan: &LogicalPlan) -> bool {
+    !matches!(
+        plan,
+        LogicalPlan::Ddl(_)
+            | LogicalPlan::Dml(_)
+            | LogicalPlan::Statement(_)
+            | LogicalPlan::DescribeTable(_)
+    )
+}
+
+/// Record `action` on `id` in the audit log once `stream` has been read to the end without errors.
+fn audit_on_completion(
+    state: EllaState,
+    stream: SendableRecordBatchStream,
+    action: AuditAction,
+    id: TableId<'static>,
+    statement: String,
+) -> SendableRecordBatchStream {
+    let schema = stream.schema();
+    let failed = Arc::new(AtomicBool::new(false));
+    let stream = stream.inspect({
+        let failed = failed.clone();
+        move |res| {
+            if res.is_err() {
+                failed.store(true, Ordering::Relaxed);
+            }
+        }
+    });
+    let audit = futures::stream::once(async move {
+        if !failed.load(Ordering::Relaxed) {
+            state.audit(action, &id, &statement).await;
+        }
+        None
+    });
+    let stream = stream.chain(audit.filter_map(futures::future::ready));
+    Box::pin(RecordBatchStreamAdapter::new(schema, stream))
 }
 
 fn empty() -> Pin<Box<dyn RecordBatchStream + Send + 'static>> {
@@ -62,18 +158,26 @@ fn empty() -> Pin<Box<dyn RecordBatchStream + Send + 'static>> {
 #[async_trait::async_trait]
 impl LazyBackend for LocalBackend {
     async fn stream(&self, plan: &Plan) -> crate::Result<SendableRecordBatchStream> {
-        let plan = plan.resolve(&self.state)?;
+        // Audit log entries record the SQL the plan was created from when it's known
+        let state = match plan.definition() {
+            Some(statement) => self.state.with_statement(statement),
+            None => self.state.clone(),
+        };
+        let plan = plan.resolve(&state)?;
+        if matches!(plan, LogicalPlan::Ddl(_) | LogicalPlan::Dml(_)) {
+            state.check_writable()?;
+        }
         match plan {
             LogicalPlan::Ddl(ddl) => match ddl {
                 DdlStatement::CreateView(cmd) => {
                     let name = TableRef::from(cmd.name.clone());
-                    let id = self.state.resolve(name.clone());
+                    let id = state.resolve(name.clone());
                     let plan = (*cmd.input).clone();
                     let mut info = ViewBuilder::new(Plan::from_plan(plan));
                     if let Some(definition) = cmd.definition.as_deref() {
                         info = info.definition(definition);
                     }
-                    self.state
+                    state
                         .create_view(id, info.build(), false, cmd.or_replace)
                         .await?;
                     Ok(empty())
@@ -82,19 +186,12 @@ impl LazyBackend for LocalBackend {
                     todo!()
                 }
                 DdlStatement::CreateCatalogSchema(cmd) => {
-                    let id =
-                        SchemaId::parse(&cmd.schema_name, self.state.default_catalog().clone());
-                    self.state
-                        .cluster()
-                        .catalog(id.catalog.as_ref())
-                        .ok_or_else(|| crate::EngineError::CatalogNotFound(id.catalog.to_string()))?
-                        .create_schema(id.schema, cmd.if_not_exists)
-                        .await?;
+                    let id = SchemaId::parse(&cmd.schema_name, state.default_catalog().clone());
+                    state.create_schema(id, cmd.if_not_exists).await?;
                     Ok(empty())
                 }
                 DdlStatement::CreateCatalog(cmd) => {
-                    self.state
-                        .cluster()
+                    state
                         .create_catalog(&cmd.catalog_name, cmd.if_not_exists)
                         .await?;
                     Ok(empty())
@@ -102,7 +199,7 @@ impl LazyBackend for LocalBackend {
                 DdlStatement::CreateExternalTable(_cmd) => unimplemented!(),
                 DdlStatement::DropTable(cmd) => {
                     let name = TableRef::from(cmd.name.clone());
-                    let id = self.state.resolve(name.clone());
+                    let id = state.resolve(name.clone());
 
                     let schema = self
                         .state
@@ -111,7 +208,10 @@ impl LazyBackend for LocalBackend {
                         .and_then(|catalog| catalog.schema(&id.schema));
                     match (cmd.if_exists, schema) {
                         (_, Some(schema)) => {
+                            state.check_user_table(&id)?;
                             schema.drop_topic(&id.table, cmd.if_exists).await?;
+                            let statement = format!("DROP TABLE {}", id);
+                            state.audit(AuditAction::Drop, &id, &statement).await;
                             Ok(empty())
                         }
                         (true, None) => Ok(empty()),
@@ -122,7 +222,7 @@ impl LazyBackend for LocalBackend {
                 }
                 DdlStatement::DropView(cmd) => {
                     let name = TableRef::from(cmd.name.clone());
-                    let id = self.state.resolve(name.clone());
+                    let id = state.resolve(name.clone());
 
                     let schema = self
                         .state
@@ -131,7 +231,10 @@ impl LazyBackend for LocalBackend {
                         .and_then(|catalog| catalog.schema(&id.schema));
                     match (cmd.if_exists, schema) {
                         (_, Some(schema)) => {
+                            state.check_user_table(&id)?;
                             schema.drop_view(&id.table, cmd.if_exists).await?;
+                            let statement = format!("DROP VIEW {}", id);
+                            state.audit(AuditAction::Drop, &id, &statement).await;
                             Ok(empty())
                         }
                         (true, None) => Ok(empty()),
@@ -141,15 +244,21 @@ impl LazyBackend for LocalBackend {
                     }
                 }
                 DdlStatement::DropCatalogSchema(cmd) => {
-                    let id =
-                        SchemaId::resolve(cmd.name.clone(), self.state.default_catalog().clone());
+                    let id = SchemaId::resolve(cmd.name.clone(), state.default_catalog().clone());
 
-                    let catalog = self.state.cluster().catalog(&id.catalog);
+                    let catalog = state.cluster().catalog(&id.catalog);
                     match (cmd.if_exists, catalog) {
                         (_, Some(catalog)) => {
+                            state.check_user_schema(&id)?;
                             catalog
                                 .deregister(&id.schema, cmd.if_exists, cmd.cascade)
                                 .await?;
+                            let statement = if cmd.cascade {
+                                format!("DROP SCHEMA {} CASCADE", id)
+                            } else {
+                                format!("DROP SCHEMA {}", id)
+                            };
+                            state.audit(AuditAction::Drop, &id, &statement).await;
                             Ok(empty())
                         }
                         (true, None) => Ok(empty()),
@@ -162,9 +271,37 @@ impl LazyBackend for LocalBackend {
             LogicalPlan::Statement(_stmt) => unimplemented!(),
             LogicalPlan::DescribeTable(_desc) => todo!(),
             plan => {
-                let plan = self.state.session().create_physical_plan(&plan).await?;
+                // Destructive DML is recorded in the audit log
+                let audit = match &plan {
+                    LogicalPlan::Dml(dml) => {
+                        let id = state.resolve(TableRef::from(dml.table_name.clone()));
+                        state.check_user_table(&id)?;
+                        match dml.op {
+                            WriteOp::Delete => {
+                                Some((AuditAction::Delete, format!("DELETE FROM {}", id), id))
+                            }
+                            WriteOp::Update => {
+                                Some((AuditAction::Update, format!("UPDATE {}", id), id))
+                            }
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                };
+                let plan = state.session().create_physical_plan(&plan).await?;
+                let permit = state.queries().admit(&plan).await?;
+                let config = state.config().engine_config();
+                let mut stream = execute_stream(plan, state.session().task_ctx())?;
+                if config.best_effort_reads() {
+                    stream = best_effort(stream);
+                }
+                let mut stream = permit.attach(split_batches(stream, config.batch_size()));
 
-                Ok(execute_stream(plan, self.state.session().task_ctx())?)
+                // Only statements that ran to completion are recorded
+                if let Some((action, statement, id)) = audit {
+                    stream = audit_on_completion(state, stream, action, id, statement);
+                }
+                Ok(stream)
             }
         }
     }
@@ -185,4 +322,8 @@ impl LazyBackend for LocalBackend {
             .build()?;
         Ok(Plan::from_plan(plan))
     }
+
+    fn state(&self) -> Option<&EllaState> {
+        Some(&self.state)
+    }
 }
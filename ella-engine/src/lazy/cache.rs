@@ -0,0 +1,60 @@
+use datafusion::{
+    physical_plan::{execute_stream, SendableRecordBatchStream},
+    prelude::SessionContext,
+};
+
+use crate::{engine::EllaState, registry::TableRef, table::info::ViewInfo, Plan};
+
+use super::LazyBackend;
+
+/// Backend for queries over results materialized by [`Lazy::cache`](super::Lazy::cache).
+///
+/// Cached plans scan in-memory tables, so they are executed in the current process
+/// regardless of where the original query ran. Caches of local queries are planned in the
+/// datastore's session so that its configuration applies; other caches use a default session.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CachedBackend {
+    state: Option<EllaState>,
+}
+
+impl CachedBackend {
+    pub(crate) fn new(state: Option<EllaState>) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait::async_trait]
+impl LazyBackend for CachedBackend {
+    async fn stream(&self, plan: &Plan) -> crate::Result<SendableRecordBatchStream> {
+        let plan = plan.as_resolved().ok_or_else(|| {
+            crate::Error::Unimplemented("executing an unresolved plan over a cache".to_string())
+        })?;
+        match &self.state {
+            Some(state) => {
+                let plan = state.session().create_physical_plan(&plan).await?;
+                Ok(execute_stream(plan, state.session().task_ctx())?)
+            }
+            None => {
+                let ctx = SessionContext::new();
+                let plan = ctx.state().create_physical_plan(&plan).await?;
+                Ok(execute_stream(plan, ctx.task_ctx())?)
+            }
+        }
+    }
+
+    fn state(&self) -> Option<&EllaState> {
+        self.state.as_ref()
+    }
+
+    async fn create_view(
+        &self,
+        _table: TableRef<'static>,
+        _info: ViewInfo,
+        _if_not_exists: bool,
+        _or_replace: bool,
+    ) -> crate::Result<Plan> {
+        Err(crate::Error::Unimplemented(
+            "creating a view from a cached result".to_string(),
+        ))
+    }
+}
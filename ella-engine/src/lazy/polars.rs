@@ -0,0 +1,68 @@
+use arrow::ffi::{export_array_into_raw, FFI_ArrowArray, FFI_ArrowSchema};
+use arrow_schema::{DataType, SchemaRef};
+use datafusion::{
+    arrow::{
+        array::{make_array, new_empty_array},
+        record_batch::RecordBatch,
+    },
+    error::DataFusionError,
+};
+use polars::{
+    export::arrow::{array::Array, ffi},
+    frame::DataFrame,
+    series::Series,
+};
+
+/// Convert record batches with the given schema into a Polars `DataFrame`.
+pub(crate) fn to_polars(schema: &SchemaRef, batches: &[RecordBatch]) -> crate::Result<DataFrame> {
+    for field in schema.fields() {
+        if matches!(
+            field.data_type(),
+            DataType::FixedSizeList(_, _) | DataType::List(_) | DataType::LargeList(_)
+        ) {
+            return Err(crate::Error::Unimplemented(format!(
+                "column {} is a tensor column and cannot be converted to a Polars series; \
+                 select its elements as scalar columns instead",
+                field.name()
+            )));
+        }
+    }
+
+    let columns = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let mut chunks = batches
+                .iter()
+                .map(|batch| export_array(batch.column(i).as_ref()))
+                .collect::<crate::Result<Vec<_>>>()?;
+            if chunks.is_empty() {
+                chunks.push(export_array(&new_empty_array(field.data_type()))?);
+            }
+            Series::try_from((field.name().as_str(), chunks)).map_err(polars_err)
+        })
+        .collect::<crate::Result<Vec<_>>>()?;
+    DataFrame::new(columns).map_err(polars_err)
+}
+
+/// Share an arrow-rs array with Polars through the Arrow C data interface.
+fn export_array(array: &dyn datafusion::arrow::array::Array) -> crate::Result<Box<dyn Array>> {
+    let mut c_array = ffi::ArrowArray::empty();
+    let mut c_schema = ffi::ArrowSchema::empty();
+    // SAFETY: arrow-rs writes the exported array into the empty C structs allocated by Polars,
+    // which then takes ownership of them and calls their release callbacks.
+    unsafe {
+        export_array_into_raw(
+            make_array(array.to_data()),
+            &mut c_array as *mut ffi::ArrowArray as *mut FFI_ArrowArray,
+            &mut c_schema as *mut ffi::ArrowSchema as *mut FFI_ArrowSchema,
+        )?;
+        let field = ffi::import_field_from_c(&c_schema).map_err(polars_err)?;
+        ffi::import_array_from_c(c_array, field.data_type).map_err(polars_err)
+    }
+}
+
+fn polars_err<E: std::error::Error + Send + Sync + 'static>(error: E) -> crate::Error {
+    DataFusionError::External(Box::new(error)).into()
+}
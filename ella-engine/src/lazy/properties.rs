@@ -0,0 +1,105 @@
+use arrow_schema::SchemaRef;
+use datafusion::logical_expr::{Expr, LogicalPlan};
+
+use crate::table::TableIndex;
+
+/// Properties of a query's results that are known before the query is executed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultProperties {
+    schema: SchemaRef,
+    ordering: Vec<TableIndex>,
+    distinct: bool,
+    max_rows: Option<usize>,
+}
+
+impl ResultProperties {
+    pub(crate) fn new(plan: &LogicalPlan) -> Self {
+        let schema: SchemaRef = (**plan.schema()).clone().into();
+        let mut ordering = None;
+        let mut distinct = false;
+        let mut max_rows: Option<usize> = None;
+        // Columns dropped by a projection can make distinct rows equal
+        let mut projected = false;
+
+        let mut node = plan;
+        loop {
+            node = match node {
+                LogicalPlan::Limit(limit) => {
+                    if let Some(fetch) = limit.fetch {
+                        max_rows = Some(max_rows.map_or(fetch, |rows| rows.min(fetch)));
+                    }
+                    &limit.input
+                }
+                LogicalPlan::Sort(sort) => {
+                    if let Some(fetch) = sort.fetch {
+                        max_rows = Some(max_rows.map_or(fetch, |rows| rows.min(fetch)));
+                    }
+                    if ordering.is_none() {
+                        ordering = Some(sort_columns(&sort.expr, &schema));
+                    }
+                    &sort.input
+                }
+                LogicalPlan::Distinct(inner) => {
+                    distinct |= !projected;
+                    &inner.input
+                }
+                LogicalPlan::Projection(projection) => {
+                    projected = true;
+                    &projection.input
+                }
+                LogicalPlan::Filter(filter) => &filter.input,
+                LogicalPlan::SubqueryAlias(alias) => &alias.input,
+                _ => break,
+            };
+        }
+
+        Self {
+            schema,
+            ordering: ordering.unwrap_or_default(),
+            distinct,
+            max_rows,
+        }
+    }
+
+    pub fn arrow_schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    /// Columns that the results are sorted by, outermost first.
+    pub fn ordering(&self) -> &[TableIndex] {
+        &self.ordering
+    }
+
+    pub fn is_ordered(&self) -> bool {
+        !self.ordering.is_empty()
+    }
+
+    /// Whether every row of the results is unique.
+    pub fn is_distinct(&self) -> bool {
+        self.distinct
+    }
+
+    /// Upper bound on the number of rows returned, if the query has a limit.
+    pub fn max_rows(&self) -> Option<usize> {
+        self.max_rows
+    }
+}
+
+/// Leading sort keys that are plain columns of the output schema.
+fn sort_columns(exprs: &[Expr], schema: &SchemaRef) -> Vec<TableIndex> {
+    exprs
+        .iter()
+        .map_while(|expr| match expr {
+            Expr::Sort(sort) => match sort.expr.as_ref() {
+                Expr::Column(col) if schema.field_with_name(&col.name).is_ok() => {
+                    Some(TableIndex {
+                        column: col.name.clone(),
+                        ascending: sort.asc,
+                    })
+                }
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
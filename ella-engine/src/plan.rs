@@ -15,6 +15,7 @@ pub struct Plan {
 }
 
 impl Plan {
+    /// SQL text the plan was created from, if any.
     pub fn definition(&self) -> Option<String> {
         self.definition.clone()
     }
@@ -72,10 +73,13 @@ impl Plan {
         self.inner.stub().schema()
     }
 
+    /// Transform the plan. The result no longer has a definition since it doesn't match the
+    /// original SQL.
     pub fn map<F>(mut self, f: F) -> Self
     where
         F: FnOnce(LogicalPlan) -> LogicalPlan,
     {
+        self.definition = None;
         self.inner = match self.inner {
             PlanInner::Resolved(plan) => PlanInner::Resolved(f(plan)),
             PlanInner::Stub(plan) => PlanInner::Stub(f(plan)),
@@ -88,6 +92,7 @@ impl Plan {
         F: FnOnce(LogicalPlan) -> Result<LogicalPlan, E>,
         crate::Error: From<E>,
     {
+        self.definition = None;
         self.inner = match self.inner {
             PlanInner::Resolved(plan) => PlanInner::Resolved(f(plan)?),
             PlanInner::Stub(plan) => PlanInner::Stub(f(plan)?),
@@ -4,4 +4,4 @@ mod transaction_log;
 pub mod transactions;
 
 pub use id::*;
-pub use transaction_log::TransactionLog;
+pub use transaction_log::{TransactionLog, VersionInfo};
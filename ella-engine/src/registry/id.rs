@@ -6,7 +6,7 @@ use std::{
 
 use datafusion::{
     common::SchemaReference,
-    sql::{ResolvedTableReference, TableReference},
+    sql::{sqlparser::ast::ObjectName, ResolvedTableReference, TableReference},
 };
 use rand::Fill;
 use uuid::{NoContext, Timestamp, Uuid};
@@ -208,6 +208,35 @@ impl<'a> From<TableReference<'a>> for TableRef<'a> {
     }
 }
 
+/// Unquoted identifiers are normalized to lowercase, as when SQL statements are planned.
+impl TryFrom<ObjectName> for TableRef<'static> {
+    type Error = crate::Error;
+
+    fn try_from(value: ObjectName) -> Result<Self, Self::Error> {
+        if value.0.is_empty() || value.0.len() > 3 {
+            return Err(crate::EngineError::InvalidTableName(value.to_string()).into());
+        }
+        let mut idents = value
+            .0
+            .into_iter()
+            .rev()
+            .map(|ident| match ident.quote_style {
+                Some(_) => Id::from(ident.value),
+                None => Id::from(ident.value.to_lowercase()),
+            });
+        let table = idents
+            .next()
+            .expect("object name should have been checked for a table");
+        let schema = idents.next();
+        let catalog = idents.next();
+        Ok(TableRef {
+            table,
+            schema,
+            catalog,
+        })
+    }
+}
+
 impl<'a> From<TableRef<'a>> for TableReference<'a> {
     fn from(value: TableRef<'a>) -> Self {
         match (value.catalog, value.schema) {
@@ -396,6 +425,11 @@ pub struct TableId<'a> {
 }
 
 impl<'a> TableId<'a> {
+    /// Format the table ID for use in a SQL statement, quoting identifiers where needed.
+    pub fn to_quoted_string(&self) -> String {
+        TableReference::from(self.clone()).to_quoted_string()
+    }
+
     pub fn into_owned(self) -> TableId<'static> {
         TableId {
             catalog: self.catalog.into_owned(),
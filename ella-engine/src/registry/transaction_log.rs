@@ -1,10 +1,21 @@
 use futures::TryStreamExt;
-use object_store::ObjectStore;
+use object_store::{path::Path as ObjPath, ObjectStore};
 
-use crate::{config::EllaConfig, Path};
-use std::sync::Arc;
+use crate::{config::EllaConfig, table::topic::ShardInfo, Path};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
-use super::{snapshot::Snapshot, transactions::Transaction};
+use super::{snapshot::Snapshot, transactions::Transaction, TableId, TransactionId};
+
+/// A version of a topic's manifest, identified by the transaction that produced it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionInfo {
+    pub version: TransactionId,
+    pub shards: usize,
+    pub rows: usize,
+}
 
 #[derive(Debug)]
 pub struct TransactionLog {
@@ -79,6 +90,168 @@ impl TransactionLog {
         Ok(snapshot)
     }
 
+    /// List the versions of `table`'s manifest retained by the log, oldest first.
+    ///
+    /// Each version corresponds to a change in the set of readable shards. Versions older than
+    /// the newest snapshot are only available at the granularity of the stored snapshots.
+    pub async fn versions(&self, table: &TableId<'_>) -> crate::Result<Vec<VersionInfo>> {
+        Ok(self
+            .manifest_history(table)
+            .await?
+            .into_iter()
+            .map(|(version, shards)| VersionInfo {
+                version,
+                shards: shards.len(),
+                rows: shards.iter().filter_map(|s| s.rows).sum(),
+            })
+            .collect())
+    }
+
+    /// Get the readable shards of `table` at `version`.
+    pub async fn manifest(
+        &self,
+        table: &TableId<'_>,
+        version: TransactionId,
+    ) -> crate::Result<Vec<ShardInfo>> {
+        self.manifest_history(table)
+            .await?
+            .into_iter()
+            .find(|(v, _)| *v == version)
+            .map(|(_, shards)| shards)
+            .ok_or_else(|| crate::EngineError::VersionNotFound(version.to_string()).into())
+    }
+
+    /// Get the paths of the shards of `table` that are readable in any retained version.
+    pub(crate) async fn retained_paths(
+        &self,
+        table: &TableId<'_>,
+    ) -> crate::Result<HashSet<ObjPath>> {
+        Ok(self
+            .manifest_history(table)
+            .await?
+            .into_iter()
+            .flat_map(|(_, shards)| shards)
+            .map(|shard| shard.path.as_path())
+            .collect())
+    }
+
+    /// Get the readable shards of `table` that were added after `version`.
+    ///
+    /// Returns an error if shards that were readable at `version` have since been compacted
+    /// together with newer shards, as the new rows can no longer be told apart.
+    pub async fn shards_since(
+        &self,
+        table: &TableId<'_>,
+        version: TransactionId,
+    ) -> crate::Result<Vec<ShardInfo>> {
+        let history = self.manifest_history(table).await?;
+        let base = history
+            .iter()
+            .find(|(v, _)| *v == version)
+            .map(|(_, shards)| shards.iter().map(|s| s.id).collect::<HashSet<_>>())
+            .ok_or_else(|| crate::EngineError::VersionNotFound(version.to_string()))?;
+        let latest = history
+            .into_iter()
+            .last()
+            .map(|(_, shards)| shards)
+            .unwrap_or_default();
+
+        let mut sources = HashMap::new();
+        for tsn in self.load_transactions().await? {
+            if let Transaction::CompactShards(t) = tsn {
+                if &t.table == table {
+                    sources.insert(t.dst, t.src);
+                }
+            }
+        }
+        let mut out = Vec::new();
+        for shard in latest {
+            if base.contains(&shard.id) {
+                continue;
+            }
+            let mut lineage = vec![shard.id];
+            while let Some(id) = lineage.pop() {
+                if base.contains(&id) {
+                    return Err(crate::EngineError::VersionNotFound(format!(
+                        "{} (shards have since been compacted)",
+                        version
+                    ))
+                    .into());
+                }
+                lineage.extend(sources.get(&id).into_iter().flatten().copied());
+            }
+            out.push(shard);
+        }
+        Ok(out)
+    }
+
+    async fn manifest_history(
+        &self,
+        table: &TableId<'_>,
+    ) -> crate::Result<Vec<(TransactionId, Vec<ShardInfo>)>> {
+        let mut current = self
+            .load_newest_snapshot()
+            .await?
+            .ok_or_else(|| crate::EngineError::InvalidDatastore(self.path.to_string()))?;
+        let mut history: Vec<(TransactionId, Vec<ShardInfo>)> = Vec::new();
+        let mut push = |version, shards: Vec<ShardInfo>, coalesce: bool| match history.last_mut() {
+            Some((_, last)) if *last == shards => {}
+            // Intermediate states of a compaction are folded into a single version
+            Some(last) if coalesce => *last = (version, shards),
+            _ => history.push((version, shards)),
+        };
+
+        for snapshot in self.load_snapshots().await? {
+            if let (Some(version), Some(shards)) =
+                (snapshot.last_transaction, readable_shards(&snapshot, table))
+            {
+                push(version, shards, false);
+            }
+        }
+
+        let mut compacting = HashSet::new();
+        let mut in_compaction = false;
+        for tsn in self.load_transactions().await? {
+            let version = tsn.uuid();
+            match &tsn {
+                Transaction::CompactShards(t) if &t.table == table => {
+                    compacting.insert(t.dst);
+                }
+                Transaction::CloseShard(t) if &t.table == table => {
+                    in_compaction = compacting.remove(&t.shard);
+                }
+                Transaction::DeleteShard(t) if &t.table == table => {}
+                // Transactions on other tables may be committed while the compaction finishes
+                tsn if tsn.table().map_or(false, |t| t != table) => {}
+                _ => in_compaction = false,
+            }
+            let coalesce = in_compaction && matches!(tsn, Transaction::DeleteShard(_));
+            current.commit_one(tsn)?;
+            if let Some(shards) = readable_shards(&current, table) {
+                push(version, shards, coalesce);
+            }
+        }
+        Ok(history)
+    }
+
+    async fn load_snapshots(&self) -> crate::Result<Vec<Snapshot>> {
+        let mut file_list = self
+            .store
+            .list(Some(&self.path.join(Self::SNAPSHOTS).as_path()))
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?;
+        // Sort oldest to newest
+        file_list.sort_unstable_by(|a, b| a.location.filename().cmp(&b.location.filename()));
+
+        let mut snapshots = Vec::with_capacity(file_list.len());
+        for file in file_list {
+            let raw = self.store.get(&file.location).await?.bytes().await?;
+            snapshots.push(serde_json::from_slice(&raw)?);
+        }
+        Ok(snapshots)
+    }
+
     async fn write_snapshot(&self, snapshot: &Snapshot) -> crate::Result<()> {
         tracing::info!(uuid=%snapshot.uuid, "saving catalog snapshot");
 
@@ -141,3 +314,26 @@ impl TransactionLog {
         Ok(())
     }
 }
+
+fn readable_shards(snapshot: &Snapshot, table: &TableId<'_>) -> Option<Vec<ShardInfo>> {
+    let topic = snapshot
+        .catalogs
+        .iter()
+        .find(|c| c.id.0 == table.catalog)?
+        .schemas
+        .iter()
+        .find(|s| s.id.schema == table.schema)?
+        .tables
+        .iter()
+        .find(|t| t.id.table == table.table)?
+        .topic()
+        .ok()?;
+    Some(
+        topic
+            .shards()
+            .iter()
+            .filter(|s| s.rows.is_some())
+            .cloned()
+            .collect(),
+    )
+}
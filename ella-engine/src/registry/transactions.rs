@@ -96,6 +96,8 @@ pub struct CreateShard {
     pub shard: ShardId,
     pub file_schema: SchemaRef,
     pub path: Path,
+    #[serde(default)]
+    pub external: bool,
 }
 
 impl CreateShard {
@@ -108,13 +110,16 @@ impl CreateShard {
             shard,
             file_schema,
             path,
+            external: false,
         }
     }
 }
 
 impl From<CreateShard> for ShardInfo {
     fn from(tsn: CreateShard) -> Self {
-        ShardInfo::new(tsn.shard, tsn.table, tsn.file_schema, tsn.path)
+        let mut shard = ShardInfo::new(tsn.shard, tsn.table, tsn.file_schema, tsn.path);
+        shard.external = tsn.external;
+        shard
     }
 }
 
@@ -279,4 +284,18 @@ impl Transaction {
     pub fn kind(&self) -> String {
         self.to_string()
     }
+
+    /// The table modified by the transaction, if it only modifies a single table.
+    pub fn table(&self) -> Option<&TableId<'static>> {
+        use Transaction::*;
+        match self {
+            CreateCatalog(_) | CreateSchema(_) | DropSchema(_) | DropCatalog(_) => None,
+            CreateTable(t) => Some(&t.id),
+            CreateShard(t) => Some(&t.table),
+            CloseShard(t) => Some(&t.table),
+            DeleteShard(t) => Some(&t.table),
+            CompactShards(t) => Some(&t.table),
+            DropTable(t) => Some(&t.id),
+        }
+    }
 }
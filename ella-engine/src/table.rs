@@ -7,12 +7,14 @@ macro_rules! table {
     };
 }
 
+mod acl;
 pub(crate) mod config;
 pub mod info;
 pub mod topic;
 pub mod view;
 
-pub use config::TableConfig;
+pub use acl::{Permission, TableAcl};
+pub use config::{NonFinitePolicy, TableConfig};
 pub use topic::EllaTopic;
 pub use view::EllaView;
 
@@ -78,6 +80,10 @@ impl EllaTable {
         table!(self, t => t.config())
     }
 
+    pub fn acl(&self) -> Option<&TableAcl> {
+        self.config().acl.as_ref()
+    }
+
     pub fn path(&self) -> &Path {
         table!(self, t => t.path())
     }
@@ -100,6 +106,9 @@ impl EllaTable {
         }
     }
 
+    /// Every value returned by [`kind`](Self::kind).
+    pub const KINDS: &'static [&'static str] = &["topic", "view"];
+
     pub fn kind(&self) -> &'static str {
         match self {
             Self::Topic(_) => "topic",
@@ -0,0 +1,56 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+/// Access level granted to a principal.
+///
+/// Each level implies all of the levels below it, i.e. `Admin` implies `Write` and `Write` implies `Read`.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Permission {
+    Read,
+    Write,
+    Admin,
+}
+
+impl Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Read => write!(f, "read"),
+            Self::Write => write!(f, "write"),
+            Self::Admin => write!(f, "admin"),
+        }
+    }
+}
+
+/// Access control list mapping principals to the permissions they have been granted on a table.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct TableAcl {
+    grants: BTreeMap<String, Permission>,
+}
+
+impl TableAcl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn grant(mut self, principal: impl Into<String>, permission: Permission) -> Self {
+        self.grants.insert(principal.into(), permission);
+        self
+    }
+
+    pub fn revoke(mut self, principal: &str) -> Self {
+        self.grants.remove(principal);
+        self
+    }
+
+    pub fn permission(&self, principal: &str) -> Option<Permission> {
+        self.grants.get(principal).copied()
+    }
+
+    pub fn allows(&self, principal: &str, permission: Permission) -> bool {
+        self.permission(principal)
+            .map_or(false, |granted| granted >= permission)
+    }
+}
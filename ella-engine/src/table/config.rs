@@ -1,3 +1,5 @@
+use super::TableAcl;
+
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(default)]
 pub struct TableConfig {
@@ -5,9 +7,14 @@ pub struct TableConfig {
     pub rw_buffer_capacity: usize,
     pub target_shard_size: usize,
     pub min_shard_size: usize,
+    pub row_group_size: Option<usize>,
     pub subscriber_queue_size: usize,
     pub rw_queue_size: usize,
     pub shard_queue_size: usize,
+    pub shard_write_retries: usize,
+    pub shard_retry_buffer: usize,
+    pub non_finite: NonFinitePolicy,
+    pub acl: Option<TableAcl>,
 }
 
 impl Default for TableConfig {
@@ -17,9 +24,14 @@ impl Default for TableConfig {
             rw_buffer_capacity: 1024 * 1024,
             min_shard_size: 1024 * 1024,
             target_shard_size: 32 * 1024 * 1024,
+            row_group_size: None,
             subscriber_queue_size: 1024,
             rw_queue_size: 1024,
             shard_queue_size: 128,
+            shard_write_retries: 0,
+            shard_retry_buffer: 1024 * 1024,
+            non_finite: NonFinitePolicy::default(),
+            acl: None,
         }
     }
 }
@@ -40,6 +52,15 @@ impl TableConfig {
         self
     }
 
+    /// Write parquet row groups with at most `rows` rows.
+    ///
+    /// Larger row groups favor full scans while smaller ones allow more row groups to be
+    /// skipped by selective queries. Defaults to the minimum shard size.
+    pub fn with_row_group_size(mut self, rows: usize) -> Self {
+        self.row_group_size = Some(rows);
+        self
+    }
+
     pub fn with_rw_buffer_capacity(mut self, capacity: usize) -> Self {
         self.rw_buffer_capacity = capacity;
         self
@@ -60,6 +81,33 @@ impl TableConfig {
         self
     }
 
+    /// Retry failed shard writes up to `retries` times.
+    ///
+    /// Rows are kept in memory until their shard is closed so that they can be rewritten.
+    pub fn with_shard_write_retries(mut self, retries: usize) -> Self {
+        self.shard_write_retries = retries;
+        self
+    }
+
+    /// Keep at most `rows` rows of each shard in memory for retrying failed writes.
+    ///
+    /// Shards that grow past this limit are no longer retried if they fail.
+    pub fn with_shard_retry_buffer(mut self, rows: usize) -> Self {
+        self.shard_retry_buffer = rows;
+        self
+    }
+
+    /// Set how NaN and infinite values in float columns are handled when rows are published.
+    pub fn with_non_finite(mut self, policy: NonFinitePolicy) -> Self {
+        self.non_finite = policy;
+        self
+    }
+
+    pub fn with_acl(mut self, acl: TableAcl) -> Self {
+        self.acl = Some(acl);
+        self
+    }
+
     pub(crate) fn channel_config(&self) -> ChannelConfig {
         ChannelConfig {
             subscriber_queue_size: self.subscriber_queue_size,
@@ -78,13 +126,32 @@ impl TableConfig {
         ShardConfig {
             target_shard_size: self.target_shard_size,
             min_shard_size: self.min_shard_size,
-            row_group_size: self.min_shard_size,
+            row_group_size: self.row_group_size.unwrap_or(self.min_shard_size),
             write_batch_size: self.write_batch_size,
             queue_size: self.shard_queue_size,
+            write_retries: self.shard_write_retries,
+            retry_buffer: self.shard_retry_buffer,
         }
     }
 }
 
+/// How NaN and infinite values in float columns are handled when rows are published.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum NonFinitePolicy {
+    /// Store the values unchanged.
+    #[default]
+    Allow,
+    /// Reject batches that contain NaN or infinite values.
+    Reject,
+    /// Replace NaN and infinite values with nulls.
+    ///
+    /// Batches are rejected if a non-nullable column contains NaN or infinite values.
+    Mask,
+}
+
 #[derive(Debug, Clone)]
 pub struct RwBufferConfig {
     pub capacity: usize,
@@ -99,6 +166,8 @@ pub struct ShardConfig {
     pub row_group_size: usize,
     pub write_batch_size: usize,
     pub queue_size: usize,
+    pub write_retries: usize,
+    pub retry_buffer: usize,
 }
 
 #[derive(Debug, Clone)]
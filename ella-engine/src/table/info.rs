@@ -53,6 +53,7 @@ pub(crate) struct EllaTableInfo {
     arrow_schema: SchemaRef,
     parquet_schema: Option<SchemaRef>,
     sorting_cols: Option<Vec<SortingColumn>>,
+    cluster: Option<usize>,
     shards: Vec<ShardInfo>,
 }
 
@@ -81,6 +82,30 @@ impl EllaTableInfo {
         self.sorting_cols.as_ref()
     }
 
+    /// Index of the column that the rows of each shard are sorted by, if any.
+    pub fn cluster(&self) -> Option<usize> {
+        self.cluster
+    }
+
+    /// Sort order of the rows within each shard file.
+    ///
+    /// For clustered topics this is the cluster column followed by the topic's index.
+    pub fn file_sorting_cols(&self) -> Option<Vec<SortingColumn>> {
+        let cluster = match self.cluster {
+            Some(cluster) => cluster as i32,
+            None => return self.sorting_cols.clone(),
+        };
+        let mut cols = vec![SortingColumn::new(cluster, false, false)];
+        cols.extend(
+            self.sorting_cols
+                .iter()
+                .flatten()
+                .filter(|col| col.column_idx != cluster)
+                .cloned(),
+        );
+        Some(cols)
+    }
+
     pub fn shards(&self) -> &Vec<ShardInfo> {
         &self.shards
     }
@@ -110,6 +135,8 @@ pub struct ViewInfo {
     materialized: bool,
     index: Option<Vec<TableIndex>>,
     config: Option<TableConfig>,
+    #[serde(default)]
+    sql_null_order: bool,
 }
 
 impl ViewInfo {
@@ -144,7 +171,7 @@ impl ViewInfo {
     ) -> crate::Result<EllaTableInfo> {
         let arrow_schema = self.plan.arrow_schema();
         let parquet_schema = parquet_compat_schema(arrow_schema.clone());
-        let sorting_cols = sorting_cols(self.index.as_deref(), &arrow_schema)?;
+        let sorting_cols = sorting_cols(self.index.as_deref(), &arrow_schema, self.sql_null_order)?;
 
         let path = state
             .root()
@@ -156,6 +183,7 @@ impl ViewInfo {
             arrow_schema,
             parquet_schema,
             sorting_cols,
+            cluster: None,
             id,
             path,
             shards: Vec::new(),
@@ -224,6 +252,7 @@ impl ViewBuilder {
             materialized: self.materialized,
             index: self.index,
             config: self.config,
+            sql_null_order: true,
         }
     }
 }
@@ -241,6 +270,17 @@ pub struct TopicInfo {
     temporary: bool,
     shards: Vec<ShardInfo>,
     config: Option<TableConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    location: Option<Path>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    secondary_time: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cluster_by: Option<String>,
+    /// Whether nulls in the index columns are placed last for ascending and first for descending
+    /// columns, as in SQL's default ordering. Topics created before this was the default sort
+    /// nulls first, and keep doing so since their existing shards are written in that order.
+    #[serde(default)]
+    sql_null_order: bool,
 }
 
 impl TopicInfo {
@@ -264,6 +304,27 @@ impl TopicInfo {
         self.config.as_ref()
     }
 
+    /// External location that new shards are written to, if the topic appends to an existing
+    /// dataset instead of writing under the datastore root.
+    pub fn location(&self) -> Option<&Path> {
+        self.location.as_ref()
+    }
+
+    /// Name of the secondary timestamp column, if any.
+    pub fn secondary_time(&self) -> Option<&str> {
+        self.secondary_time.as_deref()
+    }
+
+    /// Name of the column that the rows of each shard are sorted by, if any.
+    pub fn cluster_by(&self) -> Option<&str> {
+        self.cluster_by.as_deref()
+    }
+
+    pub(crate) fn with_location(mut self, location: Path) -> Self {
+        self.location = Some(location);
+        self
+    }
+
     pub fn into_builder(mut self) -> TopicBuilder {
         let time = self.columns.remove(0);
         debug_assert!(time.data_type == TensorType::Timestamp);
@@ -274,12 +335,18 @@ impl TopicInfo {
             columns: self.columns,
             index: self.index,
             time: Some(time.name),
+            secondary_time: self.secondary_time,
+            cluster_by: self.cluster_by,
             temporary: self.temporary,
             config: self.config,
             append_time: true,
         }
     }
 
+    pub(crate) fn shards(&self) -> &Vec<ShardInfo> {
+        &self.shards
+    }
+
     pub(crate) fn shards_mut(&mut self) -> &mut Vec<ShardInfo> {
         &mut self.shards
     }
@@ -306,18 +373,34 @@ impl TopicInfo {
     ) -> crate::Result<EllaTableInfo> {
         let arrow_schema = self.arrow_schema();
         let parquet_schema = parquet_compat_schema(arrow_schema.clone());
-        let sorting_cols = sorting_cols(Some(&self.index), &arrow_schema)?;
-
-        let path = state
-            .root()
-            .join(id.catalog.as_ref())
-            .join(id.schema.as_ref())
-            .join(id.table.as_ref());
+        let sorting_cols = sorting_cols(Some(&self.index), &arrow_schema, self.sql_null_order)?;
+        let cluster = self
+            .cluster_by
+            .as_ref()
+            .map(|name| {
+                arrow_schema.index_of(name).map_err(|_| {
+                    crate::EngineError::InvalidIndex(format!(
+                        "cannot cluster topic by nonexistent column {}",
+                        name
+                    ))
+                })
+            })
+            .transpose()?;
+
+        let path = match &self.location {
+            Some(location) => location.clone(),
+            None => state
+                .root()
+                .join(id.catalog.as_ref())
+                .join(id.schema.as_ref())
+                .join(id.table.as_ref()),
+        };
 
         Ok(EllaTableInfo {
             arrow_schema,
             parquet_schema,
             sorting_cols,
+            cluster,
             id,
             path,
             shards: self.shards.clone(),
@@ -343,6 +426,8 @@ pub struct TopicBuilder {
     columns: Vec<Column>,
     index: Vec<TableIndex>,
     time: Option<String>,
+    secondary_time: Option<String>,
+    cluster_by: Option<String>,
     temporary: bool,
     config: Option<TableConfig>,
     append_time: bool,
@@ -354,6 +439,8 @@ impl Default for TopicBuilder {
             columns: Vec::new(),
             index: Vec::new(),
             time: None,
+            secondary_time: None,
+            cluster_by: None,
             temporary: false,
             config: None,
             append_time: true,
@@ -376,6 +463,25 @@ impl TopicBuilder {
         self
     }
 
+    /// Add a second timestamp column, such as the ingestion time of each row.
+    ///
+    /// Unlike the primary time column it is not part of the topic's index, so it isn't used to
+    /// sort or prune shards, but it can be queried like any other column.
+    pub fn secondary_time(mut self, name: impl Into<String>) -> Self {
+        self.secondary_time = Some(name.into());
+        self
+    }
+
+    /// Sort the rows of each shard by `column` before they are written.
+    ///
+    /// Row groups then cover narrow ranges of the column, so queries that filter on it can skip
+    /// most of them. Rows are held in memory until their shard is closed, and scans of closed
+    /// shards are no longer assumed to be ordered by the topic's index.
+    pub fn cluster_by(mut self, column: impl Into<String>) -> Self {
+        self.cluster_by = Some(column.into());
+        self
+    }
+
     pub fn index(mut self, col: impl Into<String>, ascending: bool) -> Self {
         self.index.push(TableIndex {
             column: col.into(),
@@ -411,6 +517,16 @@ impl TopicBuilder {
                 ascending: true,
             });
         }
+        if let Some(name) = &self.secondary_time {
+            if !self.columns.iter().any(|c| &c.name == name) {
+                columns.push(Column {
+                    name: name.clone(),
+                    data_type: TensorType::Timestamp,
+                    row_shape: None,
+                    required: false,
+                });
+            }
+        }
         columns.extend(self.columns.into_iter());
         index.extend(self.index.into_iter());
 
@@ -420,6 +536,10 @@ impl TopicBuilder {
             temporary: self.temporary,
             shards: Vec::new(),
             config: self.config,
+            location: None,
+            secondary_time: self.secondary_time,
+            cluster_by: self.cluster_by,
+            sql_null_order: true,
         }
     }
 
@@ -439,6 +559,7 @@ impl From<TopicBuilder> for TopicInfo {
 fn sorting_cols(
     index: Option<&[TableIndex]>,
     schema: &SchemaRef,
+    sql_null_order: bool,
 ) -> crate::Result<Option<Vec<SortingColumn>>> {
     if let Some(index) = index {
         let mut cols = Vec::with_capacity(index.len());
@@ -451,7 +572,12 @@ fn sorting_cols(
 
         for idx in index {
             match fields.get(&idx.column) {
-                Some(&i) => cols.push(SortingColumn::new(i as i32, !idx.ascending, true)),
+                // Matching SQL's default null placement (last for ASC, first for DESC) lets
+                // `ORDER BY` on the index be satisfied by the scan's ordering without a sort.
+                Some(&i) => {
+                    let nulls_first = !sql_null_order || !idx.ascending;
+                    cols.push(SortingColumn::new(i as i32, !idx.ascending, nulls_first))
+                }
                 None => {
                     return Err(crate::EngineError::InvalidIndex(format!(
                         "cannot define table index for nonexistent column {}",
@@ -1,9 +1,15 @@
+mod changes;
 mod channel;
+mod import;
 mod rw;
 pub(crate) mod shard;
 
+pub(crate) use changes::read_changes;
+pub use changes::{change_schema, ChangeOp, OPERATION_COLUMN};
 pub use channel::{Publisher, Subscriber, TopicChannel};
 use futures::{stream::BoxStream, Stream, StreamExt};
+pub(crate) use import::scan_parquet_files;
+pub use import::ImportOptions;
 pub(crate) use rw::RwBuffer;
 pub(crate) use shard::compact_shards;
 pub use shard::ShardInfo;
@@ -25,7 +31,12 @@ use datafusion::{
     prelude::Expr,
 };
 
-use crate::{engine::EllaState, registry::TableId, table::TableConfig, Path};
+use crate::{
+    engine::{EllaState, SessionOptions},
+    registry::TableId,
+    table::TableConfig,
+    Path,
+};
 
 use self::shard::ShardSet;
 
@@ -115,6 +126,10 @@ impl EllaTopic {
 
     pub(crate) async fn drop_shards(&self) -> crate::Result<()> {
         self.close().await?;
+        // Files in an external location are shared with other readers
+        if self.info.location().is_some() {
+            return Ok(());
+        }
         if let Some(shards) = &self.shards {
             shards.delete_all().await?;
         }
@@ -125,6 +140,22 @@ impl EllaTopic {
         &self.info
     }
 
+    /// Returns `true` if the topic has buffered rows that have not been persisted yet.
+    pub fn has_pending_writes(&self) -> bool {
+        self.rw.as_ref().map_or(false, |rw| rw.has_pending())
+    }
+
+    /// Persist all buffered rows and wait until they are readable from the topic's shards.
+    pub async fn flush(&self) -> crate::Result<()> {
+        if let Some(rw) = &self.rw {
+            rw.flush().await?;
+        }
+        if let Some(shards) = &self.shards {
+            shards.flush().await?;
+        }
+        Ok(())
+    }
+
     pub(crate) fn shards(&self) -> Option<&Arc<ShardSet>> {
         self.shards.as_ref().map(|s| s.shards())
     }
@@ -137,6 +168,10 @@ impl EllaTopic {
     }
 
     pub(crate) fn sort(&self) -> Option<Vec<SortingColumn>> {
+        // Compaction concatenates shards, so clustered shards are only sorted piecewise
+        if self.table_info.cluster().is_some() {
+            return None;
+        }
         self.table_info.sorting_cols().cloned()
     }
 }
@@ -189,9 +224,21 @@ impl TableProvider for EllaTopic {
             None => None,
         };
         let rw = match self.rw.clone() {
-            Some(rw) => Some(child_plan(rw).await?),
+            Some(rw) => {
+                let read_your_writes = state
+                    .config()
+                    .get_extension::<SessionOptions>()
+                    .map_or(false, |options| options.read_your_writes);
+                if read_your_writes {
+                    rw.sync()
+                        .await
+                        .map_err(|err| DataFusionError::External(Box::new(err)))?;
+                }
+                Some(child_plan(rw).await?)
+            }
             None => None,
         };
+        let channel_idle = self.channel.is_idle();
         let channel = child_plan(self.channel.clone()).await?;
 
         Ok(Arc::new(TopicExec {
@@ -199,6 +246,7 @@ impl TableProvider for EllaTopic {
             shards,
             rw,
             channel,
+            channel_idle,
         }))
     }
 
@@ -218,6 +266,9 @@ struct TopicExec {
     shards: Option<Arc<dyn ExecutionPlan>>,
     rw: Option<Arc<dyn ExecutionPlan>>,
     channel: Arc<dyn ExecutionPlan>,
+    // Topics without publishers when the query was planned skip the channel, as if any rows
+    // published since had arrived after the query ran
+    channel_idle: bool,
 }
 
 impl ExecutionPlan for TopicExec {
@@ -283,6 +334,7 @@ impl ExecutionPlan for TopicExec {
             shards,
             rw,
             channel,
+            channel_idle: self.channel_idle,
         }))
     }
 
@@ -308,21 +360,44 @@ impl ExecutionPlan for TopicExec {
             None => None,
         };
 
-        let channel = (0..self.channel.output_partitioning().partition_count())
-            .map(|i| self.channel.execute(i, context.clone()))
-            .collect::<Result<Vec<_>>>()?;
-        let channel = futures::stream::iter(channel).flatten().boxed();
+        let channel = if self.channel_idle {
+            None
+        } else {
+            Some(exec_child(&self.channel)?)
+        };
 
         Ok(Box::pin(TopicStream {
             schema: self.schema(),
             shards,
             rw,
-            channel: Some(channel),
+            channel,
         }))
     }
 
+    /// The row count is exact if every source that is read reports an exact row count.
     fn statistics(&self) -> Statistics {
-        Statistics::default()
+        let mut sources = Vec::with_capacity(3);
+        sources.extend(self.shards.clone());
+        sources.extend(self.rw.clone());
+        if !self.channel_idle {
+            sources.push(self.channel.clone());
+        }
+        let mut num_rows = 0;
+        for child in sources {
+            match child.statistics() {
+                Statistics {
+                    num_rows: Some(rows),
+                    is_exact: true,
+                    ..
+                } => num_rows += rows,
+                _ => return Statistics::default(),
+            }
+        }
+        Statistics {
+            num_rows: Some(num_rows),
+            is_exact: true,
+            ..Default::default()
+        }
     }
 
     fn fmt_as(&self, _t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
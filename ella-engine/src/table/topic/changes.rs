@@ -0,0 +1,88 @@
+use std::{fmt::Display, sync::Arc};
+
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use datafusion::{
+    arrow::{
+        array::{ArrayRef, StringArray},
+        record_batch::RecordBatch,
+    },
+    parquet::arrow::{async_reader::ParquetObjectReader, ParquetRecordBatchStreamBuilder},
+};
+use futures::{stream::BoxStream, StreamExt, TryStreamExt};
+use object_store::ObjectStore;
+
+use crate::util::parquet::cast_batch;
+
+use super::ShardInfo;
+
+/// Name of the column holding the kind of each change in a change feed.
+pub const OPERATION_COLUMN: &str = "_operation";
+
+/// Kind of change emitted by a change feed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeOp {
+    Insert,
+}
+
+impl Display for ChangeOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Insert => write!(f, "insert"),
+        }
+    }
+}
+
+/// Schema of a change feed for a topic with schema `schema`.
+pub fn change_schema(schema: &Schema) -> SchemaRef {
+    let mut fields = schema.fields().iter().cloned().collect::<Vec<_>>();
+    fields.push(Arc::new(Field::new(
+        OPERATION_COLUMN,
+        DataType::Utf8,
+        false,
+    )));
+    Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()))
+}
+
+/// Read the rows of `shards` as insert events.
+pub(crate) fn read_changes(
+    store: Arc<dyn ObjectStore>,
+    shards: Vec<ShardInfo>,
+    schema: SchemaRef,
+) -> BoxStream<'static, crate::Result<RecordBatch>> {
+    let output = change_schema(&schema);
+    futures::stream::iter(shards)
+        .then(move |shard| {
+            let store = store.clone();
+            async move {
+                let meta = store.head(&shard.path.as_path()).await?;
+                let stream =
+                    ParquetRecordBatchStreamBuilder::new(ParquetObjectReader::new(store, meta))
+                        .await?
+                        .build()?;
+                crate::Result::Ok(stream.map_err(crate::Error::from))
+            }
+        })
+        .try_flatten()
+        .and_then(move |batch| {
+            let res = with_operation(batch, &schema, &output, ChangeOp::Insert);
+            futures::future::ready(res)
+        })
+        .boxed()
+}
+
+fn with_operation(
+    batch: RecordBatch,
+    schema: &SchemaRef,
+    output: &SchemaRef,
+    op: ChangeOp,
+) -> crate::Result<RecordBatch> {
+    let batch = if batch.schema() != *schema {
+        cast_batch(&batch, schema.clone())?
+    } else {
+        batch
+    };
+    let mut columns = batch.columns().to_vec();
+    let op: ArrayRef = Arc::new(StringArray::from(vec![op.to_string(); batch.num_rows()]));
+    columns.push(op);
+    Ok(RecordBatch::try_new(output.clone(), columns)?)
+}
@@ -7,9 +7,14 @@ use std::{
     task::Poll,
 };
 
-use arrow_schema::SchemaRef;
+use arrow_schema::{DataType, SchemaRef};
 use datafusion::{
-    arrow::record_batch::RecordBatch,
+    arrow::{
+        array::{Array, ArrayRef, FixedSizeListArray, PrimitiveArray},
+        buffer::{BooleanBuffer, NullBuffer},
+        datatypes::{ArrowPrimitiveType, Float32Type, Float64Type},
+        record_batch::RecordBatch,
+    },
     datasource::TableProvider,
     error::{DataFusionError, Result},
     execution::{context::SessionState, TaskContext},
@@ -28,7 +33,11 @@ use tokio_util::sync::ReusableBoxFuture;
 
 use crate::{
     registry::TableId,
-    table::{config::ChannelConfig, info::EllaTableInfo},
+    table::{
+        config::{ChannelConfig, NonFinitePolicy},
+        info::EllaTableInfo,
+    },
+    util::parquet::cast_batch,
     ArrowSchema,
 };
 
@@ -55,6 +64,7 @@ impl TopicChannel {
         let publisher = Publisher {
             table: table.id().clone(),
             schema: table.arrow_schema().clone(),
+            non_finite: table.config().non_finite,
             inner: PublisherInner {
                 rw: RwBuffer::sink(rw),
                 subs,
@@ -82,6 +92,11 @@ impl TopicChannel {
         &self.config
     }
 
+    /// Whether the topic currently has no active publishers.
+    pub(crate) fn is_idle(&self) -> bool {
+        self.publisher.inner.active.load(Ordering::Acquire) == 0
+    }
+
     fn subscribe_inner(&self, stop_on_inactive: bool) -> SubscriberInner {
         SubscriberInner {
             inner: self.publisher.inner.subs.subscribe(),
@@ -143,6 +158,7 @@ impl PublisherInner {
 pub struct Publisher {
     table: TableId<'static>,
     schema: SchemaRef,
+    non_finite: NonFinitePolicy,
     inner: PublisherInner,
 }
 
@@ -163,6 +179,7 @@ impl Sink<RecordBatch> for Publisher {
         item: RecordBatch,
     ) -> std::result::Result<(), Self::Error> {
         let batch = item.with_schema(self.schema.clone())?;
+        let batch = check_non_finite(batch, self.non_finite)?;
         let _ = self.inner.subs.send(batch.clone());
         self.inner.rw.start_send_unpin(batch)
     }
@@ -199,6 +216,7 @@ impl Publisher {
         Self {
             table: self.table.clone(),
             schema: self.schema.clone(),
+            non_finite: self.non_finite,
             inner: self.inner.clone_inner(is_active),
         }
     }
@@ -223,6 +241,72 @@ impl DisplayAs for Publisher {
     }
 }
 
+/// Apply `policy` to the NaN and infinite values in the float columns of `batch`.
+fn check_non_finite(batch: RecordBatch, policy: NonFinitePolicy) -> crate::Result<RecordBatch> {
+    if policy == NonFinitePolicy::Allow {
+        return Ok(batch);
+    }
+
+    let schema = batch.schema();
+    let mut columns = batch.columns().to_vec();
+    let mut changed = false;
+    for (col, field) in columns.iter_mut().zip(schema.fields().iter()) {
+        if let Some(masked) = mask_non_finite(col) {
+            if policy == NonFinitePolicy::Reject || !field.is_nullable() {
+                return Err(crate::EngineError::NonFinite(field.name().clone()).into());
+            }
+            *col = masked;
+            changed = true;
+        }
+    }
+
+    if changed {
+        Ok(RecordBatch::try_new(schema, columns)?)
+    } else {
+        Ok(batch)
+    }
+}
+
+/// Returns a copy of `array` with its NaN and infinite values set to null, or `None` if there
+/// are no such values.
+///
+/// Fixed-size lists are masked element-wise, leaving the rows themselves valid.
+fn mask_non_finite(array: &ArrayRef) -> Option<ArrayRef> {
+    match array.data_type() {
+        DataType::Float32 => mask_float::<Float32Type>(array),
+        DataType::Float64 => mask_float::<Float64Type>(array),
+        DataType::FixedSizeList(_, _) => {
+            let list = array.as_any().downcast_ref::<FixedSizeListArray>()?;
+            let values = mask_non_finite(list.values())?;
+            let data = unsafe {
+                list.to_data()
+                    .into_builder()
+                    .child_data(vec![values.to_data()])
+                    .build_unchecked()
+            };
+            Some(Arc::new(FixedSizeListArray::from(data)))
+        }
+        _ => None,
+    }
+}
+
+fn mask_float<T>(array: &ArrayRef) -> Option<ArrayRef>
+where
+    T: ArrowPrimitiveType,
+    T::Native: num_traits::Float,
+{
+    let array = array.as_any().downcast_ref::<PrimitiveArray<T>>()?;
+    let values = array.values();
+    let finite =
+        BooleanBuffer::collect_bool(array.len(), |i| array.is_null(i) || values[i].is_finite());
+    if finite.count_set_bits() == array.len() {
+        return None;
+    }
+
+    let nulls = NullBuffer::union(array.nulls(), Some(&NullBuffer::new(finite)));
+    Some(Arc::new(PrimitiveArray::<T>::new(values.clone(), nulls)))
+}
+
 #[async_trait::async_trait]
 impl DataSink for Publisher {
     async fn write_all(
@@ -235,6 +319,14 @@ impl DataSink for Publisher {
 
         while let Some(batch) = data.try_next().await? {
             rows += batch.num_rows();
+            // Rows from `INSERT ... SELECT` are cast to the topic's column types by the planner,
+            // but don't carry the topic's nullability or tensor field metadata
+            let batch = if batch.schema() == self.schema {
+                batch
+            } else {
+                cast_batch(&batch, self.schema.clone())
+                    .map_err(|err| DataFusionError::External(Box::new(err)))?
+            };
             this.feed(batch)
                 .await
                 .map_err(|err| DataFusionError::External(Box::new(err)))?;
@@ -444,7 +536,17 @@ impl ExecutionPlan for ChannelExec {
     }
 
     fn statistics(&self) -> Statistics {
-        Statistics::default()
+        // Without active publishers the subscriber ends without yielding any rows, but a
+        // publisher may start before the plan is executed
+        if self.src.stop_on_inactive && self.src.active.load(Ordering::Acquire) == 0 {
+            Statistics {
+                num_rows: Some(0),
+                is_exact: false,
+                ..Default::default()
+            }
+        } else {
+            Statistics::default()
+        }
     }
 
     fn fmt_as(&self, _t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
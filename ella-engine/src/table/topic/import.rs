@@ -0,0 +1,153 @@
+use std::sync::Arc;
+
+use arrow_schema::SchemaRef;
+use datafusion::{
+    datasource::file_format::parquet::fetch_parquet_metadata,
+    parquet::{arrow::parquet_to_arrow_schema, file::statistics::Statistics},
+};
+use futures::TryStreamExt;
+use object_store::ObjectStore;
+
+use crate::{table::info::TopicInfo, Path};
+
+/// Options for registering existing parquet files as a topic.
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    info: TopicInfo,
+    extension: String,
+    append: bool,
+}
+
+impl ImportOptions {
+    pub fn new(info: impl Into<TopicInfo>) -> Self {
+        Self {
+            info: info.into(),
+            extension: "parquet".to_string(),
+            append: false,
+        }
+    }
+
+    /// Only import files with this extension (defaults to `parquet`).
+    pub fn extension(mut self, extension: impl Into<String>) -> Self {
+        self.extension = extension.into();
+        self
+    }
+
+    /// Write shards published to the topic into the imported prefix.
+    ///
+    /// The imported files are left untouched: the topic is not compacted and dropping it does
+    /// not delete any files.
+    pub fn append(mut self, append: bool) -> Self {
+        self.append = append;
+        self
+    }
+
+    pub fn info(&self) -> &TopicInfo {
+        &self.info
+    }
+
+    pub fn is_append(&self) -> bool {
+        self.append
+    }
+}
+
+/// A parquet file whose footer has been validated against a topic's schema.
+#[derive(Debug, Clone)]
+pub(crate) struct ImportedFile {
+    pub path: Path,
+    pub rows: usize,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+}
+
+/// List the parquet files under `prefix` and read their footers.
+///
+/// Files are returned in order of their time index. Returns an error if any file's schema
+/// doesn't match `file_schema` or if the time ranges of the files overlap.
+pub(crate) async fn scan_parquet_files(
+    store: &Arc<dyn ObjectStore>,
+    prefix: &Path,
+    options: &ImportOptions,
+    file_schema: &SchemaRef,
+    time: &str,
+) -> crate::Result<Vec<ImportedFile>> {
+    let mut objects = store
+        .list(Some(&prefix.as_path()))
+        .await?
+        .try_filter(|obj| {
+            let matches = obj.location.extension() == Some(options.extension.as_str());
+            futures::future::ready(matches)
+        })
+        .try_collect::<Vec<_>>()
+        .await?;
+    objects.sort_by(|a, b| a.location.cmp(&b.location));
+
+    let mut files = Vec::with_capacity(objects.len());
+    for obj in objects {
+        let path = obj
+            .location
+            .parts()
+            .fold(prefix.store_url(), |path, part| path.join(part.as_ref()));
+
+        let meta = fetch_parquet_metadata(&**store, &obj, Some(obj.size)).await?;
+        let schema = parquet_to_arrow_schema(
+            meta.file_metadata().schema_descr(),
+            meta.file_metadata().key_value_metadata(),
+        )?;
+        if schema.fields() != file_schema.fields() {
+            return Err(crate::EngineError::InvalidImport(format!(
+                "schema of {} does not match table schema",
+                path
+            ))
+            .into());
+        }
+
+        let column = meta
+            .file_metadata()
+            .schema_descr()
+            .columns()
+            .iter()
+            .position(|c| c.path().string() == time);
+        let (mut start, mut end) = (None, None);
+        for group in meta.row_groups() {
+            let stats = column.and_then(|c| group.column(c).statistics());
+            match stats {
+                Some(Statistics::Int64(stats)) if stats.has_min_max_set() => {
+                    start = Some(start.map_or(*stats.min(), |t: i64| t.min(*stats.min())));
+                    end = Some(end.map_or(*stats.max(), |t: i64| t.max(*stats.max())));
+                }
+                // Row groups without statistics can't be placed in the time index
+                _ if group.num_rows() > 0 => {
+                    return Err(crate::EngineError::InvalidImport(format!(
+                        "{} is missing statistics for time column {}",
+                        path, time
+                    ))
+                    .into())
+                }
+                _ => {}
+            }
+        }
+
+        files.push(ImportedFile {
+            path,
+            rows: meta.file_metadata().num_rows() as usize,
+            start,
+            end,
+        });
+    }
+
+    // Shards are scanned in order, so imported files must cover disjoint time ranges.
+    files.sort_by_key(|f| f.start);
+    for pair in files.windows(2) {
+        if let (Some(end), Some(start)) = (pair[0].end, pair[1].start) {
+            if start < end {
+                return Err(crate::EngineError::InvalidImport(format!(
+                    "time ranges of {} and {} overlap",
+                    pair[0].path, pair[1].path
+                ))
+                .into());
+            }
+        }
+    }
+    Ok(files)
+}
@@ -14,7 +14,7 @@ use datafusion::{
 
 use flume::r#async::SendSink;
 use futures::{FutureExt, Sink, SinkExt};
-use tokio::sync::{Mutex, Notify};
+use tokio::sync::{oneshot, Mutex, Notify};
 use tokio::task::JoinHandle;
 use tracing::Instrument;
 
@@ -31,6 +31,7 @@ pub(crate) struct RwBuffer {
     input: InstrumentedBuffer<SendSink<'static, RecordBatch>>,
     compacting: Arc<WorkQueueIn<RecordBatch>>,
     writing: Arc<WorkQueueIn<()>>,
+    requests: flume::Sender<BufferRequest>,
     handle: Mutex<Option<JoinHandle<()>>>,
     stop: Arc<Notify>,
 }
@@ -47,6 +48,7 @@ impl Debug for RwBuffer {
 impl RwBuffer {
     pub fn new(table: EllaTableInfo, shards: Arc<ShardManager>, config: RwBufferConfig) -> Self {
         let (send, recv) = flume::bounded(config.queue_size);
+        let (requests, requests_recv) = flume::unbounded();
 
         let input = send
             .into_sink()
@@ -63,6 +65,7 @@ impl RwBuffer {
             compacting_out,
             writing_in: writing.clone(),
             writing_out,
+            requests: requests_recv,
             shards,
             stop: stop.clone(),
             config: config.clone(),
@@ -81,11 +84,37 @@ impl RwBuffer {
             handle,
             compacting,
             writing,
+            requests,
             stop,
             config,
         }
     }
 
+    /// Returns `true` if the buffer contains rows that have not been written to a readable shard.
+    pub fn has_pending(&self) -> bool {
+        !self.input.inner().is_empty() || !self.compacting.is_empty() || !self.writing.is_empty()
+    }
+
+    /// Wait until all buffered rows have been written to readable shards.
+    pub async fn flush(&self) -> crate::Result<()> {
+        self.request(BufferRequest::Flush).await
+    }
+
+    /// Wait until all rows queued before this call are visible when scanning the buffer.
+    pub async fn sync(&self) -> crate::Result<()> {
+        self.request(BufferRequest::Sync).await
+    }
+
+    async fn request(&self, req: fn(oneshot::Sender<()>) -> BufferRequest) -> crate::Result<()> {
+        let (done, out) = oneshot::channel();
+        self.requests
+            .send_async(req(done))
+            .await
+            .map_err(|_| crate::EngineError::TableClosed)?;
+        out.await.map_err(|_| crate::EngineError::TableClosed)?;
+        Ok(())
+    }
+
     pub fn sink(this: Option<Arc<Self>>) -> RwBufferSink {
         RwBufferSink(this.map(|rw| rw.input.clone()))
     }
@@ -108,6 +137,12 @@ impl RwBuffer {
     }
 }
 
+#[derive(Debug)]
+enum BufferRequest {
+    Sync(oneshot::Sender<()>),
+    Flush(oneshot::Sender<()>),
+}
+
 #[derive(Debug)]
 struct RwBufferWorker {
     arrow_schema: SchemaRef,
@@ -116,6 +151,7 @@ struct RwBufferWorker {
     compacting_out: WorkQueueOut<RecordBatch>,
     writing_in: Arc<WorkQueueIn<()>>,
     writing_out: WorkQueueOut<()>,
+    requests: flume::Receiver<BufferRequest>,
     shards: Arc<ShardManager>,
     stop: Arc<Notify>,
     config: RwBufferConfig,
@@ -148,12 +184,12 @@ impl RwBufferWorker {
             }
         };
 
+        // Returns `true` if a write job was sent to the shard writer
         let write_batch = |batch: RecordBatch| {
             let rows = batch.num_rows();
             self.writing_in.push(batch);
             let shards = self.shards.clone();
-            let _ = self
-                .writing_in
+            self.writing_in
                 .process(|values| match shards.write(values) {
                     Ok(handle) => {
                         tracing::debug!(rows, "writing compacted buffer");
@@ -167,10 +203,17 @@ impl RwBufferWorker {
                         tracing::error!(?error, rows, "failed to write compacted buffer");
                         futures::future::ready(()).right_future()
                     }
-                });
+                })
+                .is_ok()
         };
 
         let mut len = 0;
+        // Write jobs sent to the shard writer and write jobs that have finished
+        let (mut writes, mut written) = (0, 0);
+        // Flushes waiting for buffered rows to be compacted
+        let mut flushing: Vec<oneshot::Sender<()>> = Vec::new();
+        // Flushes waiting for the given number of write jobs to finish
+        let mut persisting: Vec<(usize, oneshot::Sender<()>)> = Vec::new();
         loop {
             tokio::select! {
                 biased;
@@ -189,19 +232,67 @@ impl RwBufferWorker {
                 compacted = self.compacting_out.ready() => match compacted {
                     Some(res) => {
                         match res {
-                            Ok(batch) => write_batch(batch),
+                            Ok(batch) => {
+                                if write_batch(batch) {
+                                    writes += 1;
+                                }
+                            },
                             Err(error) => tracing::error!(?error, "failed to compact records"),
                         }
                     },
                     None => unreachable!(),
                 },
                 Some(res) = self.writing_out.ready() => {
+                    written += 1;
                     if let Err(error) = res {
                         tracing::error!(?error, "failed to write batch to disk");
                     }
                 },
+                Ok(req) = self.requests.recv_async() => {
+                    for batch in self.recv.drain() {
+                        len += batch.num_rows();
+                        self.compacting_in.push(batch);
+                    }
+                    match req {
+                        BufferRequest::Sync(done) => {
+                            let _ = done.send(());
+                        }
+                        // Compact any buffered rows regardless of the batch size
+                        BufferRequest::Flush(done) => {
+                            if len > 0 {
+                                compact_buffer(len);
+                                len = 0;
+                            }
+                            flushing.push(done);
+                        }
+                    }
+                },
                 _ = &mut wait_stop => break,
             }
+            // Once all compacted batches have been sent to the shard writer, close the active shard
+            // so that the write jobs finish.
+            if !flushing.is_empty() && self.compacting_in.is_empty() {
+                if writes > written {
+                    let shards = self.shards.clone();
+                    tokio::spawn(
+                        async move {
+                            if let Err(error) = shards.flush().await {
+                                tracing::error!(?error, "failed to flush shard writer");
+                            }
+                        }
+                        .in_current_span(),
+                    );
+                }
+                persisting.extend(flushing.drain(..).map(|done| (writes, done)));
+            }
+            // Flush is complete once every write job sent before it has finished.
+            let (done, waiting) = std::mem::take(&mut persisting)
+                .into_iter()
+                .partition::<Vec<_>, _>(|(target, _)| *target <= written);
+            persisting = waiting;
+            for (_, done) in done {
+                let _ = done.send(());
+            }
         }
         tracing::debug!("shutting down R/W buffer worker");
 
@@ -220,7 +311,9 @@ impl RwBufferWorker {
         self.compacting_out.close();
         while let Some(res) = self.compacting_out.ready().await {
             match res {
-                Ok(batch) => write_batch(batch),
+                Ok(batch) => {
+                    write_batch(batch);
+                }
                 Err(error) => {
                     tracing::error!(?error, "failed to compact records")
                 }
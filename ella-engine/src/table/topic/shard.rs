@@ -30,7 +30,7 @@ use datafusion::{
 };
 use futures::FutureExt;
 use tokio::{
-    sync::{oneshot, Mutex, Notify, RwLock},
+    sync::{oneshot, Mutex, MutexGuard, Notify, RwLock},
     task::JoinHandle,
 };
 
@@ -51,6 +51,7 @@ pub(crate) struct ShardSet {
     path: Path,
     shards: RwLock<BTreeMap<ShardId, ShardInfo>>,
     log: Arc<TransactionLog>,
+    compacting: Mutex<()>,
 }
 
 impl ShardSet {
@@ -68,9 +69,17 @@ impl ShardSet {
             shards,
             table: table.id().clone(),
             path,
+            compacting: Mutex::new(()),
         }
     }
 
+    /// Acquire exclusive access for compacting the shard set.
+    ///
+    /// Returns `None` if the shards are already being compacted.
+    pub fn try_start_compaction(&self) -> Option<MutexGuard<'_, ()>> {
+        self.compacting.try_lock().ok()
+    }
+
     #[tracing::instrument(skip_all, fields(shard=tracing::field::Empty))]
     pub async fn create_shard(&self, file_schema: SchemaRef) -> crate::Result<ShardInfo> {
         let mut shards = self.shards.write().await;
@@ -84,6 +93,31 @@ impl ShardSet {
         Ok(shard)
     }
 
+    /// Register an existing parquet file as a closed external shard without rewriting it.
+    #[tracing::instrument(skip_all, fields(shard=tracing::field::Empty))]
+    pub async fn import_shard(
+        &self,
+        file_schema: SchemaRef,
+        path: Path,
+        rows: usize,
+    ) -> crate::Result<ShardInfo> {
+        let mut shards = self.shards.write().await;
+        let mut tsn = CreateShard::new(self.table.clone(), file_schema, &self.path);
+        tsn.path = path;
+        tsn.external = true;
+        tracing::Span::current().record("shard", tsn.shard.to_string());
+
+        tracing::debug!(path=%tsn.path, "importing shard");
+        self.log.commit(tsn.clone()).await?;
+        self.log
+            .commit(CloseShard::new(self.table.clone(), tsn.shard, rows))
+            .await?;
+        let mut shard = ShardInfo::from(tsn);
+        shard.close(rows);
+        shards.insert(shard.id, shard.clone());
+        Ok(shard)
+    }
+
     #[tracing::instrument(skip(self, id), fields(shard=%id))]
     pub async fn close_shard(&self, id: ShardId, rows: usize) -> crate::Result<()> {
         let mut shards = self.shards.write().await;
@@ -247,6 +281,17 @@ impl ShardManager {
         }
     }
 
+    /// Close the active shard and wait until all previously written rows are readable.
+    pub async fn flush(&self) -> crate::Result<()> {
+        let (done, out) = oneshot::channel();
+        self.input
+            .send_async(WriteJob::flush(done))
+            .await
+            .map_err(|_| crate::EngineError::TableClosed)?;
+        out.await.map_err(|_| crate::EngineError::TableClosed)?;
+        Ok(())
+    }
+
     #[tracing::instrument(skip(self), fields(table=%self.table()))]
     pub async fn close(&self) -> crate::Result<()> {
         self.stop.notify_one();
@@ -329,10 +374,10 @@ impl TableProvider for ShardManager {
         .await?;
 
         let table_partition_cols = vec![];
-        let output_ordering = if let Some(order) = self.table.output_ordering() {
-            vec![order.to_vec()]
-        } else {
-            Vec::new()
+        // Clustered shards are sorted by the cluster column rather than the index
+        let output_ordering = match self.table.output_ordering() {
+            Some(order) if self.table.cluster().is_none() => vec![order.to_vec()],
+            _ => Vec::new(),
         };
 
         let file_schema = self
@@ -341,11 +386,21 @@ impl TableProvider for ShardManager {
             .cloned()
             .unwrap_or_else(|| self.table.arrow_schema().clone());
 
+        // Closed shards record their row counts, which lets unfiltered counts skip the scan
+        let statistics = match shards.iter().map(|s| s.rows).sum::<Option<usize>>() {
+            Some(rows) if filters.is_empty() && limit.is_none() => Statistics {
+                num_rows: Some(rows),
+                is_exact: true,
+                ..Default::default()
+            },
+            _ => Statistics::default(),
+        };
+
         let config = FileScanConfig {
             object_store_url: ObjectStoreUrl::parse(self.path().store_url())?,
             file_schema,
             file_groups: vec![files],
-            statistics: Statistics::default(),
+            statistics,
             projection: projection.cloned(),
             limit,
             table_partition_cols,
@@ -386,6 +441,11 @@ pub struct ShardInfo {
     pub file_schema: SchemaRef,
     pub path: Path,
     pub rows: Option<usize>,
+    /// Whether the shard's file was imported rather than written by the engine.
+    ///
+    /// External files are never compacted or deleted.
+    #[serde(default)]
+    pub external: bool,
 }
 
 impl ShardInfo {
@@ -396,6 +456,7 @@ impl ShardInfo {
             file_schema,
             path,
             rows: None,
+            external: false,
         }
     }
 
@@ -2,7 +2,10 @@ use std::sync::Arc;
 
 use arrow_schema::SchemaRef;
 use datafusion::{
-    arrow::record_batch::RecordBatch,
+    arrow::{
+        compute::{concat_batches, lexsort_to_indices, take, SortColumn, SortOptions},
+        record_batch::RecordBatch,
+    },
     parquet::{arrow::AsyncArrowWriter, file::properties::WriterProperties, format::SortingColumn},
 };
 use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, StreamExt};
@@ -108,6 +111,28 @@ impl ShardWriterWorker {
     }
 
     async fn handle_write(&mut self, job: WriteJob) -> crate::Result<()> {
+        if job.flush {
+            // Close the active shard so that all rows written so far become readable
+            match self.active.take() {
+                Some(active) => {
+                    self.len = 0;
+                    let done = job.done;
+                    self.pending.push(
+                        async move {
+                            let res = active.finish().await;
+                            let _ = done.send(());
+                            res
+                        }
+                        .boxed(),
+                    );
+                }
+                None => {
+                    let _ = job.done.send(());
+                }
+            }
+            return Ok(());
+        }
+
         for batch in &job.values {
             self.len += batch.num_rows();
         }
@@ -117,7 +142,8 @@ impl ShardWriterWorker {
             let shard = SingleShardWriter::create(
                 self.table.arrow_schema().clone(),
                 self.table.parquet_schema().cloned(),
-                self.table.sorting_cols().cloned(),
+                self.table.file_sorting_cols(),
+                self.table.cluster().is_some(),
                 self.store.clone(),
                 &self.config,
                 self.shards.clone(),
@@ -143,11 +169,24 @@ impl ShardWriterWorker {
 pub struct WriteJob {
     values: Vec<RecordBatch>,
     done: oneshot::Sender<()>,
+    flush: bool,
 }
 
 impl WriteJob {
     pub(crate) fn new(values: Vec<RecordBatch>, done: oneshot::Sender<()>) -> Self {
-        Self { values, done }
+        Self {
+            values,
+            done,
+            flush: false,
+        }
+    }
+
+    pub(crate) fn flush(done: oneshot::Sender<()>) -> Self {
+        Self {
+            values: Vec::new(),
+            done,
+            flush: true,
+        }
     }
 }
 
@@ -220,17 +259,55 @@ impl JobHandle {
         jobs: flume::Receiver<WriteJob>,
         mut shard: SingleShardWriter,
     ) -> crate::Result<()> {
+        let retries = shard.config.write_retries;
+        let retry_buffer = shard.config.retry_buffer;
+        // Keep the rows written to the shard so they can be rewritten if the shard fails, until
+        // the shard outgrows the retry buffer
+        let mut retryable = retries > 0;
+        let mut written = Vec::new();
+        let mut buffered = 0;
         let mut pending = Vec::new();
         while let Ok(job) = jobs.recv_async().await {
             for batch in job.values {
+                if retryable {
+                    buffered += batch.num_rows();
+                    if buffered > retry_buffer {
+                        tracing::debug!(rows = buffered, "shard exceeds retry buffer");
+                        retryable = false;
+                        written = Vec::new();
+                    }
+                }
                 if let Err(error) = shard.write(&batch).await {
-                    shard.abort().await?;
-                    return Err(error);
+                    if !retryable {
+                        shard.abort().await?;
+                        return Err(error);
+                    }
+                    tracing::warn!(?error, "failed to write shard, retrying");
+                    written.push(batch);
+                    shard = shard.retry(&written, retries).await?;
+                } else if retryable {
+                    written.push(batch);
                 }
             }
             pending.push(job.done);
         }
-        shard.close().await?;
+
+        let mut attempt = 0;
+        loop {
+            let (id, params) = (shard.shard.id, shard.params());
+            match shard.close().await {
+                Ok(()) => break,
+                Err(error) if retryable && attempt < retries => {
+                    attempt += 1;
+                    tracing::warn!(?error, attempt, "failed to close shard, retrying");
+                    if let Err(error) = params.shards.delete_shard(id).await {
+                        tracing::warn!(?error, %id, "failed to remove unclosed shard");
+                    }
+                    shard = params.retry(&written, retries - attempt + 1).await?;
+                }
+                Err(error) => return Err(error),
+            }
+        }
         for p in pending {
             let _ = p.send(());
         }
@@ -239,8 +316,71 @@ impl JobHandle {
     }
 }
 
+/// Everything needed to create a new shard for a table.
+#[derive(Clone)]
+struct ShardParams {
+    table_schema: SchemaRef,
+    file_schema: Option<SchemaRef>,
+    sort: Option<Vec<SortingColumn>>,
+    cluster: bool,
+    store: Arc<dyn ObjectStore>,
+    config: ShardConfig,
+    shards: Arc<ShardSet>,
+}
+
+impl ShardParams {
+    async fn create(&self) -> crate::Result<SingleShardWriter> {
+        SingleShardWriter::create(
+            self.table_schema.clone(),
+            self.file_schema.clone(),
+            self.sort.clone(),
+            self.cluster,
+            self.store.clone(),
+            &self.config,
+            self.shards.clone(),
+        )
+        .await
+    }
+
+    /// Write `batches` to a new shard.
+    async fn rewrite(&self, batches: &[RecordBatch]) -> crate::Result<SingleShardWriter> {
+        let mut shard = self.create().await?;
+        for batch in batches {
+            if let Err(error) = shard.write(batch).await {
+                shard.abort_quietly().await;
+                return Err(error);
+            }
+        }
+        Ok(shard)
+    }
+
+    /// Write `batches` to a new shard, retrying up to `retries` times.
+    async fn retry(
+        &self,
+        batches: &[RecordBatch],
+        retries: usize,
+    ) -> crate::Result<SingleShardWriter> {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            tokio::time::sleep(SingleShardWriter::backoff(attempt)).await;
+            match self.rewrite(batches).await {
+                Ok(shard) => return Ok(shard),
+                Err(error) if attempt < retries => {
+                    tracing::warn!(?error, attempt, "failed to rewrite shard");
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
 pub(crate) struct SingleShardWriter {
     shard: ShardInfo,
+    table_schema: SchemaRef,
+    sort: Option<Vec<SortingColumn>>,
+    // Rows of clustered shards are buffered until the shard is closed so they can be sorted
+    cluster: Option<Vec<RecordBatch>>,
     file_schema: Option<SchemaRef>,
     file: AsyncArrowWriter<Box<dyn AsyncWrite + Unpin + Send>>,
     abort: String,
@@ -257,18 +397,19 @@ impl SingleShardWriter {
         table_schema: SchemaRef,
         file_schema: Option<SchemaRef>,
         sort: Option<Vec<SortingColumn>>,
+        cluster: bool,
         store: Arc<dyn ObjectStore>,
         cfg: &ShardConfig,
         shards: Arc<ShardSet>,
     ) -> crate::Result<Self> {
-        let schema = file_schema.clone().unwrap_or(table_schema);
+        let schema = file_schema.clone().unwrap_or_else(|| table_schema.clone());
         let shard = shards.create_shard(schema.clone()).await?;
         let path = shard.path.clone();
 
         let (abort, file) = store.put_multipart(&path.as_path()).await?;
 
         let props = WriterProperties::builder()
-            .set_sorting_columns(sort)
+            .set_sorting_columns(sort.clone())
             .set_max_row_group_size(cfg.row_group_size)
             .set_write_batch_size(cfg.write_batch_size)
             .build();
@@ -277,6 +418,9 @@ impl SingleShardWriter {
 
         Ok(Self {
             shard,
+            table_schema,
+            sort,
+            cluster: cluster.then(Vec::new),
             abort,
             store,
             file_schema,
@@ -291,17 +435,81 @@ impl SingleShardWriter {
         &self.shard.path
     }
 
+    fn params(&self) -> ShardParams {
+        ShardParams {
+            table_schema: self.table_schema.clone(),
+            file_schema: self.file_schema.clone(),
+            sort: self.sort.clone(),
+            cluster: self.cluster.is_some(),
+            store: self.store.clone(),
+            config: self.config.clone(),
+            shards: self.shards.clone(),
+        }
+    }
+
+    fn backoff(attempt: usize) -> std::time::Duration {
+        std::time::Duration::from_millis(100 << attempt.min(8))
+    }
+
+    /// Abort this shard and write `batches` to a new one, retrying up to `retries` times.
+    async fn retry(self, batches: &[RecordBatch], retries: usize) -> crate::Result<Self> {
+        let params = self.params();
+        self.abort_quietly().await;
+        params.retry(batches, retries).await
+    }
+
+    /// Abort the shard, logging any errors instead of returning them.
+    async fn abort_quietly(self) {
+        let path = self.path().clone();
+        if let Err(error) = self.abort().await {
+            tracing::warn!(?error, %path, "failed to abort shard");
+        }
+    }
+
     async fn write(&mut self, batch: &RecordBatch) -> crate::Result<()> {
-        if let Some(schema) = &self.file_schema {
-            let batch = cast_batch(batch, schema.clone())?;
-            self.num_rows += batch.num_rows();
-            Ok(self.file.write(&batch).await?)
-        } else {
-            self.num_rows += batch.num_rows();
-            Ok(self.file.write(batch).await?)
+        let batch = match &self.file_schema {
+            Some(schema) => cast_batch(batch, schema.clone())?,
+            None => batch.clone(),
+        };
+        self.num_rows += batch.num_rows();
+        match &mut self.cluster {
+            Some(buffered) => {
+                buffered.push(batch);
+                Ok(())
+            }
+            None => Ok(self.file.write(&batch).await?),
         }
     }
 
+    /// Sort the buffered rows of a clustered shard and write them to the file.
+    async fn write_clustered(&mut self) -> crate::Result<()> {
+        let batches = match self.cluster.as_mut() {
+            Some(buffered) if !buffered.is_empty() => std::mem::take(buffered),
+            _ => return Ok(()),
+        };
+        let batch = concat_batches(&batches[0].schema(), &batches)?;
+        let columns = self
+            .sort
+            .iter()
+            .flatten()
+            .map(|col| SortColumn {
+                values: batch.column(col.column_idx as usize).clone(),
+                options: Some(SortOptions {
+                    descending: col.descending,
+                    nulls_first: col.nulls_first,
+                }),
+            })
+            .collect::<Vec<_>>();
+        let indices = lexsort_to_indices(&columns, None)?;
+        let sorted = batch
+            .columns()
+            .iter()
+            .map(|col| take(col, &indices, None))
+            .collect::<Result<Vec<_>, _>>()?;
+        let batch = RecordBatch::try_new(batch.schema(), sorted)?;
+        Ok(self.file.write(&batch).await?)
+    }
+
     async fn abort(self) -> crate::Result<()> {
         self.shards.delete_shard(self.shard.id).await?;
         self.store
@@ -310,12 +518,14 @@ impl SingleShardWriter {
         Ok(())
     }
 
-    async fn close(self) -> crate::Result<()> {
+    async fn close(mut self) -> crate::Result<()> {
         if self.num_rows == 0 {
             tracing::debug!(path=%self.path(), "discarding empty shard");
             return self.abort().await;
         }
 
+        self.write_clustered().await?;
+
         let meta = self.file.close().await?;
         debug_assert_eq!(self.num_rows, meta.num_rows as usize);
         self.shards
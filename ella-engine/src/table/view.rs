@@ -65,6 +65,11 @@ impl EllaView {
         self.table_info.path()
     }
 
+    /// Logical plan that defines the view.
+    pub fn plan(&self) -> &LogicalPlan {
+        self.info.plan().stub()
+    }
+
     pub(crate) fn info(&self) -> &ViewInfo {
         &self.info
     }
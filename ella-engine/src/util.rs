@@ -5,10 +5,16 @@ use std::{collections::HashSet, sync::Arc};
 
 use arrow_schema::Schema;
 use datafusion::{
-    error::DataFusionError, physical_expr::PhysicalSortExpr, physical_plan::expressions::Column,
+    arrow::error::ArrowError,
+    error::DataFusionError,
+    parquet::errors::ParquetError,
+    physical_expr::PhysicalSortExpr,
+    physical_plan::{
+        expressions::Column, stream::RecordBatchStreamAdapter, SendableRecordBatchStream,
+    },
 };
 use ella_common::Duration;
-use futures::{TryFutureExt, TryStreamExt};
+use futures::{StreamExt, TryFutureExt, TryStreamExt};
 use tokio::{sync::Notify, task::JoinHandle, time::MissedTickBehavior};
 use tracing::Instrument;
 
@@ -57,68 +63,38 @@ impl MaintenanceWorker {
         interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
         loop {
             tokio::select! {
-                _ = interval.tick() => {
+                // Compaction and cleanup rewrite and delete files
+                _ = interval.tick(), if !self.state.config().engine_config().read_only() => {
                     let tables = self.state.cluster().catalogs()
                         .into_iter()
                         .flat_map(|c| c.schemas())
                         .flat_map(|s| s.tables());
+                    let concurrency = self.state.config().engine_config().compaction_concurrency();
+                    let this = &self;
 
-                    for table in tables {
-                        self.compact_table(&table)
-                            .unwrap_or_else(|error| {
-                                tracing::error!(error=?error, "failed to compact topic");
-                            })
-                            .instrument(tracing::info_span!("compact", table=%table.id()))
-                            .await;
-
-                        self.cleanup_table(&table)
-                            .unwrap_or_else(|error| {
-                                tracing::error!(error=?error, "failed to cleanup topic");
-                            })
-                            .instrument(tracing::info_span!("compact", table=%table.id()))
-                            .await;
-                    }
+                    futures::stream::iter(tables)
+                        .for_each_concurrent(concurrency, |table| async move {
+                            compact_table(&table, this.state.clone(), false)
+                                .unwrap_or_else(|error| {
+                                    tracing::error!(error=?error, "failed to compact topic");
+                                })
+                                .instrument(tracing::info_span!("compact", table=%table.id()))
+                                .await;
+
+                            this.cleanup_table(&table)
+                                .unwrap_or_else(|error| {
+                                    tracing::error!(error=?error, "failed to cleanup topic");
+                                })
+                                .instrument(tracing::info_span!("compact", table=%table.id()))
+                                .await;
+                        })
+                        .await;
                 },
                 _ = &mut stop => break,
             }
         }
     }
 
-    async fn compact_table(&self, table: &Arc<EllaTable>) -> crate::Result<()> {
-        let mut pending = vec![];
-        let mut pending_rows = 0;
-        let target_rows = table.config().target_shard_size;
-        let shard_set = match table.shards() {
-            Some(s) => s.clone(),
-            None => return Ok(()),
-        };
-        let shards = shard_set.readable_shards().await;
-        for shard in &shards {
-            if let Some(rows) = shard.rows {
-                if rows < target_rows {
-                    pending.push(shard.clone());
-                    pending_rows += rows;
-
-                    if pending_rows >= target_rows {
-                        break;
-                    }
-                }
-            }
-        }
-        if pending.len() > 1 {
-            compact_shards(
-                pending,
-                table.file_schema(),
-                table.sort(),
-                shard_set,
-                self.state.clone(),
-                table.config().shard_config(),
-            )
-            .await?;
-        }
-        Ok(())
-    }
-
     async fn cleanup_table(&self, table: &Arc<EllaTable>) -> crate::Result<()> {
         let store = self.state.store();
         let mut files = store
@@ -132,6 +108,13 @@ impl MaintenanceWorker {
             Some(s) => s.all_shards().await,
             None => return Ok(()),
         };
+        // Files at an external location belong to the existing dataset
+        if table
+            .as_topic()
+            .map_or(true, |topic| topic.info().location().is_some())
+        {
+            return Ok(());
+        }
         for shard in shards {
             files.remove(&shard.path.as_path());
         }
@@ -144,6 +127,146 @@ impl MaintenanceWorker {
     }
 }
 
+/// Files removed by [`EllaContext::vacuum`](crate::EllaContext::vacuum).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VacuumReport {
+    pub files: usize,
+    pub bytes: usize,
+}
+
+/// Delete files under `table`'s directory that were last modified before `cutoff` (in seconds
+/// since the epoch) and aren't referenced by the topic or any of its retained manifest versions.
+pub(crate) async fn vacuum_table(
+    table: &Arc<EllaTable>,
+    state: &EllaState,
+    cutoff: i64,
+) -> crate::Result<VacuumReport> {
+    let mut report = VacuumReport::default();
+    let shards = match table.shards() {
+        Some(s) => s.all_shards().await,
+        None => return Ok(report),
+    };
+    // Files at an external location belong to the existing dataset
+    if table
+        .as_topic()
+        .map_or(true, |topic| topic.info().location().is_some())
+    {
+        return Ok(report);
+    }
+
+    let mut keep = state.log().retained_paths(table.id()).await?;
+    keep.extend(shards.iter().map(|shard| shard.path.as_path()));
+
+    let store = state.store();
+    let files = store
+        .list(Some(&table.path().as_path()))
+        .await?
+        .try_filter(|f| {
+            futures::future::ready(
+                !keep.contains(&f.location) && f.last_modified.timestamp() < cutoff,
+            )
+        })
+        .try_collect::<Vec<_>>()
+        .await?;
+    for file in files {
+        store.delete(&file.location).await?;
+        tracing::info!(path=%file.location, bytes=file.size, "vacuumed orphaned file");
+        report.files += 1;
+        report.bytes += file.size;
+    }
+    Ok(report)
+}
+
+/// Compact `tables`, processing up to `concurrency` tables at the same time.
+pub(crate) async fn compact_tables(
+    state: Arc<EllaState>,
+    tables: Vec<Arc<EllaTable>>,
+    concurrency: usize,
+) -> crate::Result<()> {
+    // Let every compaction run to completion rather than aborting the others on the first error
+    let results = futures::stream::iter(tables)
+        .map(|table| {
+            let state = state.clone();
+            async move {
+                compact_table(&table, state, true)
+                    .instrument(tracing::info_span!("compact", table=%table.id()))
+                    .await
+            }
+        })
+        .buffer_unordered(concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await;
+    results.into_iter().collect()
+}
+
+/// Compact the undersized shards of `table`.
+///
+/// Unless `force` is set, the shards are only compacted if they meet the configured triggers.
+async fn compact_table(
+    table: &Arc<EllaTable>,
+    state: Arc<EllaState>,
+    force: bool,
+) -> crate::Result<()> {
+    let shard_set = match table.shards() {
+        Some(s) => s.clone(),
+        None => return Ok(()),
+    };
+    // Compaction would replace files that are shared with external readers
+    if table
+        .as_topic()
+        .map_or(false, |topic| topic.info().location().is_some())
+    {
+        return Ok(());
+    }
+    // Skip tables that are already being compacted by another task
+    let _guard = match shard_set.try_start_compaction() {
+        Some(guard) => guard,
+        None => return Ok(()),
+    };
+
+    let mut pending = vec![];
+    let mut pending_rows = 0;
+    let target_rows = table.config().target_shard_size;
+    let shards = shard_set.readable_shards().await;
+    let small = shards
+        .iter()
+        .filter(|s| s.rows.map_or(false, |rows| rows < target_rows))
+        .count();
+    if !force
+        && !state
+            .config()
+            .engine_config()
+            .should_compact(small, shards.len())
+    {
+        return Ok(());
+    }
+    // Imported files are read in place and must not be replaced
+    for shard in shards.iter().filter(|s| !s.external) {
+        if let Some(rows) = shard.rows {
+            if rows < target_rows {
+                pending.push(shard.clone());
+                pending_rows += rows;
+
+                if pending_rows >= target_rows {
+                    break;
+                }
+            }
+        }
+    }
+    if pending.len() > 1 {
+        compact_shards(
+            pending,
+            table.file_schema(),
+            table.sort(),
+            shard_set.clone(),
+            state,
+            table.config().shard_config(),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
 pub(crate) fn project_ordering(
     schema: &Schema,
     projection: &[usize],
@@ -166,3 +289,72 @@ pub(crate) fn project_ordering(
     }
     Ok(out)
 }
+
+/// Split batches in `stream` so that none has more than `batch_size` rows.
+pub(crate) fn split_batches(
+    stream: SendableRecordBatchStream,
+    batch_size: usize,
+) -> SendableRecordBatchStream {
+    let schema = stream.schema();
+    let batch_size = batch_size.max(1);
+    let stream = stream
+        .map_ok(move |batch| {
+            let batches = (0..batch.num_rows())
+                .step_by(batch_size)
+                .map(|offset| {
+                    let len = batch_size.min(batch.num_rows() - offset);
+                    Ok::<_, DataFusionError>(batch.slice(offset, len))
+                })
+                .collect::<Vec<_>>();
+            futures::stream::iter(batches)
+        })
+        .try_flatten();
+    Box::pin(RecordBatchStreamAdapter::new(schema, stream))
+}
+
+/// End `stream` with [`EngineError::PartialResults`](crate::EngineError::PartialResults)
+/// instead of the storage error if storage becomes unavailable after some rows have already
+/// been returned, so that callers can tell that the rows they received are valid but incomplete.
+pub(crate) fn best_effort(stream: SendableRecordBatchStream) -> SendableRecordBatchStream {
+    let schema = stream.schema();
+    let stream = stream.scan(Some(0_usize), |state, res| {
+        let item = match (*state, res) {
+            (None, _) => None,
+            (Some(rows), Ok(batch)) => {
+                *state = Some(rows + batch.num_rows());
+                Some(Ok(batch))
+            }
+            (Some(rows), Err(error)) if rows > 0 && is_storage_error(&error) => {
+                tracing::warn!(
+                    ?error,
+                    rows,
+                    "storage unavailable, returning partial results"
+                );
+                *state = None;
+                let error = crate::Error::from(crate::EngineError::PartialResults {
+                    rows,
+                    error: error.to_string(),
+                });
+                Some(Err(DataFusionError::External(Box::new(error))))
+            }
+            (Some(_), Err(error)) => Some(Err(error)),
+        };
+        futures::future::ready(item)
+    });
+    Box::pin(RecordBatchStreamAdapter::new(schema, stream))
+}
+
+fn is_storage_error(error: &DataFusionError) -> bool {
+    let is_storage = |error: &(dyn std::error::Error + Send + Sync + 'static)| {
+        error.is::<object_store::Error>() || error.is::<std::io::Error>()
+    };
+    match error {
+        DataFusionError::ObjectStore(_) | DataFusionError::IoError(_) => true,
+        DataFusionError::ParquetError(ParquetError::External(error))
+        | DataFusionError::ArrowError(ArrowError::ExternalError(error))
+        | DataFusionError::External(error) => is_storage(error.as_ref()),
+        DataFusionError::ArrowError(ArrowError::IoError(_)) => true,
+        DataFusionError::Context(_, error) => is_storage_error(error),
+        _ => false,
+    }
+}
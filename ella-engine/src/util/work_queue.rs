@@ -75,6 +75,10 @@ where
         self.values.read().unwrap().values()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.values.read().unwrap().items.is_empty()
+    }
+
     pub fn try_process<F, Fut>(&self, f: F) -> crate::Result<()>
     where
         F: FnOnce(Vec<RecordBatch>) -> Fut,
@@ -0,0 +1,73 @@
+mod common;
+
+use common::count_rows;
+
+#[tokio::test]
+async fn audit_records_statement_text() -> ella_engine::Result<()> {
+    let (_dir, ctx) = common::create(Default::default()).await?;
+    let ctx = ctx.read_your_writes(true);
+    ctx.create_topic("t", common::topic(), false, false).await?;
+
+    ctx.execute("drop   table t").await?;
+    let sql = "SELECT * FROM system.audit WHERE action = 'DROP' AND statement = 'drop   table t'";
+    assert_eq!(count_rows(&ctx, sql).await?, 1);
+
+    ctx.shutdown().await
+}
+
+#[tokio::test]
+async fn audit_records_create_and_drop() -> ella_engine::Result<()> {
+    let (_dir, ctx) = common::create(Default::default()).await?;
+    ctx.create_topic("t", common::topic(), false, false).await?;
+    ctx.execute("DROP TABLE t").await?;
+
+    // Entries are written to a shard before the audited operation returns
+    assert!(!ctx.has_pending_writes("system.audit")?);
+    let sql =
+        "SELECT * FROM system.audit WHERE action = 'CREATE' AND statement LIKE 'CREATE TOPIC %t'";
+    assert_eq!(count_rows(&ctx, sql).await?, 1);
+    let sql = "SELECT * FROM system.audit WHERE action = 'DROP' AND statement = 'DROP TABLE t'";
+    assert_eq!(count_rows(&ctx, sql).await?, 1);
+
+    ctx.shutdown().await
+}
+
+#[tokio::test]
+async fn audit_skips_failed_statements() -> ella_engine::Result<()> {
+    let (_dir, ctx) = common::create(Default::default()).await?;
+    let ctx = ctx.read_your_writes(true);
+    let topic = ctx.create_topic("t", common::topic(), false, false).await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+
+    assert!(ctx.execute("DELETE FROM t WHERE x = 1").await.is_err());
+    let sql = "SELECT * FROM system.audit WHERE action = 'DELETE'";
+    assert_eq!(count_rows(&ctx, sql).await?, 0);
+
+    ctx.shutdown().await
+}
+
+#[tokio::test]
+async fn audit_table_is_protected() -> ella_engine::Result<()> {
+    let (_dir, ctx) = common::create(Default::default()).await?;
+
+    for sql in [
+        "INSERT INTO system.audit SELECT * FROM system.audit",
+        "DROP TABLE system.audit",
+        "DROP SCHEMA system CASCADE",
+    ] {
+        let err = ctx.execute(sql).await.unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ella_engine::Error::Engine(ella_engine::EngineError::SystemTable(_))
+            ),
+            "{}: unexpected error {:?}",
+            sql,
+            err
+        );
+    }
+    // The audit log is still readable
+    count_rows(&ctx, "SELECT * FROM system.audit").await?;
+
+    ctx.shutdown().await
+}
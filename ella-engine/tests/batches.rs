@@ -0,0 +1,32 @@
+mod common;
+
+use ella_engine::{config::EngineConfig, EllaConfig};
+use futures::TryStreamExt;
+
+#[tokio::test]
+async fn results_are_split_into_configured_batch_size() -> ella_engine::Result<()> {
+    let config = EllaConfig::builder()
+        .engine_config(EngineConfig::builder().batch_size(2))
+        .build();
+    let (_dir, ctx) = common::create(config).await?;
+    let topic = ctx.create_topic("t", common::topic(), false, false).await?;
+    common::publish(&topic, &[1, 2, 3, 4, 5, 6, 7]).await?;
+    ctx.flush("t").await?;
+
+    let batches = ctx
+        .query("SELECT x FROM t")
+        .await?
+        .stream()
+        .await?
+        .into_inner()
+        .try_collect::<Vec<_>>()
+        .await?;
+    assert!(batches.len() >= 4, "expected at least 4 batches");
+    assert!(batches.iter().all(|batch| batch.num_rows() <= 2));
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 7);
+
+    // Collecting the results still returns every row
+    assert_eq!(common::count_rows(&ctx, "SELECT x FROM t").await?, 7);
+
+    ctx.shutdown().await
+}
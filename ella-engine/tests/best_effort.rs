@@ -0,0 +1,87 @@
+mod common;
+
+use std::path::{Path, PathBuf};
+
+use datafusion::error::DataFusionError;
+use ella_engine::{config::EngineConfig, EllaConfig, EngineError, Error};
+use futures::StreamExt;
+
+fn parquet_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in std::fs::read_dir(dir).expect("failed to read directory") {
+        let path = entry.expect("failed to read directory entry").path();
+        if path.is_dir() {
+            parquet_files(&path, files);
+        } else if path.extension().map_or(false, |ext| ext == "parquet") {
+            files.push(path);
+        }
+    }
+}
+
+/// Delete the most recently written shard, as if storage failed while the query was reading it.
+fn delete_newest_shard(dir: &Path) {
+    let mut files = Vec::new();
+    parquet_files(dir, &mut files);
+    let newest = files
+        .into_iter()
+        .max_by_key(|path| path.metadata().and_then(|m| m.modified()).ok())
+        .expect("datastore should contain shards");
+    std::fs::remove_file(newest).expect("failed to remove shard");
+}
+
+#[tokio::test]
+async fn best_effort_read_reports_partial_results() -> ella_engine::Result<()> {
+    let config = EllaConfig::builder()
+        .engine_config(
+            EngineConfig::builder()
+                .auto_compaction(false)
+                .best_effort_reads(true)
+                .target_partitions(1),
+        )
+        .build();
+    let (dir, ctx) = common::create(config).await?;
+    let topic = ctx.create_topic("t", common::topic(), false, false).await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    ctx.flush("t").await?;
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    common::publish(&topic, &[4, 5]).await?;
+    ctx.flush("t").await?;
+    delete_newest_shard(dir.path());
+
+    let mut stream = ctx
+        .query("SELECT x FROM t")
+        .await?
+        .stream()
+        .await?
+        .into_inner();
+    let mut rows = 0;
+    let mut error = None;
+    while let Some(res) = stream.next().await {
+        match res {
+            Ok(batch) => rows += batch.num_rows(),
+            Err(err) => {
+                error = Some(err);
+                break;
+            }
+        }
+    }
+    assert_eq!(rows, 3);
+    let error = error.expect("query over a missing shard should not succeed");
+    let partial = match &error {
+        DataFusionError::External(err) => match err.downcast_ref::<Error>() {
+            Some(Error::Engine(EngineError::PartialResults { rows, .. })) => Some(*rows),
+            _ => None,
+        },
+        _ => None,
+    };
+    assert_eq!(
+        partial,
+        Some(3),
+        "expected partial results error, got {}",
+        error
+    );
+
+    // The partial results error is the last item in the stream
+    assert!(stream.next().await.is_none());
+
+    ctx.shutdown().await
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use ella_engine::EllaConfig;
+
+#[tokio::test]
+async fn cached_query_executes_once() -> ella_engine::Result<()> {
+    let (_dir, ctx) = common::create(EllaConfig::default()).await?;
+    let topic = ctx.create_topic("t", common::topic(), false, false).await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    ctx.flush("t").await?;
+
+    let cached = ctx.query("SELECT x FROM t").await?.cache().await?;
+
+    // Rows written after the cache was created are not seen by its consumers
+    common::publish(&topic, &[4, 5]).await?;
+    ctx.flush("t").await?;
+    assert_eq!(common::count_rows(&ctx, "SELECT x FROM t").await?, 5);
+
+    let first = cached.clone().execute().await?;
+    let second = cached.clone().limit(2)?.execute().await?;
+    assert_eq!(first.nrows(), 3);
+    assert_eq!(second.nrows(), 2);
+
+    ctx.shutdown().await
+}
+
+#[tokio::test]
+async fn cached_query_combines_with_datastore_queries() -> ella_engine::Result<()> {
+    let (_dir, ctx) = common::create(EllaConfig::default()).await?;
+    let topic = ctx.create_topic("t", common::topic(), false, false).await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    ctx.flush("t").await?;
+
+    let cached = ctx.query("SELECT x FROM t").await?.cache().await?;
+    common::publish(&topic, &[4]).await?;
+    ctx.flush("t").await?;
+
+    // The cache is planned in the datastore's session, so it can be combined with live tables
+    let live = ctx.query("SELECT x FROM t").await?;
+    let combined = cached.clone().union(live, true)?.execute().await?;
+    assert_eq!(combined.nrows(), 7);
+
+    // Caching a cache keeps the datastore's session
+    let recached = cached.cache().await?;
+    assert_eq!(recached.execute().await?.nrows(), 3);
+
+    ctx.shutdown().await
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use datafusion::arrow::{array::StringArray, record_batch::RecordBatch};
+use ella_engine::{
+    config::EngineConfig,
+    table::topic::{ChangeOp, OPERATION_COLUMN},
+    EllaConfig,
+};
+use futures::TryStreamExt;
+
+fn operations(batches: &[RecordBatch]) -> Vec<String> {
+    batches
+        .iter()
+        .flat_map(|batch| {
+            let ops = batch
+                .column_by_name(OPERATION_COLUMN)
+                .expect("change batches should have an operation column")
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .expect("operation column should be a string column")
+                .clone();
+            ops.iter()
+                .map(|op| op.unwrap().to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn changes_since_checkpoint() -> ella_engine::Result<()> {
+    let config = EllaConfig::builder()
+        .engine_config(EngineConfig::builder().auto_compaction(false))
+        .build();
+    let (_dir, ctx) = common::create(config).await?;
+    let topic = ctx.create_topic("t", common::topic(), false, false).await?;
+
+    common::publish(&topic, &[1, 2]).await?;
+    ctx.flush("t").await?;
+    let checkpoint = ctx.manifest_versions("t").await?.last().unwrap().version;
+
+    common::publish(&topic, &[3]).await?;
+    ctx.flush("t").await?;
+    common::publish(&topic, &[4, 5]).await?;
+    ctx.flush("t").await?;
+
+    let batches = ctx
+        .changes_since("t", checkpoint)
+        .await?
+        .try_collect::<Vec<_>>()
+        .await?;
+    let ops = operations(&batches);
+    assert_eq!(ops.len(), 3);
+    assert!(ops.iter().all(|op| *op == ChangeOp::Insert.to_string()));
+
+    // Compacting the checkpoint's shards with newer shards makes the changes unrecoverable
+    ctx.compact().await?;
+    assert!(ctx.changes_since("t", checkpoint).await.is_err());
+
+    ctx.shutdown().await
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use datafusion::arrow::{array::StringArray, record_batch::RecordBatch};
+use ella_engine::{EllaContext, TableConfig};
+use futures::TryStreamExt;
+
+/// Number of parquet row groups skipped while running `sql`, from `EXPLAIN ANALYZE`.
+async fn row_groups_pruned(ctx: &EllaContext, sql: &str) -> ella_engine::Result<usize> {
+    let batches = ctx
+        .query(format!("EXPLAIN ANALYZE {}", sql))
+        .await?
+        .stream()
+        .await?
+        .into_inner()
+        .try_collect::<Vec<RecordBatch>>()
+        .await?;
+    let mut pruned = 0;
+    for batch in &batches {
+        let plans = batch
+            .column_by_name("plan")
+            .and_then(|col| col.as_any().downcast_ref::<StringArray>())
+            .expect("EXPLAIN output should have a plan column");
+        for text in plans.iter().flatten() {
+            for (_, rest) in text.match_indices("row_groups_pruned=") {
+                let rest = &rest["row_groups_pruned=".len()..];
+                let digits = rest
+                    .chars()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect::<String>();
+                pruned += digits.parse::<usize>().unwrap_or_default();
+            }
+        }
+    }
+    Ok(pruned)
+}
+
+#[tokio::test]
+async fn clustered_topic_prunes_row_groups() -> ella_engine::Result<()> {
+    let (_dir, ctx) = common::create(Default::default()).await?;
+    let config = TableConfig::default().with_row_group_size(4);
+    // Channels are interleaved, so every row group of the unclustered topic contains each one
+    let channels = (0..40).map(|i| i % 4).collect::<Vec<_>>();
+    for (name, info) in [
+        ("plain", common::topic().config(config.clone())),
+        ("clustered", common::topic().config(config).cluster_by("x")),
+    ] {
+        let topic = ctx.create_topic(name, info, false, false).await?;
+        common::publish(&topic, &channels).await?;
+        ctx.flush(name).await?;
+    }
+
+    let plain = row_groups_pruned(&ctx, "SELECT * FROM plain WHERE x = 2").await?;
+    let clustered = row_groups_pruned(&ctx, "SELECT * FROM clustered WHERE x = 2").await?;
+    assert_eq!(plain, 0);
+    assert!(clustered > plain, "{} row groups pruned", clustered);
+    for name in ["plain", "clustered"] {
+        let sql = format!("SELECT * FROM {} WHERE x = 2", name);
+        assert_eq!(common::count_rows(&ctx, &sql).await?, 10);
+    }
+
+    ctx.shutdown().await
+}
@@ -0,0 +1,69 @@
+#![allow(dead_code)]
+
+use datafusion::arrow::{array::StringArray, record_batch::RecordBatch};
+use ella_common::{TensorType, Time};
+use ella_engine::{
+    table::{info::TopicBuilder, ColumnBuilder, EllaTopic},
+    EllaConfig, EllaContext,
+};
+use futures::{SinkExt, TryStreamExt};
+use tempfile::TempDir;
+
+/// Create a datastore in a new temporary directory.
+///
+/// The directory is deleted when the returned `TempDir` is dropped, so it must outlive the context.
+pub async fn create(config: EllaConfig) -> ella_engine::Result<(TempDir, EllaContext)> {
+    let dir = tempfile::tempdir()?;
+    let root = dir.path().to_str().expect("temporary path should be UTF-8");
+    let ctx = ella_engine::create(root, config, false).await?;
+    Ok((dir, ctx))
+}
+
+/// A topic with a time index and a single `Int32` column `x`.
+pub fn topic() -> TopicBuilder {
+    TopicBuilder::new().column(ColumnBuilder::new("x", TensorType::Int32))
+}
+
+/// Publish `values` to a topic created from [`topic`], one row per value.
+pub async fn publish(topic: &EllaTopic, values: &[i32]) -> ella_engine::Result<()> {
+    let mut rows = topic.publish().rows::<(Time, i32)>(1)?;
+    for &x in values {
+        rows.send((ella_common::now(), x)).await?;
+    }
+    rows.close().await
+}
+
+/// Run `sql` and return the number of rows in the result.
+pub async fn count_rows(ctx: &EllaContext, sql: &str) -> ella_engine::Result<usize> {
+    Ok(ctx.query(sql).await?.execute().await?.nrows())
+}
+
+/// The physical plan of `sql`, as printed by `EXPLAIN`.
+pub async fn physical_plan(ctx: &EllaContext, sql: &str) -> ella_engine::Result<String> {
+    let batches = ctx
+        .query(format!("EXPLAIN {}", sql))
+        .await?
+        .stream()
+        .await?
+        .into_inner()
+        .try_collect::<Vec<RecordBatch>>()
+        .await?;
+    let mut plan = String::new();
+    for batch in &batches {
+        let types = batch
+            .column_by_name("plan_type")
+            .and_then(|col| col.as_any().downcast_ref::<StringArray>())
+            .expect("EXPLAIN output should have a plan_type column");
+        let plans = batch
+            .column_by_name("plan")
+            .and_then(|col| col.as_any().downcast_ref::<StringArray>())
+            .expect("EXPLAIN output should have a plan column");
+        for (ty, text) in types.iter().zip(plans.iter()) {
+            if ty == Some("physical_plan") {
+                plan.push_str(text.unwrap_or_default());
+            }
+        }
+    }
+    assert!(!plan.is_empty(), "EXPLAIN output had no physical plan");
+    Ok(plan)
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use ella_common::Duration;
+use ella_engine::{config::EngineConfig, EllaConfig, EllaContext};
+
+const TOPICS: usize = 8;
+const SHARDS: usize = 3;
+
+/// Create topics `t0..t7`, each with several small shards.
+async fn setup() -> ella_engine::Result<(tempfile::TempDir, EllaContext)> {
+    let engine = EngineConfig::builder()
+        .compaction_concurrency(4)
+        .auto_compaction(false);
+    let config = EllaConfig::builder().engine_config(engine).build();
+    let (dir, ctx) = common::create(config).await?;
+    for i in 0..TOPICS {
+        let name = format!("t{}", i);
+        let topic = ctx
+            .create_topic(name.as_str(), common::topic(), false, false)
+            .await?;
+        for _ in 0..SHARDS {
+            common::publish(&topic, &[1, 2]).await?;
+            ctx.flush(name.as_str()).await?;
+        }
+    }
+    Ok((dir, ctx))
+}
+
+/// Assert that every topic has been compacted into a single shard without losing rows.
+async fn assert_compacted(ctx: &EllaContext) -> ella_engine::Result<()> {
+    for i in 0..TOPICS {
+        let versions = ctx.manifest_versions(format!("t{}", i).as_str()).await?;
+        let latest = versions
+            .last()
+            .expect("topic should have a manifest version");
+        assert_eq!(latest.shards, 1, "t{} was not compacted", i);
+        assert_eq!(latest.rows, SHARDS * 2);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn compact_every_topic_concurrently() -> ella_engine::Result<()> {
+    let (_dir, ctx) = setup().await?;
+    ctx.compact().await?;
+    assert_compacted(&ctx).await?;
+    ctx.shutdown().await
+}
+
+#[tokio::test]
+async fn overlapping_compactions_skip_busy_topics() -> ella_engine::Result<()> {
+    let (_dir, ctx) = setup().await?;
+    // A topic is only compacted by one of the calls at a time
+    tokio::try_join!(ctx.compact(), ctx.compact())?;
+    assert_compacted(&ctx).await?;
+    ctx.shutdown().await
+}
+
+/// Number of shards in the latest manifest version of `table`, after waiting for the
+/// background maintenance task to run a few times.
+async fn shards_after_maintenance(ctx: &EllaContext, table: &str) -> ella_engine::Result<usize> {
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    let versions = ctx.manifest_versions(table).await?;
+    Ok(versions.last().map_or(0, |v| v.shards))
+}
+
+#[tokio::test]
+async fn background_compaction_waits_for_min_files() -> ella_engine::Result<()> {
+    for (auto_compaction, compacted) in [(true, 1), (false, 3)] {
+        let engine = EngineConfig::builder()
+            .maintenance_interval(Duration::milliseconds(50))
+            .auto_compaction(auto_compaction)
+            .compaction_min_files(3);
+        let config = EllaConfig::builder().engine_config(engine).build();
+        let (_dir, ctx) = common::create(config).await?;
+        let topic = ctx.create_topic("t", common::topic(), false, false).await?;
+
+        for _ in 0..2 {
+            common::publish(&topic, &[1, 2]).await?;
+            ctx.flush("t").await?;
+        }
+        assert_eq!(shards_after_maintenance(&ctx, "t").await?, 2);
+
+        // The third small shard reaches the threshold
+        common::publish(&topic, &[1, 2]).await?;
+        ctx.flush("t").await?;
+        assert_eq!(
+            shards_after_maintenance(&ctx, "t").await?,
+            compacted,
+            "auto_compaction: {}",
+            auto_compaction
+        );
+        assert_eq!(common::count_rows(&ctx, "SELECT x FROM t").await?, 6);
+
+        ctx.shutdown().await?;
+    }
+    Ok(())
+}
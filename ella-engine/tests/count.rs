@@ -0,0 +1,84 @@
+mod common;
+
+use common::physical_plan;
+use datafusion::arrow::array::Int64Array;
+use ella_common::Time;
+use ella_engine::{config::EngineConfig, EllaConfig, EllaContext};
+use futures::{SinkExt, TryStreamExt};
+
+async fn count(ctx: &EllaContext, sql: &str) -> ella_engine::Result<i64> {
+    let batches = ctx
+        .query(sql)
+        .await?
+        .stream()
+        .await?
+        .into_inner()
+        .try_collect::<Vec<_>>()
+        .await?;
+    let counts = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .expect("COUNT(*) should return an Int64 column");
+    Ok(counts.value(0))
+}
+
+#[tokio::test]
+async fn unfiltered_count_uses_manifest_statistics() -> ella_engine::Result<()> {
+    let config = EllaConfig::builder()
+        .engine_config(EngineConfig::builder().auto_compaction(false))
+        .build();
+    let (_dir, ctx) = common::create(config).await?;
+    let topic = ctx.create_topic("t", common::topic(), false, false).await?;
+    let values = (0..10_000).collect::<Vec<_>>();
+    common::publish(&topic, &values).await?;
+    ctx.flush("t").await?;
+    common::publish(&topic, &values[..500]).await?;
+    ctx.flush("t").await?;
+
+    let plan = physical_plan(&ctx, "SELECT COUNT(*) FROM t").await?;
+    assert!(
+        !plan.contains("TopicExec"),
+        "expected no scan in plan:\n{}",
+        plan
+    );
+    assert_eq!(count(&ctx, "SELECT COUNT(*) FROM t").await?, 10_500);
+
+    // Filtered counts still scan
+    let plan = physical_plan(&ctx, "SELECT COUNT(*) FROM t WHERE x < 100").await?;
+    assert!(
+        plan.contains("TopicExec"),
+        "expected scan in plan:\n{}",
+        plan
+    );
+    assert_eq!(
+        count(&ctx, "SELECT COUNT(*) FROM t WHERE x < 100").await?,
+        200
+    );
+
+    ctx.shutdown().await
+}
+
+#[tokio::test]
+async fn count_with_active_publisher_scans() -> ella_engine::Result<()> {
+    let (_dir, ctx) = common::create(EllaConfig::default()).await?;
+    let topic = ctx.create_topic("t", common::topic(), false, false).await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    ctx.flush("t").await?;
+
+    // Rows from a live publisher aren't in the manifest, so the count can't be precomputed
+    let mut rows = topic.publish().rows::<(Time, i32)>(1)?;
+    rows.send((ella_common::now(), 4)).await?;
+    let plan = physical_plan(&ctx, "SELECT COUNT(*) FROM t").await?;
+    assert!(
+        plan.contains("TopicExec"),
+        "expected scan in plan:\n{}",
+        plan
+    );
+
+    rows.close().await?;
+    ctx.flush("t").await?;
+    assert_eq!(count(&ctx, "SELECT COUNT(*) FROM t").await?, 4);
+
+    ctx.shutdown().await
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use std::path::Path;
+
+use ella_common::Duration;
+use ella_engine::table::topic::ImportOptions;
+
+/// Number of parquet files anywhere under `dir`.
+fn parquet_files(dir: &Path) -> usize {
+    std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .map(|path| {
+            if path.is_dir() {
+                parquet_files(&path)
+            } else {
+                usize::from(path.extension().map_or(false, |ext| ext == "parquet"))
+            }
+        })
+        .sum()
+}
+
+#[tokio::test]
+async fn imported_files_are_never_deleted() -> ella_engine::Result<()> {
+    // Write a parquet file with another datastore
+    let (src_tmp, src) = common::create(Default::default()).await?;
+    let topic = src.create_topic("t", common::topic(), false, false).await?;
+    let prefix = topic.path().to_string();
+    common::publish(&topic, &[1, 2, 3]).await?;
+    src.shutdown().await?;
+    let src_dir = src_tmp.path();
+    let imported = parquet_files(src_dir);
+    assert!(imported > 0);
+
+    let (_dir, ctx) = common::create(Default::default()).await?;
+    let topic = ctx
+        .import_parquet("t", &prefix, ImportOptions::new(common::topic()))
+        .await?;
+    // Add shards that are small enough to be compacted with the imported file
+    for values in [[4, 5], [6, 7]] {
+        common::publish(&topic, &values).await?;
+        ctx.flush("t").await?;
+    }
+
+    ctx.compact().await?;
+    ctx.vacuum(Duration::ZERO).await?;
+    assert_eq!(parquet_files(src_dir), imported);
+    assert_eq!(common::count_rows(&ctx, "SELECT x FROM t").await?, 7);
+
+    ctx.shutdown().await
+}
+
+#[tokio::test]
+async fn appending_import_writes_to_the_dataset() -> ella_engine::Result<()> {
+    let (src_tmp, src) = common::create(Default::default()).await?;
+    let topic = src.create_topic("t", common::topic(), false, false).await?;
+    let prefix = topic.path().to_string();
+    common::publish(&topic, &[1, 2, 3]).await?;
+    src.shutdown().await?;
+    let src_dir = src_tmp.path();
+    let imported = parquet_files(src_dir);
+    assert!(imported > 0);
+
+    let (_dir, ctx) = common::create(Default::default()).await?;
+    let topic = ctx
+        .import_parquet(
+            "t",
+            &prefix,
+            ImportOptions::new(common::topic()).append(true),
+        )
+        .await?;
+    common::publish(&topic, &[4, 5]).await?;
+    ctx.flush("t").await?;
+
+    // New shards are written next to the existing files
+    assert!(parquet_files(src_dir) > imported);
+    assert_eq!(common::count_rows(&ctx, "SELECT x FROM t").await?, 5);
+    assert_eq!(
+        common::count_rows(&ctx, "SELECT x FROM t WHERE x IN (1, 5)").await?,
+        2
+    );
+
+    ctx.shutdown().await
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use std::path::Path;
+
+use ella_common::TensorType;
+use ella_engine::{
+    engine::IngestOptions,
+    table::{info::TopicBuilder, ColumnBuilder},
+    EllaContext,
+};
+
+// Column `y` only contains floats after the first two rows
+const CSV: &str = "time,y
+2023-01-01T00:00:00Z,1
+2023-01-01T00:00:01Z,2
+2023-01-01T00:00:02Z,2.5
+";
+
+fn write_file(dir: &Path, name: &str) -> String {
+    let path = dir.join(name);
+    std::fs::write(&path, CSV).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+async fn create_topic(ctx: &EllaContext) -> ella_engine::Result<()> {
+    let topic = TopicBuilder::new().column(ColumnBuilder::new("y", TensorType::Float64));
+    ctx.create_topic("t", topic, false, false).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn ingest_infers_types_from_sample() -> ella_engine::Result<()> {
+    let (dir, ctx) = common::create(Default::default()).await?;
+    let ctx = ctx.read_your_writes(true);
+    create_topic(&ctx).await?;
+    let path = write_file(dir.path(), "data.csv");
+
+    // Too few rows to see the float values
+    let res = ctx
+        .ingest_file("t", &path, IngestOptions::csv().infer_rows(2))
+        .await;
+    assert!(res.is_err());
+
+    let rows = ctx
+        .ingest_file("t", &path, IngestOptions::csv().infer_all())
+        .await?;
+    assert_eq!(rows, 3);
+    assert_eq!(
+        common::count_rows(&ctx, "SELECT * FROM t WHERE y = 2.5").await?,
+        1
+    );
+
+    ctx.shutdown().await
+}
+
+#[tokio::test]
+async fn ingest_applies_type_overrides() -> ella_engine::Result<()> {
+    let (dir, ctx) = common::create(Default::default()).await?;
+    let ctx = ctx.read_your_writes(true);
+    create_topic(&ctx).await?;
+    let path = write_file(dir.path(), "data.csv");
+
+    let options = IngestOptions::csv()
+        .infer_rows(2)
+        .column_type("y", TensorType::Float64);
+    assert_eq!(ctx.ingest_file("t", &path, options).await?, 3);
+
+    ctx.shutdown().await
+}
+
+#[tokio::test]
+async fn ingest_rejects_unmatched_extension() -> ella_engine::Result<()> {
+    let (dir, ctx) = common::create(Default::default()).await?;
+    create_topic(&ctx).await?;
+    let path = write_file(dir.path(), "data.txt");
+
+    let err = ctx
+        .ingest_file("t", &path, IngestOptions::csv())
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ella_engine::Error::Engine(ella_engine::EngineError::InvalidImport(_))
+        ),
+        "unexpected error {:?}",
+        err
+    );
+
+    ctx.shutdown().await
+}
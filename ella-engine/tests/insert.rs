@@ -0,0 +1,51 @@
+mod common;
+
+use datafusion::arrow::{array::Int64Array, record_batch::RecordBatch};
+use ella_common::TensorType;
+use ella_engine::table::{info::TopicBuilder, ColumnBuilder};
+use futures::TryStreamExt;
+
+#[tokio::test]
+async fn insert_select_derives_a_topic() -> ella_engine::Result<()> {
+    let (_dir, ctx) = common::create(Default::default()).await?;
+    let ctx = ctx.read_your_writes(true);
+    let src = ctx
+        .create_topic("src", common::topic(), false, false)
+        .await?;
+    common::publish(&src, &[1, 2, 3]).await?;
+    let info = TopicBuilder::new().column(ColumnBuilder::new("y", TensorType::Int64));
+    ctx.create_topic("dst", info, false, false).await?;
+
+    // The Int32 expression is coerced to the destination's Int64 column
+    ctx.execute("INSERT INTO dst SELECT time + INTERVAL '1 hour', x * 10 FROM src WHERE x > 1")
+        .await?;
+
+    let batches = ctx
+        .query("SELECT y FROM dst ORDER BY y")
+        .await?
+        .stream()
+        .await?
+        .into_inner()
+        .try_collect::<Vec<RecordBatch>>()
+        .await?;
+    let values = batches
+        .iter()
+        .flat_map(|batch| {
+            batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int64Array>()
+                .expect("y should be an Int64 column")
+                .values()
+                .to_vec()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(values, [20, 30]);
+
+    // The destination's time index comes from the projected column
+    let sql = "SELECT * FROM dst JOIN src ON dst.time = src.time + INTERVAL '1 hour' \
+               WHERE dst.y = src.x * 10";
+    assert_eq!(common::count_rows(&ctx, sql).await?, 2);
+
+    ctx.shutdown().await
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use ella_engine::{config::EngineConfig, EllaConfig};
+
+#[tokio::test]
+async fn versions_advance_on_flush_and_compaction() -> ella_engine::Result<()> {
+    let config = EllaConfig::builder()
+        .engine_config(EngineConfig::builder().auto_compaction(false))
+        .build();
+    let (_dir, ctx) = common::create(config).await?;
+    let topic = ctx.create_topic("t", common::topic(), false, false).await?;
+
+    common::publish(&topic, &[1, 2]).await?;
+    ctx.flush("t").await?;
+    let first = ctx.manifest_versions("t").await?;
+    common::publish(&topic, &[3, 4]).await?;
+    ctx.flush("t").await?;
+    let second = ctx.manifest_versions("t").await?;
+    assert!(second.len() > first.len());
+    assert_eq!(second.last().unwrap().shards, 2);
+
+    ctx.compact().await?;
+    let compacted = ctx.manifest_versions("t").await?;
+    assert!(compacted.len() > second.len());
+    let latest = compacted.last().unwrap();
+    assert_eq!((latest.shards, latest.rows), (1, 4));
+
+    // The shards of an old version can still be listed
+    let old = first.last().unwrap();
+    let shards = ctx.manifest_at("t", old.version).await?;
+    assert_eq!(shards.len(), old.shards);
+    assert_eq!(shards.iter().filter_map(|s| s.rows).sum::<usize>(), 2);
+
+    ctx.shutdown().await
+}
+
+#[tokio::test]
+async fn compaction_is_one_version_despite_other_tables() -> ella_engine::Result<()> {
+    let engine = EngineConfig::builder()
+        .auto_compaction(false)
+        .compaction_concurrency(4);
+    let config = EllaConfig::builder().engine_config(engine).build();
+    let (_dir, ctx) = common::create(config).await?;
+    let tables = ["a", "b", "c", "d"];
+    for name in tables {
+        let topic = ctx
+            .create_topic(name, common::topic(), false, false)
+            .await?;
+        for _ in 0..3 {
+            common::publish(&topic, &[1, 2]).await?;
+            ctx.flush(name).await?;
+        }
+    }
+
+    // The tables are compacted concurrently, so their transactions are interleaved
+    ctx.compact().await?;
+    for name in tables {
+        let versions = ctx.manifest_versions(name).await?;
+        // Intermediate compaction states would include both the sources and the destination
+        assert!(
+            versions.iter().all(|v| v.rows <= 6),
+            "{}: {:?}",
+            name,
+            versions
+        );
+        assert_eq!(versions.last().unwrap().shards, 1);
+    }
+
+    ctx.shutdown().await
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use ella_engine::{config::EngineConfig, EllaConfig};
+
+#[tokio::test]
+async fn auto_create_namespaces_on_write() -> ella_engine::Result<()> {
+    let config = EllaConfig::builder()
+        .engine_config(EngineConfig::builder().auto_create_namespaces(true))
+        .build();
+    let (_dir, ctx) = common::create(config).await?;
+
+    // The schema doesn't exist until a table is created in it
+    let ctx = ctx.use_schema("scratch")?;
+    assert!(ctx
+        .cluster()
+        .catalog(ctx.default_catalog())
+        .unwrap()
+        .schema("scratch")
+        .is_none());
+
+    ctx.create_topic("t", common::topic(), false, false).await?;
+    assert!(ctx.table("scratch.t").is_some());
+    ctx.create_topic("other.more.t", common::topic(), false, false)
+        .await?;
+    assert!(ctx.cluster().catalog("other").is_some());
+
+    ctx.shutdown().await
+}
+
+#[tokio::test]
+async fn missing_namespaces_are_errors_by_default() -> ella_engine::Result<()> {
+    let (_dir, ctx) = common::create(Default::default()).await?;
+
+    assert!(ctx.clone().use_catalog("missing").is_err());
+    assert!(ctx.clone().use_schema("missing").is_err());
+    assert!(ctx
+        .create_topic("missing.t", common::topic(), false, false)
+        .await
+        .is_err());
+
+    ctx.shutdown().await
+}
@@ -0,0 +1,33 @@
+mod common;
+
+use ella_common::{TensorType, Time};
+use ella_engine::table::{info::TopicBuilder, ColumnBuilder, NonFinitePolicy, TableConfig};
+use futures::SinkExt;
+
+#[tokio::test]
+async fn masked_values_read_back_as_null() -> ella_engine::Result<()> {
+    let (_dir, ctx) = common::create(Default::default()).await?;
+    let info = TopicBuilder::new()
+        .column(ColumnBuilder::new("v", TensorType::Float64))
+        .config(TableConfig::default().with_non_finite(NonFinitePolicy::Mask));
+    let topic = ctx.create_topic("t", info, false, false).await?;
+
+    let mut rows = topic.publish().rows::<(Time, f64)>(1)?;
+    for v in [1.0, f64::NAN, f64::INFINITY, 2.0] {
+        rows.send((ella_common::now(), v)).await?;
+    }
+    rows.close().await?;
+    ctx.flush("t").await?;
+
+    assert_eq!(common::count_rows(&ctx, "SELECT v FROM t").await?, 4);
+    assert_eq!(
+        common::count_rows(&ctx, "SELECT v FROM t WHERE v IS NULL").await?,
+        2
+    );
+    assert_eq!(
+        common::count_rows(&ctx, "SELECT v FROM t WHERE v IN (1.0, 2.0)").await?,
+        2
+    );
+
+    ctx.shutdown().await
+}
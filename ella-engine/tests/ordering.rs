@@ -0,0 +1,63 @@
+mod common;
+
+use common::physical_plan;
+use ella_engine::EllaConfig;
+
+#[tokio::test]
+async fn order_by_time_index_skips_sort() -> ella_engine::Result<()> {
+    let (_dir, ctx) = common::create(EllaConfig::default()).await?;
+    let topic = ctx.create_topic("t", common::topic(), false, false).await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    ctx.flush("t").await?;
+    common::publish(&topic, &[4, 5]).await?;
+    ctx.flush("t").await?;
+
+    let plan = physical_plan(&ctx, "SELECT time, x FROM t ORDER BY time ASC").await?;
+    assert!(
+        !plan.contains("SortExec"),
+        "unexpected sort in plan:\n{}",
+        plan
+    );
+
+    // Sorting by a column other than the index still needs a sort
+    let plan = physical_plan(&ctx, "SELECT time, x FROM t ORDER BY x").await?;
+    assert!(
+        plan.contains("SortExec"),
+        "expected sort in plan:\n{}",
+        plan
+    );
+
+    let rows = ctx
+        .query("SELECT x FROM t ORDER BY time ASC")
+        .await?
+        .execute()
+        .await?;
+    assert_eq!(rows.nrows(), 5);
+
+    ctx.shutdown().await
+}
+
+#[tokio::test]
+async fn properties_report_order_by_columns() -> ella_engine::Result<()> {
+    let (_dir, ctx) = common::create(EllaConfig::default()).await?;
+    ctx.create_topic("t", common::topic(), false, false).await?;
+
+    let props = ctx
+        .query("SELECT time, x FROM t ORDER BY x DESC, time LIMIT 2")
+        .await?
+        .properties();
+    let ordering = props
+        .ordering()
+        .iter()
+        .map(|index| (index.column.as_str(), index.ascending))
+        .collect::<Vec<_>>();
+    assert_eq!(ordering, [("x", false), ("time", true)]);
+    assert_eq!(props.max_rows(), Some(2));
+    assert_eq!(props.arrow_schema().fields().len(), 2);
+
+    let props = ctx.query("SELECT time, x FROM t").await?.properties();
+    assert!(!props.is_ordered());
+    assert_eq!(props.max_rows(), None);
+
+    ctx.shutdown().await
+}
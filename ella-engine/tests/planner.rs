@@ -0,0 +1,40 @@
+mod common;
+
+use common::physical_plan;
+use ella_engine::{config::EngineConfig, EllaConfig};
+
+/// Whether the physical plan of a grouped query over a topic with several shards repartitions.
+async fn repartitions(engine: EngineConfig) -> ella_engine::Result<bool> {
+    let config = EllaConfig::builder().engine_config(engine).build();
+    let (_dir, ctx) = common::create(config).await?;
+    let topic = ctx.create_topic("t", common::topic(), false, false).await?;
+    for values in [[1, 2], [3, 4]] {
+        common::publish(&topic, &values).await?;
+        ctx.flush("t").await?;
+    }
+
+    let sql = "SELECT x, COUNT(*) FROM t GROUP BY x";
+    let plan = physical_plan(&ctx, sql).await?;
+    assert_eq!(common::count_rows(&ctx, sql).await?, 4);
+    ctx.shutdown().await?;
+    Ok(plan.contains("RepartitionExec"))
+}
+
+#[tokio::test]
+async fn repartitioning_can_be_disabled() -> ella_engine::Result<()> {
+    let enabled = EngineConfig::builder()
+        .target_partitions(4)
+        .round_robin_repartition(true)
+        .repartition_aggregations(true)
+        .build();
+    assert!(repartitions(enabled).await?);
+
+    let disabled = EngineConfig::builder()
+        .target_partitions(4)
+        .round_robin_repartition(false)
+        .repartition_joins(false)
+        .repartition_aggregations(false)
+        .build();
+    assert!(!repartitions(disabled).await?);
+    Ok(())
+}
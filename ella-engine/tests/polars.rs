@@ -0,0 +1,55 @@
+#![cfg(feature = "polars")]
+
+mod common;
+
+use common::{create, publish, topic};
+use ella_common::TensorType;
+use ella_engine::{
+    table::{info::TopicBuilder, ColumnBuilder},
+    EllaConfig,
+};
+
+#[tokio::test]
+async fn collect_query_into_dataframe() -> ella_engine::Result<()> {
+    let (_dir, ctx) = create(EllaConfig::default()).await?;
+    let t = ctx.create_topic("values", topic(), false, false).await?;
+    publish(&t, &[1, 2, 3, 4, 5]).await?;
+    ctx.flush("values").await?;
+
+    let df = ctx
+        .query("SELECT time, x FROM values WHERE x > 1")
+        .await?
+        .collect_polars()
+        .await?;
+    assert_eq!(df.shape(), (4, 2));
+    let names = df.get_column_names();
+    assert_eq!(names, ["time", "x"]);
+
+    // An empty result still has the query's columns
+    let df = ctx
+        .query("SELECT x FROM values WHERE x > 10")
+        .await?
+        .collect_polars()
+        .await?;
+    assert_eq!(df.shape(), (0, 1));
+
+    ctx.shutdown().await
+}
+
+#[tokio::test]
+async fn tensor_columns_are_rejected() -> ella_engine::Result<()> {
+    let (_dir, ctx) = create(EllaConfig::default()).await?;
+    let builder =
+        TopicBuilder::new().column(ColumnBuilder::new("v", TensorType::Float32).row_shape(3));
+    ctx.create_topic("tensors", builder, false, false).await?;
+
+    let err = ctx
+        .query("SELECT v FROM tensors")
+        .await?
+        .collect_polars()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("tensor column"), "{}", err);
+
+    ctx.shutdown().await
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use ella_engine::{config::EngineConfig, EllaConfig};
+use futures::TryStreamExt;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn queued_queries_complete_in_order() -> ella_engine::Result<()> {
+    let config = EllaConfig::builder()
+        .engine_config(EngineConfig::builder().max_concurrent_queries(1))
+        .build();
+    let (_dir, ctx) = common::create(config).await?;
+    let topic = ctx.create_topic("t", common::topic(), false, false).await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    ctx.flush("t").await?;
+
+    // The first query holds the only slot until its results are read
+    let mut first = ctx
+        .query("SELECT x FROM t")
+        .await?
+        .stream()
+        .await?
+        .into_inner();
+
+    let completed = Arc::new(Mutex::new(Vec::new()));
+    let mut waiting = Vec::new();
+    for i in 0..4 {
+        let (ctx, completed) = (ctx.clone(), completed.clone());
+        waiting.push(tokio::spawn(async move {
+            let rows = common::count_rows(&ctx, "SELECT x FROM t").await?;
+            completed.lock().unwrap().push(i);
+            Ok::<_, ella_engine::Error>(rows)
+        }));
+        // Give each query time to join the queue before the next one arrives
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert!(completed.lock().unwrap().is_empty());
+
+    // Reading the first query to the end releases its slot even though the stream is still held
+    let mut rows = 0;
+    while let Some(batch) = first.try_next().await? {
+        rows += batch.num_rows();
+    }
+    assert_eq!(rows, 3);
+
+    for query in waiting {
+        let rows = query.await.expect("query task panicked")?;
+        assert_eq!(rows, 3);
+    }
+    assert_eq!(*completed.lock().unwrap(), vec![0, 1, 2, 3]);
+    drop(first);
+
+    ctx.shutdown().await
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use ella_engine::{EngineError, Error};
+
+fn assert_read_only<T: std::fmt::Debug>(result: ella_engine::Result<T>) {
+    match result {
+        Err(Error::Engine(EngineError::ReadOnly)) => {}
+        res => panic!("expected read-only error, got {:?}", res),
+    }
+}
+
+#[tokio::test]
+async fn read_only_context_rejects_writes() -> ella_engine::Result<()> {
+    let (_dir, ctx) = common::create(Default::default()).await?;
+    let topic = ctx.create_topic("t", common::topic(), false, false).await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    ctx.flush("t").await?;
+
+    let reader = ctx.clone().read_only(true);
+    assert_read_only(
+        reader
+            .create_topic("u", common::topic(), false, false)
+            .await,
+    );
+    assert_read_only(reader.execute("INSERT INTO t SELECT * FROM t").await);
+    assert_read_only(reader.execute("DROP TABLE t").await);
+    assert_read_only(reader.compact().await);
+    assert_eq!(common::count_rows(&reader, "SELECT x FROM t").await?, 3);
+
+    // The flag can't be cleared once set
+    let writer = reader.read_only(false);
+    assert_read_only(writer.execute("DROP TABLE t").await);
+    // Other contexts are unaffected
+    ctx.execute("DROP TABLE t").await?;
+
+    ctx.shutdown().await
+}
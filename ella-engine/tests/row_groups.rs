@@ -0,0 +1,39 @@
+mod common;
+
+use std::fs::File;
+
+use datafusion::parquet::file::reader::{FileReader, SerializedFileReader};
+use ella_engine::{EllaContext, TableConfig};
+use url::Url;
+
+/// Number of row groups in each shard file of `table`.
+async fn row_groups(ctx: &EllaContext, table: &str) -> ella_engine::Result<Vec<usize>> {
+    let version = ctx.manifest_versions(table).await?.last().unwrap().version;
+    let mut out = Vec::new();
+    for shard in ctx.manifest_at(table, version).await? {
+        let path = AsRef::<Url>::as_ref(&shard.path)
+            .to_file_path()
+            .expect("datastore should be on the local filesystem");
+        let reader = SerializedFileReader::new(File::open(path)?)?;
+        out.push(reader.metadata().num_row_groups());
+    }
+    Ok(out)
+}
+
+#[tokio::test]
+async fn row_group_size_is_configurable() -> ella_engine::Result<()> {
+    let (_dir, ctx) = common::create(Default::default()).await?;
+    let values = (0..10).collect::<Vec<_>>();
+    for (name, rows) in [("small", 2), ("large", 100)] {
+        let info = common::topic().config(TableConfig::default().with_row_group_size(rows));
+        let topic = ctx.create_topic(name, info, false, false).await?;
+        common::publish(&topic, &values).await?;
+        ctx.flush(name).await?;
+    }
+
+    assert_eq!(row_groups(&ctx, "small").await?, [5]);
+    assert_eq!(row_groups(&ctx, "large").await?, [1]);
+    assert_eq!(common::count_rows(&ctx, "SELECT x FROM small").await?, 10);
+
+    ctx.shutdown().await
+}
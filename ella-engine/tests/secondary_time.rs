@@ -0,0 +1,60 @@
+mod common;
+
+use datafusion::arrow::{array::Int32Array, record_batch::RecordBatch};
+use ella_common::{Duration, Time};
+use ella_engine::EllaContext;
+use futures::{SinkExt, TryStreamExt};
+
+/// Values of the `Int32` column `x` in the results of `sql`.
+async fn values(ctx: &EllaContext, sql: &str) -> ella_engine::Result<Vec<i32>> {
+    let batches = ctx
+        .query(sql)
+        .await?
+        .stream()
+        .await?
+        .into_inner()
+        .try_collect::<Vec<RecordBatch>>()
+        .await?;
+    Ok(batches
+        .iter()
+        .flat_map(|batch| {
+            batch
+                .column_by_name("x")
+                .and_then(|col| col.as_any().downcast_ref::<Int32Array>())
+                .expect("results should have an Int32 column x")
+                .values()
+                .to_vec()
+        })
+        .collect())
+}
+
+#[tokio::test]
+async fn query_event_and_ingest_time() -> ella_engine::Result<()> {
+    let (_dir, ctx) = common::create(Default::default()).await?;
+    let info = common::topic().secondary_time("ingest_time");
+    let topic = ctx.create_topic("t", info, false, false).await?;
+
+    // The second row arrives long after it happened
+    let base = Time::from_timestamp(1_700_000_000_000_000_000);
+    let mut rows = topic.publish().rows::<(Time, Time, i32)>(1)?;
+    for (event, ingest, x) in [(0, 1, 1), (1, 30, 2), (2, 3, 3)] {
+        let event = base + Duration::seconds(event);
+        let ingest = base + Duration::seconds(ingest);
+        rows.send((event, ingest, x)).await?;
+    }
+    rows.close().await?;
+    ctx.flush("t").await?;
+
+    assert_eq!(
+        values(&ctx, "SELECT x FROM t ORDER BY time").await?,
+        [1, 2, 3]
+    );
+    assert_eq!(
+        values(&ctx, "SELECT x FROM t ORDER BY ingest_time").await?,
+        [1, 3, 2]
+    );
+    let late = "SELECT x FROM t WHERE ingest_time > time + INTERVAL '5 seconds'";
+    assert_eq!(values(&ctx, late).await?, [2]);
+
+    ctx.shutdown().await
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use datafusion::arrow::{array::Int32Array, record_batch::RecordBatch};
+use ella_engine::lazy::Lazy;
+use futures::TryStreamExt;
+
+/// Sorted values of the single `Int32` column returned by `lazy`.
+async fn values(lazy: Lazy) -> ella_engine::Result<Vec<i32>> {
+    let batches = lazy
+        .stream()
+        .await?
+        .into_inner()
+        .try_collect::<Vec<RecordBatch>>()
+        .await?;
+    let mut values = batches
+        .iter()
+        .flat_map(|batch| {
+            batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .expect("results should have an Int32 column")
+                .values()
+                .to_vec()
+        })
+        .collect::<Vec<_>>();
+    values.sort();
+    Ok(values)
+}
+
+#[tokio::test]
+async fn union_matches_sql() -> ella_engine::Result<()> {
+    let (_dir, ctx) = common::create(Default::default()).await?;
+    let ctx = ctx.read_your_writes(true);
+    let a = ctx.create_topic("a", common::topic(), false, false).await?;
+    common::publish(&a, &[1, 2, 3]).await?;
+    let b = ctx.create_topic("b", common::topic(), false, false).await?;
+    common::publish(&b, &[3, 4]).await?;
+
+    for (all, sql, rows) in [
+        (
+            true,
+            "SELECT x FROM a UNION ALL SELECT x FROM b",
+            &[1, 2, 3, 3, 4][..],
+        ),
+        (
+            false,
+            "SELECT x FROM a UNION SELECT x FROM b",
+            &[1, 2, 3, 4][..],
+        ),
+    ] {
+        let lazy = ctx
+            .query("SELECT x FROM a")
+            .await?
+            .union(ctx.query("SELECT x FROM b").await?, all)?;
+        let expected = values(ctx.query(sql).await?).await?;
+        assert_eq!(expected, rows);
+        assert_eq!(values(lazy).await?, expected, "{}", sql);
+    }
+
+    // Both sides must have the same number of columns
+    let result = ctx
+        .query("SELECT x FROM a")
+        .await?
+        .union(ctx.query("SELECT time, x FROM b").await?, true);
+    assert!(result.is_err());
+
+    ctx.shutdown().await
+}
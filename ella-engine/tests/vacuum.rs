@@ -0,0 +1,55 @@
+mod common;
+
+use std::path::PathBuf;
+
+use ella_common::Duration;
+use ella_engine::{config::EngineConfig, EllaConfig};
+use url::Url;
+
+/// The local file that `path` refers to.
+fn local(path: &ella_engine::Path) -> PathBuf {
+    AsRef::<Url>::as_ref(path)
+        .to_file_path()
+        .expect("datastore should be on the local filesystem")
+}
+
+#[tokio::test]
+async fn vacuum_deletes_orphaned_files() -> ella_engine::Result<()> {
+    let config = EllaConfig::builder()
+        .engine_config(EngineConfig::builder().auto_compaction(false))
+        .build();
+    let (_dir, ctx) = common::create(config).await?;
+    let topic = ctx.create_topic("t", common::topic(), false, false).await?;
+    for values in [[1, 2], [3, 4]] {
+        common::publish(&topic, &values).await?;
+        ctx.flush("t").await?;
+    }
+    let old = ctx.manifest_versions("t").await?.last().unwrap().version;
+    ctx.compact().await?;
+    let latest = ctx.manifest_versions("t").await?.last().unwrap().version;
+
+    let live = ctx.manifest_at("t", latest).await?;
+    assert_eq!(live.len(), 1);
+    let live = local(&live[0].path);
+    // Compaction deletes its sources, so put back one that the old version still refers to
+    let retained = local(&ctx.manifest_at("t", old).await?[0].path);
+    std::fs::write(&retained, b"retained")?;
+    let orphan = local(topic.path()).join("orphan.parquet");
+    std::fs::write(&orphan, b"orphaned file")?;
+
+    // Files modified more recently than the threshold are kept
+    let report = ctx.vacuum(Duration::hours(1)).await?;
+    assert_eq!((report.files, report.bytes), (0, 0));
+    assert!(orphan.exists());
+
+    // Modification times are compared in whole seconds
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    let report = ctx.vacuum(Duration::ZERO).await?;
+    assert_eq!((report.files, report.bytes), (1, b"orphaned file".len()));
+    assert!(!orphan.exists());
+    assert!(live.exists());
+    assert!(retained.exists());
+    assert_eq!(common::count_rows(&ctx, "SELECT x FROM t").await?, 4);
+
+    ctx.shutdown().await
+}
@@ -0,0 +1,33 @@
+mod common;
+
+#[tokio::test]
+async fn flush_persists_pending_writes() -> ella_engine::Result<()> {
+    let (_dir, ctx) = common::create(Default::default()).await?;
+    let topic = ctx.create_topic("t", common::topic(), false, false).await?;
+
+    common::publish(&topic, &[1, 2, 3]).await?;
+    assert!(ctx.has_pending_writes("t")?);
+
+    ctx.flush("t").await?;
+    assert!(!ctx.has_pending_writes("t")?);
+    assert_eq!(common::count_rows(&ctx, "SELECT x FROM t").await?, 3);
+
+    ctx.shutdown().await
+}
+
+#[tokio::test]
+async fn read_your_writes_sees_buffered_rows() -> ella_engine::Result<()> {
+    let (_dir, ctx) = common::create(Default::default()).await?;
+    let fresh = ctx.clone().read_your_writes(true);
+    assert!(fresh.state().read_your_writes());
+    // The option only applies to the context it was set on
+    assert!(!ctx.state().read_your_writes());
+
+    let topic = fresh
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    assert_eq!(common::count_rows(&fresh, "SELECT x FROM t").await?, 3);
+
+    ctx.shutdown().await
+}
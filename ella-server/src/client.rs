@@ -1,22 +1,38 @@
 mod backend;
+mod cancel;
+mod pool;
+mod prepared;
 mod publisher;
+mod retry;
+#[cfg(feature = "tls")]
+mod tls;
 
 use std::{
     fmt::Debug,
-    sync::{Arc, Mutex},
+    future::Future,
+    sync::{Arc, Mutex, RwLock},
 };
 
 use arrow_flight::{
+    decode::FlightRecordBatchStream,
     error::FlightError,
-    sql::{client::FlightSqlServiceClient, Any, Command},
+    flight_service_client::FlightServiceClient,
+    sql::{client::FlightSqlServiceClient, Any, Command, CommandGetDbSchemas, CommandGetTables},
+    Action, FlightData, FlightInfo,
+};
+use datafusion::arrow::{
+    array::{AsArray, StringArray},
+    datatypes::Schema as ArrowSchema,
+    record_batch::RecordBatch,
 };
 use ella_engine::{
     lazy::Lazy,
-    registry::{Id, SchemaRef, TableRef},
+    registry::{Id, SchemaRef, TableId, TableRef},
     table::info::TableInfo,
-    EllaConfig, Plan,
+    EllaConfig, EngineError, Plan,
 };
-use prost::Message;
+use futures::{Stream, TryStreamExt};
+use prost::{bytes::Bytes, Message};
 use tonic::{
     codegen::InterceptedService,
     metadata::{Ascii, MetadataValue},
@@ -26,42 +42,109 @@ use tonic::{
 
 use crate::{
     gen::{self, engine_service_client::EngineServiceClient},
+    server::TicketStatus,
     table::RemoteTable,
+    PublishError,
 };
 
 use self::backend::RemoteBackend;
+pub use self::cancel::CancelHandle;
+pub use self::pool::{EllaPool, PooledClient};
+pub use self::prepared::PreparedQuery;
 pub use self::publisher::FlightPublisher;
+use self::retry::Reconnect;
+pub use self::retry::RetryPolicy;
+#[cfg(feature = "tls")]
+pub use self::tls::TlsConfig;
 
 #[derive(Debug, Clone)]
 pub struct EllaClient {
     flight: FlightSqlServiceClient<Channel>,
+    // The flight SQL client discards status codes, which are needed to classify publish errors
+    put: FlightServiceClient<InterceptedService<Channel, BearerAuth>>,
     engine: EngineServiceClient<InterceptedService<Channel, BearerAuth>>,
     config: Arc<Mutex<EllaConfig>>,
+    auth: BearerAuth,
+    reconnect: Option<Arc<Reconnect>>,
 }
 
 impl EllaClient {
     pub async fn connect(channel: Channel) -> crate::Result<Self> {
+        Self::connect_with_credentials(channel, "", "").await
+    }
+
+    /// Connect to the TLS-secured server at `uri`.
+    #[cfg(feature = "tls")]
+    pub async fn connect_tls(uri: impl AsRef<str>, config: TlsConfig) -> crate::Result<Self> {
+        let uri: tonic::transport::Uri =
+            uri.as_ref()
+                .parse()
+                .map_err(|err: tonic::codegen::http::uri::InvalidUri| {
+                    crate::ClientError::InvalidUri(err.to_string())
+                })?;
+        let tls = config.to_tonic(&uri);
+        let channel = Channel::builder(uri).tls_config(tls)?.connect().await?;
+        Self::connect(channel).await
+    }
+
+    /// Connect to a server, reconnecting and retrying idempotent requests according to `policy`
+    /// if the connection is lost.
+    pub async fn connect_with(channel: Channel, policy: RetryPolicy) -> crate::Result<Self> {
+        Self::connect_with_credentials_and_policy(channel, "", "", policy).await
+    }
+
+    /// Connect to a server that requires a user name and password, reconnecting and retrying
+    /// idempotent requests according to `policy` if the connection is lost.
+    ///
+    /// The credentials are kept so the handshake can be repeated after reconnecting.
+    pub async fn connect_with_credentials_and_policy(
+        channel: Channel,
+        user: &str,
+        password: &str,
+        policy: RetryPolicy,
+    ) -> crate::Result<Self> {
+        let reconnect = Reconnect {
+            policy,
+            channel: channel.clone(),
+            user: user.to_string(),
+            password: password.to_string(),
+        };
+        let mut this = Self::connect_with_credentials(channel, user, password).await?;
+        this.reconnect = Some(Arc::new(reconnect));
+        Ok(this)
+    }
+
+    /// Connect to a server that requires a user name and password.
+    pub async fn connect_with_credentials(
+        channel: Channel,
+        user: &str,
+        password: &str,
+    ) -> crate::Result<Self> {
         let mut flight = FlightSqlServiceClient::new(channel.clone());
-        let token = flight.handshake("", "").await?;
+        let token = flight.handshake(user, password).await?;
         let token =
             String::from_utf8(token.into()).map_err(|_| crate::ClientError::InvalidToken)?;
         flight.set_token(token.clone());
 
         let auth = BearerAuth::try_new(&token)?;
-        let mut engine = EngineServiceClient::with_interceptor(channel, auth);
+        let put = FlightServiceClient::with_interceptor(channel.clone(), auth.clone());
+        let mut engine = EngineServiceClient::with_interceptor(channel, auth.clone());
 
         let resp = engine
             .get_config(gen::GetConfigReq {
                 scope: gen::ConfigScope::Connection.into(),
             })
             .await
-            .map_err(crate::ClientError::Server)?;
+            .map_err(crate::ClientError::from)?;
         let config = serde_json::from_slice(&resp.into_inner().config)?;
         let config = Arc::new(Mutex::new(config));
         Ok(Self {
             flight,
+            put,
             engine,
             config,
+            auth,
+            reconnect: None,
         })
     }
 
@@ -83,7 +166,7 @@ impl EllaClient {
             .engine
             .create_table(req)
             .await
-            .map_err(crate::ClientError::Server)?
+            .map_err(crate::ClientError::from)?
             .into_inner();
 
         Ok(RemoteTable::new(
@@ -96,52 +179,425 @@ impl EllaClient {
     }
 
     pub async fn get_table(&self, table: TableRef<'_>) -> crate::Result<Option<RemoteTable>> {
-        let mut this = self.clone();
-        let resp = this
-            .engine
-            .get_table(gen::TableRef::from(table))
-            .await
-            .map_err(crate::ClientError::Server)?
-            .into_inner();
-        Ok(match (&resp.table, &resp.info) {
-            (Some(table), Some(info)) => Some(RemoteTable::new(
-                table.clone().into(),
-                info.clone().try_into()?,
-                this,
-            )),
-            (None, None) => None,
-            (_, _) => panic!(
-                "expected empty or fully-populated response, got: {:?}",
-                resp
-            ),
+        let table = &table;
+        self.retry(|mut this| async move {
+            let resp = this
+                .engine
+                .get_table(gen::TableRef::from(table.clone()))
+                .await
+                .map_err(crate::ClientError::from)?
+                .into_inner();
+            Ok::<_, crate::Error>(match (&resp.table, &resp.info) {
+                (Some(table), Some(info)) => Some(RemoteTable::new(
+                    table.clone().into(),
+                    info.clone().try_into()?,
+                    this,
+                )),
+                (None, None) => None,
+                (_, _) => panic!(
+                    "expected empty or fully-populated response, got: {:?}",
+                    resp
+                ),
+            })
         })
+        .await
+    }
+
+    /// Open a publisher that streams record batches into the topic `table`.
+    ///
+    /// Returns an error if the table doesn't exist or is not a topic.
+    pub async fn publish(&self, table: TableRef<'_>) -> crate::Result<FlightPublisher> {
+        let name = table.to_string();
+        let table = self
+            .get_table(table)
+            .await?
+            .ok_or_else(|| EngineError::TableNotFound(name))?;
+        match table.info() {
+            TableInfo::Topic(_) => Ok(table.publish()),
+            TableInfo::View(_) => Err(EngineError::table_kind("topic", "view").into()),
+        }
     }
 
     pub async fn query<S: Into<String>>(&self, query: S) -> crate::Result<Lazy> {
+        Ok(self.start_query(query.into()).await?.0)
+    }
+
+    /// Start a query and return a handle that can cancel it while its results are read.
+    ///
+    /// Dropping the handle doesn't affect the query.
+    pub async fn query_cancelable<S: Into<String>>(
+        &self,
+        query: S,
+    ) -> crate::Result<(Lazy, CancelHandle)> {
+        let (lazy, tickets) = self.start_query(query.into()).await?;
+        Ok((lazy, CancelHandle::new(self.clone(), tickets)))
+    }
+
+    /// Start `query` and return it along with the tickets its executions send to the server.
+    async fn start_query(&self, query: String) -> crate::Result<(Lazy, Arc<Mutex<Vec<Bytes>>>)> {
+        let query = &query;
+        self.retry(|mut this| async move {
+            let info = this.flight.execute(query.clone(), None).await?;
+            if info.endpoint.is_empty() {
+                return Err(crate::ClientError::MissingEndpoint.into());
+            }
+            // Each endpoint refers to a partition of the results
+            let mut handles = Vec::with_capacity(info.endpoint.len());
+            for endpoint in &info.endpoint {
+                let ticket = endpoint
+                    .ticket
+                    .as_ref()
+                    .ok_or(crate::ClientError::MissingTicket)?;
+                let msg = Any::decode(&*ticket.ticket)?;
+                match Command::try_from(msg)? {
+                    Command::TicketStatementQuery(ticket) => handles.push(ticket.statement_handle),
+                    cmd => {
+                        return Err(FlightError::DecodeError(format!(
+                            "unexpected response command: {:?}",
+                            cmd
+                        ))
+                        .into())
+                    }
+                }
+            }
+            // The handles only refer to the plan stored on the server, so fetch the plan itself
+            let raw_plan = this
+                .engine
+                .get_plan(gen::GetPlanReq {
+                    handle: handles[0].to_vec(),
+                })
+                .await
+                .map_err(crate::ClientError::from)?
+                .into_inner()
+                .plan;
+            let plan = Plan::from_bytes(&raw_plan)?;
+            let backend = RemoteBackend::from(this).with_handles(raw_plan, handles);
+            let tickets = backend.executed();
+            Ok::<_, crate::Error>((Lazy::new(plan, Arc::new(backend)), tickets))
+        })
+        .await
+    }
+
+    /// Prepare `query` on the server so it can be executed repeatedly with different parameters.
+    ///
+    /// Parameters are referred to in the query as `$1`, `$2`, etc.
+    pub async fn prepare<S: Into<String>>(&self, query: S) -> crate::Result<PreparedQuery> {
+        PreparedQuery::new(self.clone(), query.into()).await
+    }
+
+    /// Check whether `query` returns any rows.
+    ///
+    /// The query is limited to a single row, so the server stops reading as soon as a matching
+    /// row is found.
+    pub async fn exists<S: Into<String>>(&self, query: S) -> crate::Result<bool> {
+        let mut stream = self
+            .query(format!("SELECT 1 FROM ({}) AS q LIMIT 1", query.into()))
+            .await?
+            .stream()
+            .await?
+            .into_inner();
+        while let Some(batch) = stream.try_next().await? {
+            if batch.num_rows() > 0 {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Check that the server is reachable and the connection is still authorized.
+    pub async fn ping(&self) -> crate::Result<()> {
+        self.retry(|mut this| async move {
+            this.engine
+                .get_config(gen::GetConfigReq {
+                    scope: gen::ConfigScope::Connection.into(),
+                })
+                .await
+                .map_err(crate::ClientError::from)?;
+            Ok::<_, crate::Error>(())
+        })
+        .await
+    }
+
+    /// List the tables whose names match the SQL `LIKE` pattern `pattern`.
+    ///
+    /// `%` matches any sequence of characters and `_` matches any single character.
+    /// The pattern is applied by the server, so only matching tables are sent to the client.
+    pub async fn list_tables_matching(
+        &self,
+        pattern: impl Into<String>,
+    ) -> crate::Result<Vec<TableId<'static>>> {
+        let pattern: String = pattern.into();
+        let pattern = &pattern;
+        self.retry(|mut this| async move {
+            let info = this
+                .flight
+                .get_tables(CommandGetTables {
+                    catalog: None,
+                    db_schema_filter_pattern: None,
+                    table_name_filter_pattern: Some(pattern.clone()),
+                    table_types: Vec::new(),
+                    include_schema: false,
+                })
+                .await?;
+
+            let mut tables = Vec::new();
+            for batch in this.fetch_info(info).await? {
+                let catalogs = string_column(&batch, "catalog_name")?;
+                let schemas = string_column(&batch, "db_schema_name")?;
+                let names = string_column(&batch, "table_name")?;
+                for i in 0..batch.num_rows() {
+                    tables.push(TableId::from((
+                        catalogs.value(i).to_string(),
+                        schemas.value(i).to_string(),
+                        names.value(i).to_string(),
+                    )));
+                }
+            }
+            Ok::<_, crate::Error>(tables)
+        })
+        .await
+    }
+
+    /// List the catalogs whose names match the SQL `LIKE` pattern `pattern`.
+    pub async fn list_catalogs(&self, pattern: Option<&str>) -> crate::Result<Vec<Id<'static>>> {
+        self.retry(|mut this| async move {
+            let info = this.flight.get_catalogs().await?;
+
+            let mut catalogs = Vec::new();
+            for batch in this.fetch_info(info).await? {
+                let names = string_column(&batch, "catalog_name")?;
+                for i in 0..batch.num_rows() {
+                    // Flight SQL has no catalog filter, so the pattern is applied by the client
+                    if crate::server::matches_pattern(pattern, names.value(i)) {
+                        catalogs.push(Id::from(names.value(i).to_string()));
+                    }
+                }
+            }
+            Ok::<_, crate::Error>(catalogs)
+        })
+        .await
+    }
+
+    /// List the schemas in `catalog` whose names match the SQL `LIKE` pattern `pattern`.
+    pub async fn list_schemas<'a>(
+        &self,
+        catalog: impl Into<Id<'a>>,
+        pattern: Option<&str>,
+    ) -> crate::Result<Vec<Id<'static>>> {
+        let catalog: Id<'a> = catalog.into();
+        let catalog = &catalog;
+        self.retry(|mut this| async move {
+            let info = this
+                .flight
+                .get_db_schemas(CommandGetDbSchemas {
+                    catalog: Some(catalog.to_string()),
+                    db_schema_filter_pattern: pattern.map(str::to_string),
+                })
+                .await?;
+
+            let mut schemas = Vec::new();
+            for batch in this.fetch_info(info).await? {
+                let names = string_column(&batch, "db_schema_name")?;
+                for i in 0..batch.num_rows() {
+                    schemas.push(Id::from(names.value(i).to_string()));
+                }
+            }
+            Ok::<_, crate::Error>(schemas)
+        })
+        .await
+    }
+
+    /// List the tables in `schema` whose names match the SQL `LIKE` pattern `pattern`.
+    pub async fn list_tables<'a>(
+        &self,
+        schema: impl Into<SchemaRef<'a>>,
+        pattern: Option<&str>,
+    ) -> crate::Result<Vec<TableRef<'static>>> {
+        let schema: SchemaRef<'a> = schema.into();
+        let schema = schema.resolve(&self.default_catalog());
+        let schema = &schema;
+        self.retry(|mut this| async move {
+            let info = this
+                .flight
+                .get_tables(CommandGetTables {
+                    catalog: Some(schema.catalog.to_string()),
+                    db_schema_filter_pattern: Some(schema.schema.to_string()),
+                    table_name_filter_pattern: pattern.map(str::to_string),
+                    table_types: Vec::new(),
+                    include_schema: false,
+                })
+                .await?;
+
+            let mut tables = Vec::new();
+            for batch in this.fetch_info(info).await? {
+                let schemas = string_column(&batch, "db_schema_name")?;
+                let names = string_column(&batch, "table_name")?;
+                for i in 0..batch.num_rows() {
+                    // The schema name is used as a pattern, so `_` may match other schemas
+                    if schema.schema == Id::from(schemas.value(i)) {
+                        tables.push(TableRef {
+                            catalog: Some(schema.catalog.clone()),
+                            schema: Some(schema.schema.clone()),
+                            table: Id::from(names.value(i).to_string()),
+                        });
+                    }
+                }
+            }
+            Ok::<_, crate::Error>(tables)
+        })
+        .await
+    }
+
+    /// Fetch every endpoint of a metadata query and collect the resulting batches.
+    async fn fetch_info(&mut self, info: FlightInfo) -> crate::Result<Vec<RecordBatch>> {
+        let mut batches = Vec::new();
+        for endpoint in info.endpoint {
+            let ticket = endpoint.ticket.ok_or(crate::ClientError::MissingTicket)?;
+            let stream = self.flight.do_get(ticket).await?.map_err(FlightError::from);
+            batches.extend(
+                FlightRecordBatchStream::new_from_flight_data(stream)
+                    .try_collect::<Vec<_>>()
+                    .await?,
+            );
+        }
+        Ok(batches)
+    }
+
+    /// End the session on the server.
+    ///
+    /// The server releases the connection's statement handles and ticket statuses immediately,
+    /// and rejects the connection's token afterwards.
+    pub async fn close(mut self) -> crate::Result<()> {
+        self.engine
+            .logout(gen::Empty {})
+            .await
+            .map_err(crate::ClientError::from)?;
+        Ok(())
+    }
+
+    /// Append the rows of an Arrow IPC stream with schema `schema` to `table`.
+    pub(crate) async fn publish_ipc(
+        &self,
+        table: &TableId<'_>,
+        schema: &ArrowSchema,
+        data: Bytes,
+    ) -> crate::Result<()> {
         let mut this = self.clone();
+        let header = FlightData::new().with_descriptor(publisher::insert_descriptor(table));
+        let messages = publisher::ipc_flight_data(data, schema)?;
+        let stream = futures::stream::iter(std::iter::once(header).chain(messages));
+        this.do_put(stream).await
+    }
+
+    /// Run an idempotent request, re-establishing the session and retrying it on connection
+    /// errors if the client has a retry policy.
+    async fn retry<T, F, Fut>(&self, mut request: F) -> crate::Result<T>
+    where
+        F: FnMut(Self) -> Fut,
+        Fut: Future<Output = crate::Result<T>>,
+    {
+        let reconnect = match &self.reconnect {
+            Some(reconnect) => reconnect,
+            None => return request(self.clone()).await,
+        };
+        let mut attempt = 0;
+        loop {
+            // Another clone may have refreshed the shared token since this client was created
+            let mut this = self.clone();
+            this.flight.set_token(self.auth.token());
+            match request(this).await {
+                Err(error)
+                    if attempt < reconnect.policy.max_retries() && retry::is_retryable(&error) =>
+                {
+                    tracing::debug!(?error, attempt, "retrying request after connection error");
+                    tokio::time::sleep(reconnect.policy.backoff(attempt)).await;
+                    attempt += 1;
+                    if let Err(error) = self.refresh_session(reconnect).await {
+                        tracing::debug!(?error, "failed to re-establish session");
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Repeat the handshake to get a new token and restore the connection's settings.
+    async fn refresh_session(&self, reconnect: &Reconnect) -> crate::Result<()> {
+        let mut flight = FlightSqlServiceClient::new(reconnect.channel.clone());
+        let token = flight
+            .handshake(&reconnect.user, &reconnect.password)
+            .await?;
+        let token =
+            String::from_utf8(token.into()).map_err(|_| crate::ClientError::InvalidToken)?;
+        self.auth.set_token(&token)?;
+
+        // Connection-scoped settings are lost along with the server-side session
+        let config = serde_json::to_vec(&self.config())?;
+        self.engine
+            .clone()
+            .set_config(gen::Config {
+                scope: gen::ConfigScope::Connection.into(),
+                config,
+            })
+            .await
+            .map_err(crate::ClientError::from)?;
+        Ok(())
+    }
 
-        let info = this.flight.execute(query.into(), None).await?;
-        let ticket = match info.endpoint.len() {
-            0 => Err(crate::ClientError::MissingEndpoint),
-            1 => info.endpoint[0]
-                .ticket
-                .as_ref()
-                .ok_or_else(|| crate::ClientError::MissingTicket),
-            _ => unimplemented!(),
-        }?;
-        let msg = Any::decode(&*ticket.ticket)?;
-        let raw_plan = match Command::try_from(msg)? {
-            Command::TicketStatementQuery(ticket) => ticket.statement_handle,
-            cmd => {
-                return Err(FlightError::DecodeError(format!(
-                    "unexpected response command: {:?}",
-                    cmd
-                ))
-                .into())
+    /// Run the Flight action `action` and return the body of its first result, if any.
+    pub(crate) async fn do_action(
+        &mut self,
+        action: &str,
+        body: Vec<u8>,
+    ) -> crate::Result<Option<Bytes>> {
+        let mut resp = self
+            .put
+            .do_action(Action {
+                r#type: action.to_string(),
+                body: body.into(),
+            })
+            .await
+            .map_err(crate::ClientError::from)?
+            .into_inner();
+        let result = resp.message().await.map_err(crate::ClientError::from)?;
+        Ok(result.map(|result| result.body))
+    }
+
+    /// Send a `do_put` request and wait for the server to acknowledge it.
+    pub(crate) async fn do_put<S>(&mut self, stream: S) -> crate::Result<()>
+    where
+        S: Stream<Item = FlightData> + Send + 'static,
+    {
+        let publish_err = |status| match crate::ClientError::from(status) {
+            crate::ClientError::Server(status) => {
+                crate::ClientError::Publish(PublishError::from(status))
             }
+            err => err,
         };
-        let plan = Plan::from_bytes(&raw_plan)?;
-        Ok(Lazy::new(plan, Arc::new(RemoteBackend::from(this))))
+        let mut resp = self
+            .put
+            .do_put(stream)
+            .await
+            .map_err(publish_err)?
+            .into_inner();
+        resp.message().await.map_err(publish_err)?;
+        Ok(())
+    }
+
+    /// Get the execution status of the query for `plan` on the server.
+    pub async fn ticket_status(&self, plan: &Plan) -> crate::Result<TicketStatus> {
+        self.retry(|mut this| async move {
+            let resp = this
+                .engine
+                .get_ticket_status(gen::TicketStatusReq {
+                    ticket: plan.to_bytes(),
+                })
+                .await
+                .map_err(crate::ClientError::from)?
+                .into_inner();
+            Ok::<_, crate::Error>(resp.into())
+        })
+        .await
     }
 
     pub fn config(&self) -> EllaConfig {
@@ -171,7 +627,7 @@ impl EllaClient {
                 config: raw_config,
             })
             .await
-            .map_err(crate::ClientError::Server)?;
+            .map_err(crate::ClientError::from)?;
         Ok(())
     }
 
@@ -207,7 +663,7 @@ impl EllaClient {
                 if_not_exists,
             })
             .await
-            .map_err(crate::ClientError::Server)?;
+            .map_err(crate::ClientError::from)?;
         Ok(())
     }
 
@@ -224,22 +680,98 @@ impl EllaClient {
                 if_not_exists,
             })
             .await
-            .map_err(crate::ClientError::Server)?;
+            .map_err(crate::ClientError::from)?;
+        Ok(())
+    }
+
+    /// Drop a table, deleting its data if it's a topic.
+    pub async fn drop_table(&mut self, table: TableRef<'_>, if_exists: bool) -> crate::Result<()> {
+        self.engine
+            .drop_table(gen::DropTableReq {
+                table: Some(table.into()),
+                if_exists,
+            })
+            .await
+            .map_err(crate::ClientError::from)?;
+        Ok(())
+    }
+
+    /// Drop a schema. Unless `cascade` is set the schema must not contain any tables.
+    pub async fn drop_schema<'a>(
+        &mut self,
+        schema: impl Into<SchemaRef<'a>>,
+        if_exists: bool,
+        cascade: bool,
+    ) -> crate::Result<()> {
+        let schema: SchemaRef<'a> = schema.into();
+        self.engine
+            .drop_schema(gen::DropSchemaReq {
+                catalog: schema.catalog.map(|c| c.to_string()),
+                schema: schema.schema.to_string(),
+                if_exists,
+                cascade,
+            })
+            .await
+            .map_err(crate::ClientError::from)?;
+        Ok(())
+    }
+
+    /// Drop a catalog. Unless `cascade` is set the catalog must not contain any schemas.
+    pub async fn drop_catalog<'a>(
+        &mut self,
+        catalog: impl Into<Id<'a>>,
+        if_exists: bool,
+        cascade: bool,
+    ) -> crate::Result<()> {
+        let catalog: Id<'a> = catalog.into();
+        self.engine
+            .drop_catalog(gen::DropCatalogReq {
+                catalog: catalog.to_string(),
+                if_exists,
+                cascade,
+            })
+            .await
+            .map_err(crate::ClientError::from)?;
         Ok(())
     }
 }
 
+fn string_column<'a>(batch: &'a RecordBatch, name: &str) -> crate::Result<&'a StringArray> {
+    batch
+        .column_by_name(name)
+        .and_then(|col| col.as_string_opt::<i32>())
+        .ok_or_else(|| {
+            FlightError::DecodeError(format!("missing string column {} in response", name)).into()
+        })
+}
+
+/// Bearer token shared by every clone of a client, so a refreshed token is used by all of them.
 #[derive(Debug, Clone)]
 struct BearerAuth {
-    payload: MetadataValue<Ascii>,
+    inner: Arc<RwLock<(String, MetadataValue<Ascii>)>>,
 }
 
 impl BearerAuth {
     fn try_new(token: &str) -> crate::Result<Self> {
+        Ok(Self {
+            inner: Arc::new(RwLock::new(Self::entry(token)?)),
+        })
+    }
+
+    fn entry(token: &str) -> crate::Result<(String, MetadataValue<Ascii>)> {
         let payload = format!("Bearer {token}")
             .parse()
             .map_err(|_| crate::ClientError::InvalidToken)?;
-        Ok(Self { payload })
+        Ok((token.to_string(), payload))
+    }
+
+    fn token(&self) -> String {
+        self.inner.read().unwrap().0.clone()
+    }
+
+    fn set_token(&self, token: &str) -> crate::Result<()> {
+        *self.inner.write().unwrap() = Self::entry(token)?;
+        Ok(())
     }
 }
 
@@ -250,7 +782,7 @@ impl Interceptor for BearerAuth {
     ) -> Result<tonic::Request<()>, tonic::Status> {
         request
             .metadata_mut()
-            .insert("authorization", self.payload.clone());
+            .insert("authorization", self.inner.read().unwrap().1.clone());
         Ok(request)
     }
 }
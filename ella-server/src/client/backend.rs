@@ -1,4 +1,8 @@
-use std::{fmt::Debug, sync::Arc, task::Poll};
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    task::Poll,
+};
 
 use arrow_flight::{
     decode::{DecodedPayload, FlightDataDecoder},
@@ -11,27 +15,68 @@ use datafusion::{
     datasource::provider_as_source,
     error::DataFusionError,
     logical_expr::LogicalPlanBuilder,
-    physical_plan::{RecordBatchStream, SendableRecordBatchStream},
+    physical_plan::{
+        stream::RecordBatchStreamAdapter, RecordBatchStream, SendableRecordBatchStream,
+    },
 };
 use ella_engine::{lazy::LazyBackend, registry::TableRef, table::info::ViewInfo, Plan};
 use futures::{Stream, StreamExt, TryStreamExt};
-use prost::Message;
+use prost::{bytes::Bytes, Message};
 
 use super::EllaClient;
 
 #[derive(Debug, Clone)]
-pub(crate) struct RemoteBackend(EllaClient);
+pub(crate) struct RemoteBackend {
+    client: EllaClient,
+    // Serialized plan and the server-side handles that refer to the partitions of its results.
+    // The server discards the plan once the handles are fetched, so they're only used once.
+    handles: Arc<Mutex<Option<(Vec<u8>, Vec<Bytes>)>>>,
+    // Tickets sent for the most recent execution, shared by every query derived from this one so
+    // that the running query can be cancelled
+    executed: Arc<Mutex<Vec<Bytes>>>,
+}
 
 impl From<EllaClient> for RemoteBackend {
-    fn from(value: EllaClient) -> Self {
-        Self(value)
+    fn from(client: EllaClient) -> Self {
+        Self {
+            client,
+            handles: Default::default(),
+            executed: Default::default(),
+        }
     }
 }
 
-#[tonic::async_trait]
-impl LazyBackend for RemoteBackend {
-    async fn stream(&self, plan: &Plan) -> crate::Result<SendableRecordBatchStream> {
-        let statement_handle = plan.to_bytes().into();
+impl RemoteBackend {
+    /// Send `handles` instead of the serialized plan when executing `raw_plan`.
+    ///
+    /// Each handle is fetched concurrently and the results are merged in no particular order.
+    /// Later executions send the serialized plan.
+    pub fn with_handles(self, raw_plan: Vec<u8>, handles: Vec<Bytes>) -> Self {
+        *self.executed.lock().unwrap() = handles.clone();
+        *self.handles.lock().unwrap() = Some((raw_plan, handles));
+        self
+    }
+
+    /// Tickets sent by the most recent execution of any query using this backend.
+    ///
+    /// Before the first execution these are the handles passed to
+    /// [`with_handles`](Self::with_handles).
+    pub fn executed(&self) -> Arc<Mutex<Vec<Bytes>>> {
+        self.executed.clone()
+    }
+
+    /// Take the handles registered for `raw_plan`, if they haven't been used yet.
+    fn take_handles(&self, raw_plan: &[u8]) -> Option<Vec<Bytes>> {
+        let mut handles = self.handles.lock().unwrap();
+        match &*handles {
+            Some((registered, _)) if registered == raw_plan => {
+                handles.take().map(|(_, handles)| handles)
+            }
+            _ => None,
+        }
+    }
+
+    async fn fetch(&self, statement_handle: Bytes) -> crate::Result<RemoteStream> {
         let ticket = Ticket {
             ticket: TicketStatementQuery { statement_handle }
                 .as_any()
@@ -39,13 +84,38 @@ impl LazyBackend for RemoteBackend {
                 .into(),
         };
         let stream = self
-            .0
+            .client
             .flight
             .clone()
             .do_get(ticket)
             .await?
             .map_err(FlightError::from);
-        Ok(Box::pin(RemoteStream::new(stream).await?))
+        Ok(RemoteStream::new(stream).await?)
+    }
+}
+
+#[tonic::async_trait]
+impl LazyBackend for RemoteBackend {
+    async fn stream(&self, plan: &Plan) -> crate::Result<SendableRecordBatchStream> {
+        let raw_plan = plan.to_bytes();
+        let handles = self
+            .take_handles(&raw_plan)
+            .unwrap_or_else(|| vec![raw_plan.into()]);
+        *self.executed.lock().unwrap() = handles.clone();
+        let mut streams =
+            futures::future::try_join_all(handles.into_iter().map(|handle| self.fetch(handle)))
+                .await?;
+        if streams.len() == 1 {
+            return Ok(Box::pin(streams.remove(0)));
+        }
+        let schema = streams
+            .first()
+            .map(|stream| stream.schema())
+            .unwrap_or_else(|| plan.arrow_schema());
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::select_all(streams),
+        )))
     }
 
     async fn create_view(
@@ -56,7 +126,7 @@ impl LazyBackend for RemoteBackend {
         or_replace: bool,
     ) -> crate::Result<Plan> {
         let table = self
-            .0
+            .client
             .clone()
             .create_table(table, info.into(), if_not_exists, or_replace)
             .await?;
@@ -0,0 +1,60 @@
+use std::sync::{Arc, Mutex};
+
+use arrow_flight::{
+    error::FlightError,
+    sql::{
+        action_cancel_query_result::CancelResult, ActionCancelQueryRequest,
+        ActionCancelQueryResult, Any, ProstMessageExt, TicketStatementQuery,
+    },
+    FlightEndpoint, FlightInfo, Ticket,
+};
+use prost::{bytes::Bytes, Message};
+
+use super::EllaClient;
+
+/// Handle used to cancel a query started by [`EllaClient::query_cancelable`].
+///
+/// The handle follows the query and every query derived from it, and cancels whichever was
+/// executed most recently.
+#[derive(Debug, Clone)]
+pub struct CancelHandle {
+    client: EllaClient,
+    tickets: Arc<Mutex<Vec<Bytes>>>,
+}
+
+impl CancelHandle {
+    pub(crate) fn new(client: EllaClient, tickets: Arc<Mutex<Vec<Bytes>>>) -> Self {
+        Self { client, tickets }
+    }
+
+    /// Ask the server to stop executing the query.
+    ///
+    /// Returns `false` if the query had already finished or couldn't be cancelled.
+    pub async fn cancel(&self) -> crate::Result<bool> {
+        let tickets = self.tickets.lock().unwrap().clone();
+        let info = tickets
+            .into_iter()
+            .fold(FlightInfo::new(), |info, statement_handle| {
+                let ticket = Ticket {
+                    ticket: TicketStatementQuery { statement_handle }
+                        .as_any()
+                        .encode_to_vec()
+                        .into(),
+                };
+                info.with_endpoint(FlightEndpoint::new().with_ticket(ticket))
+            });
+        let request = ActionCancelQueryRequest {
+            info: info.encode_to_vec().into(),
+        };
+        let body = self
+            .client
+            .clone()
+            .do_action("CancelQuery", request.as_any().encode_to_vec())
+            .await?
+            .ok_or_else(|| FlightError::protocol("no response to CancelQuery"))?;
+        let result = Any::decode(&*body)?
+            .unpack::<ActionCancelQueryResult>()?
+            .ok_or_else(|| FlightError::protocol("unexpected CancelQuery response"))?;
+        Ok(result.result() == CancelResult::Cancelled)
+    }
+}
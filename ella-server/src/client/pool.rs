@@ -0,0 +1,157 @@
+use std::{
+    fmt::Debug,
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tonic::transport::Endpoint;
+
+use super::EllaClient;
+
+/// A pool of client connections to an ella server.
+///
+/// At most `max_size` clients are checked out at once. Idle clients are health-checked before
+/// being handed out again and are replaced with a new connection if the check fails.
+#[derive(Debug, Clone)]
+pub struct EllaPool {
+    inner: Arc<PoolInner>,
+}
+
+#[derive(Debug)]
+struct PoolInner {
+    endpoint: Endpoint,
+    credentials: Option<(String, String)>,
+    max_size: usize,
+    idle: Mutex<Vec<EllaClient>>,
+    permits: Arc<Semaphore>,
+}
+
+impl EllaPool {
+    pub fn new(endpoint: Endpoint, max_size: usize) -> Self {
+        Self::build(endpoint, None, max_size)
+    }
+
+    /// Create a pool whose connections authenticate as `user`.
+    pub fn with_credentials(
+        endpoint: Endpoint,
+        max_size: usize,
+        user: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        Self::build(endpoint, Some((user.into(), password.into())), max_size)
+    }
+
+    fn build(endpoint: Endpoint, credentials: Option<(String, String)>, max_size: usize) -> Self {
+        let max_size = max_size.max(1);
+        Self {
+            inner: Arc::new(PoolInner {
+                endpoint,
+                credentials,
+                max_size,
+                idle: Mutex::new(Vec::with_capacity(max_size)),
+                permits: Arc::new(Semaphore::new(max_size)),
+            }),
+        }
+    }
+
+    /// Check out a client, waiting if `max_size` clients are already in use.
+    pub async fn get(&self) -> crate::Result<PooledClient> {
+        let permit = self
+            .inner
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("pool semaphore should never be closed");
+
+        loop {
+            let idle = self.inner.idle.lock().unwrap().pop();
+            match idle {
+                Some(client) => match client.ping().await {
+                    Ok(_) => return Ok(self.pooled(client, permit)),
+                    Err(error) => {
+                        tracing::debug!(?error, "dropping unhealthy pooled connection");
+                    }
+                },
+                None => break,
+            }
+        }
+
+        let channel = self.inner.endpoint.connect().await?;
+        let client = match &self.inner.credentials {
+            Some((user, password)) => {
+                EllaClient::connect_with_credentials(channel, user, password).await?
+            }
+            None => EllaClient::connect(channel).await?,
+        };
+        Ok(self.pooled(client, permit))
+    }
+
+    pub fn max_size(&self) -> usize {
+        self.inner.max_size
+    }
+
+    /// Number of idle connections held by the pool.
+    pub fn idle(&self) -> usize {
+        self.inner.idle.lock().unwrap().len()
+    }
+
+    fn pooled(&self, client: EllaClient, permit: OwnedSemaphorePermit) -> PooledClient {
+        PooledClient {
+            client: Some(client),
+            pool: self.inner.clone(),
+            _permit: permit,
+        }
+    }
+}
+
+/// A client checked out from an [`EllaPool`].
+///
+/// The client is returned to the pool when dropped.
+pub struct PooledClient {
+    client: Option<EllaClient>,
+    pool: Arc<PoolInner>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl PooledClient {
+    /// Drop the connection instead of returning it to the pool.
+    pub fn discard(mut self) {
+        self.client = None;
+    }
+}
+
+impl Debug for PooledClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PooledClient")
+            .field("client", &self.client)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Deref for PooledClient {
+    type Target = EllaClient;
+
+    fn deref(&self) -> &Self::Target {
+        self.client
+            .as_ref()
+            .expect("pooled client should only be taken on drop")
+    }
+}
+
+impl DerefMut for PooledClient {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.client
+            .as_mut()
+            .expect("pooled client should only be taken on drop")
+    }
+}
+
+impl Drop for PooledClient {
+    fn drop(&mut self) {
+        if let Some(client) = self.client.take() {
+            self.pool.idle.lock().unwrap().push(client);
+        }
+    }
+}
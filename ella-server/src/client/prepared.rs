@@ -0,0 +1,152 @@
+use std::{fmt::Debug, sync::Arc};
+
+use arrow_flight::{
+    encode::FlightDataEncoderBuilder,
+    error::FlightError,
+    sql::{
+        ActionClosePreparedStatementRequest, ActionCreatePreparedStatementRequest,
+        ActionCreatePreparedStatementResult, Any, CommandPreparedStatementQuery, ProstMessageExt,
+    },
+    Action, FlightData, FlightDescriptor, IpcMessage,
+};
+use datafusion::arrow::{
+    datatypes::{DataType, Schema, SchemaRef},
+    record_batch::RecordBatch,
+};
+use ella_engine::{lazy::Lazy, Plan};
+use futures::TryStreamExt;
+use prost::{bytes::Bytes, Message};
+
+use super::{backend::RemoteBackend, EllaClient};
+use crate::gen;
+
+/// A query planned once by the server and executed with different parameter values.
+#[derive(Debug, Clone)]
+pub struct PreparedQuery {
+    client: EllaClient,
+    handle: Bytes,
+    dataset_schema: SchemaRef,
+    parameter_schema: SchemaRef,
+}
+
+impl PreparedQuery {
+    pub(crate) async fn new(mut client: EllaClient, query: String) -> crate::Result<Self> {
+        let request = ActionCreatePreparedStatementRequest {
+            query,
+            transaction_id: None,
+        };
+        let body = client
+            .do_action("CreatePreparedStatement", request.as_any().encode_to_vec())
+            .await?
+            .ok_or_else(|| FlightError::protocol("no response to CreatePreparedStatement"))?;
+        let result = Any::decode(&*body)?
+            .unpack::<ActionCreatePreparedStatementResult>()?
+            .ok_or_else(|| FlightError::protocol("unexpected CreatePreparedStatement response"))?;
+
+        Ok(Self {
+            client,
+            handle: result.prepared_statement_handle,
+            dataset_schema: Arc::new(Schema::try_from(IpcMessage(result.dataset_schema))?),
+            parameter_schema: Arc::new(Schema::try_from(IpcMessage(result.parameter_schema))?),
+        })
+    }
+
+    /// Schema of the query's results.
+    pub fn arrow_schema(&self) -> &SchemaRef {
+        &self.dataset_schema
+    }
+
+    /// Schema of the query's parameters, with one field per `$N` placeholder in order.
+    ///
+    /// Fields with type `Null` are placeholders whose type the server couldn't infer.
+    pub fn parameter_schema(&self) -> &SchemaRef {
+        &self.parameter_schema
+    }
+
+    /// Bind `params` to the query's placeholders and return the bound query.
+    ///
+    /// `params` must contain a single row with one column per parameter. Binding replaces any
+    /// values bound by previous calls, but queries returned earlier are not affected.
+    pub async fn execute(&self, params: RecordBatch) -> crate::Result<Lazy> {
+        let mut client = self.client.clone();
+        if !self.parameter_schema.fields().is_empty() {
+            self.check_parameters(&params)?;
+
+            let descriptor = FlightDescriptor::new_cmd(
+                CommandPreparedStatementQuery {
+                    prepared_statement_handle: self.handle.clone(),
+                }
+                .as_any()
+                .encode_to_vec(),
+            );
+            let messages = FlightDataEncoderBuilder::new()
+                .build(futures::stream::iter([Ok(params)]))
+                .try_collect::<Vec<_>>()
+                .await?;
+            let header = FlightData::new().with_descriptor(descriptor);
+            let stream = futures::stream::iter(std::iter::once(header).chain(messages));
+            client
+                .put
+                .do_put(stream)
+                .await
+                .map_err(crate::ClientError::from)?
+                .into_inner()
+                .message()
+                .await
+                .map_err(crate::ClientError::from)?;
+        }
+
+        // Execute the plan itself so re-binding the statement doesn't change this query
+        let raw_plan = client
+            .engine
+            .get_plan(gen::GetPlanReq {
+                handle: self.handle.to_vec(),
+            })
+            .await
+            .map_err(crate::ClientError::from)?
+            .into_inner()
+            .plan;
+        let plan = Plan::from_bytes(&raw_plan)?;
+        Ok(Lazy::new(plan, Arc::new(RemoteBackend::from(client))))
+    }
+
+    /// Release the prepared statement on the server.
+    pub async fn close(mut self) -> crate::Result<()> {
+        let request = ActionClosePreparedStatementRequest {
+            prepared_statement_handle: self.handle.clone(),
+        };
+        self.client
+            .do_action("ClosePreparedStatement", request.as_any().encode_to_vec())
+            .await?;
+        Ok(())
+    }
+
+    fn check_parameters(&self, params: &RecordBatch) -> crate::Result<()> {
+        let invalid = |msg: String| crate::Error::from(crate::ClientError::InvalidParameters(msg));
+        let expected = self.parameter_schema.fields();
+        if params.num_rows() != 1 {
+            return Err(invalid(format!(
+                "expected a single row but got {}",
+                params.num_rows()
+            )));
+        }
+        if params.num_columns() != expected.len() {
+            return Err(invalid(format!(
+                "expected {} parameters but got {}",
+                expected.len(),
+                params.num_columns()
+            )));
+        }
+        for (field, column) in expected.iter().zip(params.columns()) {
+            if field.data_type() != &DataType::Null && field.data_type() != column.data_type() {
+                return Err(invalid(format!(
+                    "parameter {} has type {} but expected {}",
+                    field.name(),
+                    column.data_type(),
+                    field.data_type()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
@@ -5,14 +5,18 @@ use arrow_flight::{
     sql::{CommandStatementUpdate, ProstMessageExt},
     FlightData, FlightDescriptor,
 };
-use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::arrow::{
+    datatypes::Schema,
+    ipc::{convert::fb_to_schema, root_as_message},
+    record_batch::RecordBatch,
+};
 use ella_engine::{registry::TableId, EngineError};
 use flume::r#async::SendSink;
 use futures::{
     stream::{AbortHandle, Abortable},
     FutureExt, Sink, SinkExt, StreamExt,
 };
-use prost::Message;
+use prost::{bytes::Bytes, Message};
 use tokio::task::JoinHandle;
 
 use super::EllaClient;
@@ -36,14 +40,7 @@ impl FlightPublisher {
     pub fn new(mut client: EllaClient, table: TableId<'static>) -> Self {
         let (send, recv) = flume::bounded(1);
         let send = send.into_sink();
-        let descriptor = FlightDescriptor::new_cmd(
-            CommandStatementUpdate {
-                query: format!("insert into {} table this", table),
-                transaction_id: None,
-            }
-            .as_any()
-            .encode_to_vec(),
-        );
+        let descriptor = insert_descriptor(&table);
         let (stop, reg) = AbortHandle::new_pair();
         let header = futures::stream::once(async { FlightData::new().with_descriptor(descriptor) });
         let stream = FlightDataEncoderBuilder::new()
@@ -51,11 +48,7 @@ impl FlightPublisher {
             .map(|res| res.unwrap());
         let stream = Abortable::new(stream, reg);
 
-        let handle = tokio::spawn(async move {
-            let mut resp = client.flight.do_put(header.chain(stream)).await?;
-            resp.message().await.map_err(crate::ClientError::from)?;
-            Ok(())
-        });
+        let handle = tokio::spawn(async move { client.do_put(header.chain(stream)).await });
         Self {
             send,
             handle,
@@ -121,3 +114,77 @@ impl Sink<RecordBatch> for FlightPublisher {
         })
     }
 }
+
+/// Descriptor for a `do_put` request that appends its data to `table`.
+pub(crate) fn insert_descriptor(table: &TableId<'_>) -> FlightDescriptor {
+    FlightDescriptor::new_cmd(
+        CommandStatementUpdate {
+            query: format!("insert into {} table this", table.to_quoted_string()),
+            transaction_id: None,
+        }
+        .as_any()
+        .encode_to_vec(),
+    )
+}
+
+/// Split an Arrow IPC stream into flight messages without decoding its record batches.
+///
+/// Only the schema message is decoded, to check that it matches `schema`.
+pub(crate) fn ipc_flight_data(data: Bytes, schema: &Schema) -> crate::Result<Vec<FlightData>> {
+    let invalid = |msg: &str| crate::Error::from(crate::ClientError::InvalidIpc(msg.to_string()));
+    let read_len = |offset: usize| {
+        data.get(offset..offset + 4)
+            .map(|b| i32::from_le_bytes(b.try_into().unwrap()))
+            .ok_or_else(|| invalid("truncated message length"))
+    };
+
+    let mut messages = Vec::new();
+    let mut offset = 0;
+    while offset < data.len() {
+        let mut len = read_len(offset)?;
+        offset += 4;
+        // Messages are prefixed with a continuation marker except in the legacy format
+        if len == -1 {
+            len = read_len(offset)?;
+            offset += 4;
+        }
+        // A zero-length message marks the end of the stream
+        if len == 0 {
+            break;
+        }
+        let len = usize::try_from(len).map_err(|_| invalid("negative message length"))?;
+        let header = data
+            .get(offset..offset + len)
+            .map(|_| data.slice(offset..offset + len))
+            .ok_or_else(|| invalid("truncated message header"))?;
+        offset += len;
+
+        let message = root_as_message(&header)
+            .map_err(|err| crate::ClientError::InvalidIpc(err.to_string()))?;
+        let body_len = message.bodyLength() as usize;
+        let body = data
+            .get(offset..offset + body_len)
+            .map(|_| data.slice(offset..offset + body_len))
+            .ok_or_else(|| invalid("truncated message body"))?;
+        offset += body_len;
+
+        if messages.is_empty() {
+            let actual = message
+                .header_as_schema()
+                .map(fb_to_schema)
+                .ok_or_else(|| invalid("stream must start with a schema message"))?;
+            if actual.fields() != schema.fields() {
+                return Err(invalid("stream schema does not match table schema"));
+            }
+        }
+        messages.push(
+            FlightData::new()
+                .with_data_header(header)
+                .with_data_body(body),
+        );
+    }
+    if messages.is_empty() {
+        return Err(invalid("stream is empty"));
+    }
+    Ok(messages)
+}
@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use arrow_flight::error::FlightError;
+use datafusion::arrow::error::ArrowError;
+use tonic::{transport::Channel, Code};
+
+/// How a client reconnects and retries requests after losing its connection to the server.
+///
+/// Only idempotent requests, such as queries and catalog lookups, are retried. Requests that
+/// modify the datastore or publish data fail on the first error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_retries: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: usize) -> Self {
+        Self {
+            max_retries,
+            ..Default::default()
+        }
+    }
+
+    /// Wait `initial` before the first retry, doubling the wait after each attempt up to `max`.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    pub fn max_retries(&self) -> usize {
+        self.max_retries
+    }
+
+    /// Time to wait before retry number `attempt`, starting from 0.
+    pub fn backoff(&self, attempt: usize) -> Duration {
+        let factor = 1u32.checked_shl(attempt as u32).unwrap_or(u32::MAX);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Everything needed to re-establish a session after the connection is lost.
+#[derive(Debug)]
+pub(crate) struct Reconnect {
+    pub policy: RetryPolicy,
+    pub channel: Channel,
+    pub user: String,
+    pub password: String,
+}
+
+/// Whether `error` means the connection or session was lost, rather than the request failing.
+pub(crate) fn is_retryable(error: &crate::Error) -> bool {
+    let retryable_code = |code| matches!(code, Code::Unavailable | Code::Unauthenticated);
+    match error {
+        crate::Error::Client(crate::ClientError::Server(status)) => retryable_code(status.code()),
+        crate::Error::Flight(FlightError::Tonic(status)) => retryable_code(status.code()),
+        crate::Error::Transport(_) => true,
+        // The flight SQL client only keeps the debug representation of the status
+        crate::Error::Arrow(ArrowError::IpcError(msg)) => {
+            msg.contains("code: Unavailable") || msg.contains("code: Unauthenticated")
+        }
+        _ => false,
+    }
+}
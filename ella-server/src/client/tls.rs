@@ -0,0 +1,50 @@
+use tonic::transport::{Certificate, ClientTlsConfig, Identity, Uri};
+
+/// TLS settings used by [`EllaClient::connect_tls`](super::EllaClient::connect_tls).
+///
+/// Server certificates are verified against the system's trusted roots unless a CA certificate
+/// is provided.
+#[derive(Debug, Clone, Default)]
+pub struct TlsConfig {
+    ca_certificate: Option<Vec<u8>>,
+    identity: Option<(Vec<u8>, Vec<u8>)>,
+    domain: Option<String>,
+}
+
+impl TlsConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Verify the server's certificate against the PEM-encoded CA certificate `pem`.
+    pub fn ca_certificate(mut self, pem: impl AsRef<[u8]>) -> Self {
+        self.ca_certificate = Some(pem.as_ref().to_vec());
+        self
+    }
+
+    /// Authenticate the client with a PEM-encoded certificate and private key (mutual TLS).
+    pub fn identity(mut self, cert: impl AsRef<[u8]>, key: impl AsRef<[u8]>) -> Self {
+        self.identity = Some((cert.as_ref().to_vec(), key.as_ref().to_vec()));
+        self
+    }
+
+    /// Expect the server's certificate to be issued for `domain` instead of the host in the URI.
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    pub(crate) fn to_tonic(&self, uri: &Uri) -> ClientTlsConfig {
+        let mut config = ClientTlsConfig::new();
+        if let Some(domain) = self.domain.as_deref().or_else(|| uri.host()) {
+            config = config.domain_name(domain);
+        }
+        if let Some(pem) = &self.ca_certificate {
+            config = config.ca_certificate(Certificate::from_pem(pem));
+        }
+        if let Some((cert, key)) = &self.identity {
+            config = config.identity(Identity::from_pem(cert, key));
+        }
+        config
+    }
+}
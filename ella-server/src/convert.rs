@@ -8,7 +8,10 @@ use ella_engine::{
     Plan,
 };
 
-use crate::gen::{self, table_info::Kind};
+use crate::{
+    gen::{self, table_info::Kind},
+    server::TicketStatus,
+};
 
 impl TryFrom<gen::TensorType> for TensorType {
     type Error = crate::Error;
@@ -149,6 +152,12 @@ impl TryFrom<gen::TopicInfo> for TopicInfo {
         if let Some(config) = value.config.as_deref() {
             builder = builder.config(serde_json::from_slice(config)?);
         }
+        if let Some(name) = value.secondary_time {
+            builder = builder.secondary_time(name);
+        }
+        if let Some(column) = value.cluster_by {
+            builder = builder.cluster_by(column);
+        }
 
         Ok(builder.build())
     }
@@ -182,6 +191,8 @@ impl TryFrom<TopicInfo> for gen::TopicInfo {
             temporary: value.temporary(),
             index,
             config,
+            secondary_time: value.secondary_time().map(str::to_string),
+            cluster_by: value.cluster_by().map(str::to_string),
         })
     }
 }
@@ -263,3 +274,33 @@ impl TryFrom<TableInfo> for gen::TableInfo {
         })
     }
 }
+
+impl From<TicketStatus> for gen::TicketStatus {
+    fn from(value: TicketStatus) -> Self {
+        let (state, error) = match value {
+            TicketStatus::Unknown => (gen::TaskState::Unknown, None),
+            TicketStatus::Ready => (gen::TaskState::Ready, None),
+            TicketStatus::Planning => (gen::TaskState::Planning, None),
+            TicketStatus::Streaming => (gen::TaskState::Streaming, None),
+            TicketStatus::Finished => (gen::TaskState::Finished, None),
+            TicketStatus::Failed(error) => (gen::TaskState::Failed, Some(error)),
+        };
+        gen::TicketStatus {
+            state: state.into(),
+            error,
+        }
+    }
+}
+
+impl From<gen::TicketStatus> for TicketStatus {
+    fn from(value: gen::TicketStatus) -> Self {
+        match gen::TaskState::from_i32(value.state) {
+            Some(gen::TaskState::Ready) => TicketStatus::Ready,
+            Some(gen::TaskState::Planning) => TicketStatus::Planning,
+            Some(gen::TaskState::Streaming) => TicketStatus::Streaming,
+            Some(gen::TaskState::Finished) => TicketStatus::Finished,
+            Some(gen::TaskState::Failed) => TicketStatus::Failed(value.error.unwrap_or_default()),
+            Some(gen::TaskState::Unknown) | None => TicketStatus::Unknown,
+        }
+    }
+}
@@ -6,7 +6,7 @@ pub mod table;
 pub use tonic;
 
 pub use ella_common::{
-    error::{ClientError, ServerError},
+    error::{ClientError, PublishError, ServerError},
     Error, Result,
 };
 
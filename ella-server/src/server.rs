@@ -1,6 +1,12 @@
 mod auth;
 mod ella;
 mod flight;
+mod tasks;
+
+pub use auth::CredentialStore;
+pub(crate) use flight::matches_pattern;
+pub use flight::SqlCapabilities;
+pub use tasks::TicketStatus;
 
 use std::{net::ToSocketAddrs, sync::Arc};
 
@@ -27,19 +33,75 @@ impl EllaServer {
     // TODO: this should be configurable
     const SECRET: &[u8] = b"ella";
 
+    /// Start the server, accepting anonymous clients.
+    ///
+    /// Fails if any table has an ACL, since anonymous clients could never be granted access to it.
     pub fn start<A: ToSocketAddrs>(
         server: Server,
         state: EllaState,
         addr: A,
     ) -> crate::Result<Self> {
+        Self::start_with_capabilities(server, state, addr, &SqlCapabilities::default())
+    }
+
+    /// Start the server, advertising `capabilities` to Flight SQL clients.
+    pub fn start_with_capabilities<A: ToSocketAddrs>(
+        server: Server,
+        state: EllaState,
+        addr: A,
+        capabilities: &SqlCapabilities,
+    ) -> crate::Result<Self> {
+        Self::start_inner(server, state, addr, capabilities, None, None)
+    }
+
+    /// Start the server, only accepting clients whose credentials are verified by `credentials`.
+    ///
+    /// Once its credentials have been verified, the `superuser` principal bypasses all table ACLs.
+    pub fn start_with_credentials<A: ToSocketAddrs>(
+        server: Server,
+        state: EllaState,
+        addr: A,
+        capabilities: &SqlCapabilities,
+        credentials: Arc<dyn CredentialStore>,
+        superuser: Option<&str>,
+    ) -> crate::Result<Self> {
+        Self::start_inner(
+            server,
+            state,
+            addr,
+            capabilities,
+            Some(credentials),
+            superuser.map(str::to_string),
+        )
+    }
+
+    fn start_inner<A: ToSocketAddrs>(
+        server: Server,
+        state: EllaState,
+        addr: A,
+        capabilities: &SqlCapabilities,
+        credentials: Option<Arc<dyn CredentialStore>>,
+        superuser: Option<String>,
+    ) -> crate::Result<Self> {
+        // Without a credential store every connection is anonymous, so ACLs could never be satisfied
+        if credentials.is_none() {
+            Self::check_no_acls(&state)?;
+        }
+
         let auth = Arc::new(AuthProvider::from_secret(Self::SECRET)?);
-        let connections = ConnectionManager::new(auth, state);
+        let mut connections = ConnectionManager::new(auth, state);
+        if let Some(superuser) = superuser {
+            connections = connections.with_superuser(superuser);
+        }
 
         let flight_svc = FlightServiceServer::with_interceptor(
-            EllaSqlService::new(connections.clone()),
+            EllaSqlService::new(connections.clone(), capabilities, credentials),
             connections.clone(),
         );
-        let engine_svc = EngineServiceServer::with_interceptor(EllaEngineService, connections);
+        let engine_svc = EngineServiceServer::with_interceptor(
+            EllaEngineService::new(connections.clone()),
+            connections,
+        );
         let stop = Arc::new(Notify::new());
 
         let stop_signal = stop.clone();
@@ -81,6 +143,21 @@ impl EllaServer {
         Ok(Self { handle, stop })
     }
 
+    fn check_no_acls(state: &EllaState) -> crate::Result<()> {
+        for catalog in state.cluster().catalogs() {
+            for schema in catalog.schemas() {
+                for table in schema.tables() {
+                    if table.acl().is_some() {
+                        return Err(
+                            crate::ServerError::UnverifiedAcl(table.id().to_string()).into()
+                        );
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn cancel(&self) {
         self.stop.notify_one()
     }
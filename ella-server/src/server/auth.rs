@@ -1,24 +1,79 @@
 use std::sync::{Arc, Mutex};
 
+use base64::{engine::general_purpose::STANDARD, Engine};
 use dashmap::DashMap;
 use ella_common::OffsetDateTime;
-use ella_engine::{engine::EllaState, EllaConfig};
+use ella_engine::{engine::EllaState, registry::TableRef, table::Permission, EllaConfig};
 use hmac::{Hmac, Mac};
 use jwt::{RegisteredClaims, SignWithKey, VerifyWithKey};
 use sha2::Sha256;
 use tonic::service::Interceptor;
 use uuid::Uuid;
 
+use super::tasks::TaskTracker;
+
 #[derive(Debug, Clone)]
 pub(crate) struct ConnectionState {
+    id: Uuid,
     state: Arc<Mutex<EllaState>>,
+    principal: Option<String>,
+    superuser: bool,
+    tasks: TaskTracker,
 }
 
 impl ConnectionState {
-    pub fn new(state: EllaState) -> Self {
+    /// Must be called from within a Tokio runtime, which runs the task that discards the
+    /// connection's expired tickets.
+    pub fn new(id: Uuid, mut state: EllaState, principal: Option<String>, superuser: bool) -> Self {
+        state.with_principal(principal.clone());
+        let ttl = state.config().engine_config().ticket_ttl().unsigned_abs();
         Self {
+            id,
             state: Arc::new(Mutex::new(state)),
+            principal,
+            superuser,
+            tasks: TaskTracker::new(ttl),
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn principal(&self) -> Option<&str> {
+        self.principal.as_deref()
+    }
+
+    /// Check that the connection's principal has been granted `permission` on `table`.
+    ///
+    /// Tables without an ACL are accessible to everyone, and the superuser bypasses all ACLs.
+    /// Anonymous connections are denied access to every table with an ACL.
+    pub fn authorize(
+        &self,
+        table: TableRef<'_>,
+        permission: Permission,
+    ) -> Result<(), tonic::Status> {
+        if self.superuser {
+            return Ok(());
         }
+        let state = self.read();
+        let table = match state.table(state.resolve(table)) {
+            Some(table) => table,
+            None => return Ok(()),
+        };
+        match (table.acl(), self.principal()) {
+            (None, _) => Ok(()),
+            (Some(acl), Some(principal)) if acl.allows(principal, permission) => Ok(()),
+            (Some(_), _) => Err(tonic::Status::permission_denied(format!(
+                "{} permission required for table {}",
+                permission,
+                table.id()
+            ))),
+        }
+    }
+
+    pub fn tasks(&self) -> &TaskTracker {
+        &self.tasks
     }
 
     pub fn read(&self) -> EllaState {
@@ -30,6 +85,12 @@ impl ConnectionState {
     }
 }
 
+/// Verifies the credentials that clients present when connecting.
+pub trait CredentialStore: std::fmt::Debug + Send + Sync {
+    /// Returns `true` if `password` is valid for `user`.
+    fn verify(&self, user: &str, password: &str) -> bool;
+}
+
 #[derive(Debug)]
 pub(crate) struct AuthProvider {
     key: Hmac<Sha256>,
@@ -98,6 +159,7 @@ pub(crate) struct ConnectionManager {
     state: EllaState,
     auth: Arc<AuthProvider>,
     connections: Arc<DashMap<Uuid, ConnectionState>>,
+    superuser: Option<String>,
 }
 
 impl ConnectionManager {
@@ -106,20 +168,33 @@ impl ConnectionManager {
             auth,
             state,
             connections: Arc::new(DashMap::new()),
+            superuser: None,
         }
     }
 
-    pub fn handshake(&self) -> crate::Result<String> {
-        let conn = ConnectionToken::new(None);
+    pub fn with_superuser(mut self, principal: impl Into<String>) -> Self {
+        self.superuser = Some(principal.into());
+        self
+    }
+
+    pub fn handshake(&self, principal: Option<String>) -> crate::Result<String> {
+        let superuser = principal.is_some() && principal == self.superuser;
+        let conn = ConnectionToken::new(principal.clone());
         let token = self.auth.encode(&conn)?;
-        let state = ConnectionState::new(self.state.clone());
-        self.connections.insert(
-            conn.uuid()
-                .expect("newly created UUID should always be valid"),
-            state,
-        );
+        let id = conn
+            .uuid()
+            .expect("newly created UUID should always be valid");
+        let state = ConnectionState::new(id, self.state.clone(), principal, superuser);
+        self.connections.insert(id, state);
         Ok(token)
     }
+
+    /// Remove the connection `id` so that its token is no longer accepted.
+    ///
+    /// The connection's statement handles and ticket statuses are released along with it.
+    pub fn logout(&self, id: Uuid) -> bool {
+        self.connections.remove(&id).is_some()
+    }
 }
 
 impl Interceptor for ConnectionManager {
@@ -153,3 +228,29 @@ pub(crate) fn connection<T>(request: &tonic::Request<T>) -> Result<ConnectionSta
         .cloned()
         .ok_or_else(|| tonic::Status::unauthenticated("missing connection token"))
 }
+
+/// Returns the user name and password from the request's basic authorization header, if any.
+pub(crate) fn basic_auth<T>(
+    request: &tonic::Request<T>,
+) -> Result<Option<(String, String)>, tonic::Status> {
+    let auth = match request.metadata().get("authorization").map(|m| m.to_str()) {
+        Some(Ok(auth)) => auth,
+        Some(Err(_)) => {
+            return Err(tonic::Status::unauthenticated(
+                "unable to parse authorization header as ASCII",
+            ))
+        }
+        None => return Ok(None),
+    };
+    let encoded = match auth.split_once(' ') {
+        Some(("Basic", encoded)) => encoded,
+        _ => return Ok(None),
+    };
+    let decoded = STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(|raw| String::from_utf8(raw).ok())
+        .ok_or_else(|| tonic::Status::unauthenticated("invalid basic authorization header"))?;
+    let (user, password) = decoded.split_once(':').unwrap_or((decoded.as_str(), ""));
+    Ok((!user.is_empty()).then(|| (user.to_string(), password.to_string())))
+}
@@ -1,14 +1,23 @@
 use crate::gen::{self, engine_service_server::EngineService};
 use ella_engine::{
     registry::{SchemaRef, TableRef},
+    table::Permission,
     EllaConfig,
 };
 use tonic::{Request, Response};
 
-use super::auth::connection;
+use super::auth::{connection, ConnectionManager};
 
-#[derive(Debug, Clone, Default)]
-pub(crate) struct EllaEngineService;
+#[derive(Debug, Clone)]
+pub(crate) struct EllaEngineService {
+    connections: ConnectionManager,
+}
+
+impl EllaEngineService {
+    pub fn new(connections: ConnectionManager) -> Self {
+        Self { connections }
+    }
+}
 
 #[tonic::async_trait]
 impl EngineService for EllaEngineService {
@@ -32,13 +41,17 @@ impl EngineService for EllaEngineService {
         &self,
         request: Request<gen::CreateTableReq>,
     ) -> tonic::Result<Response<gen::ResolvedTable>> {
-        let state = connection(&request)?.read();
+        let conn = connection(&request)?;
+        let state = conn.read();
         let req = request.into_inner();
         let table: TableRef<'static> = req
             .table
             .ok_or_else(|| tonic::Status::invalid_argument("missing table field in request"))?
             .into();
         let table = state.resolve(table);
+        if req.or_replace {
+            conn.authorize(table.clone().into(), Permission::Admin)?;
+        }
 
         let info = req
             .info
@@ -139,4 +152,76 @@ impl EngineService for EllaEngineService {
             schema: schema.id().schema.to_string(),
         }))
     }
+
+    async fn drop_table(
+        &self,
+        request: Request<gen::DropTableReq>,
+    ) -> tonic::Result<Response<gen::Empty>> {
+        let conn = connection(&request)?;
+        let state = conn.read();
+        let req = request.into_inner();
+        let table: TableRef<'static> = req
+            .table
+            .ok_or_else(|| tonic::Status::invalid_argument("missing table field in request"))?
+            .into();
+        let table = state.resolve(table);
+        conn.authorize(table.clone().into(), Permission::Admin)?;
+        state.drop_table(table, req.if_exists).await?;
+        Ok(Response::new(gen::Empty {}))
+    }
+
+    async fn drop_schema(
+        &self,
+        request: Request<gen::DropSchemaReq>,
+    ) -> tonic::Result<Response<gen::Empty>> {
+        let state = connection(&request)?.read();
+        let req = request.into_inner();
+        let schema = SchemaRef {
+            catalog: req.catalog.map(Into::into),
+            schema: req.schema.into(),
+        };
+        state
+            .drop_schema(schema, req.if_exists, req.cascade)
+            .await?;
+        Ok(Response::new(gen::Empty {}))
+    }
+
+    async fn drop_catalog(
+        &self,
+        request: Request<gen::DropCatalogReq>,
+    ) -> tonic::Result<Response<gen::Empty>> {
+        let state = connection(&request)?.read();
+        let req = request.into_inner();
+        state
+            .drop_catalog(req.catalog, req.if_exists, req.cascade)
+            .await?;
+        Ok(Response::new(gen::Empty {}))
+    }
+
+    async fn get_ticket_status(
+        &self,
+        request: Request<gen::TicketStatusReq>,
+    ) -> tonic::Result<Response<gen::TicketStatus>> {
+        let conn = connection(&request)?;
+        let status = conn.tasks().status(&request.into_inner().ticket);
+        Ok(Response::new(status.into()))
+    }
+
+    async fn get_plan(
+        &self,
+        request: Request<gen::GetPlanReq>,
+    ) -> tonic::Result<Response<gen::SerializedPlan>> {
+        let conn = connection(&request)?;
+        let plan = conn
+            .tasks()
+            .plan(&request.into_inner().handle)
+            .ok_or_else(|| tonic::Status::not_found("unknown statement handle"))?;
+        Ok(Response::new(gen::SerializedPlan { plan }))
+    }
+
+    async fn logout(&self, request: Request<gen::Empty>) -> tonic::Result<Response<gen::Empty>> {
+        let conn = connection(&request)?;
+        self.connections.logout(conn.id());
+        Ok(Response::new(gen::Empty {}))
+    }
 }
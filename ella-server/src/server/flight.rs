@@ -8,30 +8,43 @@ use arrow_flight::sql::{
     ActionCancelQueryResult, ActionClosePreparedStatementRequest,
     ActionCreatePreparedStatementRequest, ActionCreatePreparedStatementResult,
     ActionCreatePreparedSubstraitPlanRequest, ActionEndSavepointRequest,
-    ActionEndTransactionRequest, Any, CommandGetCatalogs, CommandGetCrossReference,
+    ActionEndTransactionRequest, Any, CancelResult, CommandGetCatalogs, CommandGetCrossReference,
     CommandGetDbSchemas, CommandGetExportedKeys, CommandGetImportedKeys, CommandGetPrimaryKeys,
     CommandGetSqlInfo, CommandGetTableTypes, CommandGetTables, CommandGetXdbcTypeInfo,
     CommandPreparedStatementQuery, CommandPreparedStatementUpdate, CommandStatementQuery,
     CommandStatementSubstraitPlan, CommandStatementUpdate, ProstMessageExt, SqlInfo,
-    TicketStatementQuery,
+    SqlSupportedTransaction, TicketStatementQuery,
 };
 use arrow_flight::{
     flight_service_server::FlightService, Action, FlightData, FlightDescriptor, FlightEndpoint,
-    FlightInfo, HandshakeRequest, HandshakeResponse, Ticket,
+    FlightInfo, HandshakeRequest, HandshakeResponse, IpcMessage, PutResult, SchemaAsIpc, Ticket,
 };
+use datafusion::arrow::array::{Array, ArrayRef, Int32Array, StringArray, UInt64Array};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::ipc::writer::IpcWriteOptions;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::tree_node::{TreeNode, VisitRecursion};
+use datafusion::common::{ScalarValue, TableReference};
 use datafusion::datasource::TableProvider;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{DdlStatement, Expr, LogicalPlan};
 use datafusion::sql::parser::Statement;
 use datafusion::sql::sqlparser::ast::{self, SetExpr};
 use ella_engine::engine::EllaState;
+use ella_engine::lazy::LazyBackend;
+use ella_engine::registry::TableRef;
+use ella_engine::table::{EllaTable, Permission};
 use ella_engine::{EngineError, Plan};
 use futures::{SinkExt, Stream, TryStreamExt};
-use once_cell::sync::Lazy;
 use prost::Message;
 use std::pin::Pin;
 use std::sync::Arc;
 use tonic::{Request, Response, Status, Streaming};
 
-use super::auth::{connection, ConnectionManager};
+use super::{
+    auth::{basic_auth, connection, ConnectionManager, ConnectionState, CredentialStore},
+    tasks::TicketStatus,
+};
 
 macro_rules! status {
     ($desc:expr, $err:expr) => {
@@ -39,47 +52,241 @@ macro_rules! status {
     };
 }
 
-static SQL_INFO: Lazy<SqlInfoData> = Lazy::new(|| {
-    let mut builder = SqlInfoDataBuilder::new();
-    builder.append(SqlInfo::FlightSqlServerName, "ella");
-    builder.append(SqlInfo::FlightSqlServerVersion, env!("CARGO_PKG_VERSION"));
-    // https://github.com/apache/arrow/blob/f9324b79bf4fc1ec7e97b32e3cce16e75ef0f5e3/format/Schema.fbs#L24
-    builder.append(SqlInfo::FlightSqlServerArrowVersion, "1.3");
-    builder.build().unwrap()
-});
+/// Capabilities advertised to Flight SQL clients through `SqlInfo`.
+///
+/// Features that ella only partially supports are disabled by default so that clients don't
+/// attempt operations the server will reject.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SqlCapabilities {
+    read_only: bool,
+    transactions: bool,
+    savepoints: bool,
+    cancel: bool,
+    catalog_filter: CatalogFilter,
+}
+
+/// Decides which catalogs are listed to a connection, given the catalog name and the
+/// connection's principal.
+#[derive(Clone, Default)]
+struct CatalogFilter(Option<Arc<dyn Fn(&str, Option<&str>) -> bool + Send + Sync>>);
+
+impl CatalogFilter {
+    fn is_visible(&self, catalog: &str, principal: Option<&str>) -> bool {
+        self.0
+            .as_ref()
+            .map_or(true, |filter| filter(catalog, principal))
+    }
+}
+
+impl std::fmt::Debug for CatalogFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("CatalogFilter")
+            .field(&self.0.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+impl PartialEq for CatalogFilter {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for CatalogFilter {}
+
+impl SqlCapabilities {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advertise the server as read-only.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Advertise support for transactions.
+    pub fn transactions(mut self, transactions: bool) -> Self {
+        self.transactions = transactions;
+        self
+    }
+
+    /// Advertise support for savepoints. Implies transaction support.
+    pub fn savepoints(mut self, savepoints: bool) -> Self {
+        self.savepoints = savepoints;
+        self
+    }
+
+    /// Advertise support for cancelling queries.
+    pub fn cancel(mut self, cancel: bool) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Only list catalogs for which `filter` returns true.
+    ///
+    /// The filter is called with the catalog name and the connection's principal. Hidden
+    /// catalogs are omitted from catalog, schema and table listings but can still be queried.
+    pub fn catalog_filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&str, Option<&str>) -> bool + Send + Sync + 'static,
+    {
+        self.catalog_filter = CatalogFilter(Some(Arc::new(filter)));
+        self
+    }
+
+    fn sql_info(&self) -> SqlInfoData {
+        let transaction = if self.savepoints {
+            SqlSupportedTransaction::Savepoint
+        } else if self.transactions {
+            SqlSupportedTransaction::Transaction
+        } else {
+            SqlSupportedTransaction::None
+        };
+
+        let mut builder = SqlInfoDataBuilder::new();
+        builder.append(SqlInfo::FlightSqlServerName, "ella");
+        builder.append(SqlInfo::FlightSqlServerVersion, env!("CARGO_PKG_VERSION"));
+        // https://github.com/apache/arrow/blob/f9324b79bf4fc1ec7e97b32e3cce16e75ef0f5e3/format/Schema.fbs#L24
+        builder.append(SqlInfo::FlightSqlServerArrowVersion, "1.3");
+        builder.append(SqlInfo::FlightSqlServerReadOnly, self.read_only);
+        builder.append(
+            SqlInfo::SqlTransactionsSupported,
+            transaction != SqlSupportedTransaction::None,
+        );
+        builder.append(SqlInfo::FlightSqlServerTransaction, transaction as i32);
+        builder.append(SqlInfo::FlightSqlServerCancel, self.cancel);
+        builder.build().unwrap()
+    }
+}
 
 #[derive(Debug, Clone)]
 pub(crate) struct EllaSqlService {
     connections: ConnectionManager,
+    sql_info: Arc<SqlInfoData>,
+    catalog_filter: CatalogFilter,
+    // Anonymous connections are accepted if there is no credential store
+    credentials: Option<Arc<dyn CredentialStore>>,
 }
 
 impl EllaSqlService {
-    pub fn new(connections: ConnectionManager) -> Self {
-        Self { connections }
+    pub fn new(
+        connections: ConnectionManager,
+        capabilities: &SqlCapabilities,
+        credentials: Option<Arc<dyn CredentialStore>>,
+    ) -> Self {
+        Self {
+            connections,
+            sql_info: Arc::new(capabilities.sql_info()),
+            catalog_filter: capabilities.catalog_filter.clone(),
+            credentials,
+        }
     }
 }
 
 impl EllaSqlService {
     async fn execute_plan(
         &self,
-        state: &EllaState,
+        conn: &ConnectionState,
         ticket: &[u8],
     ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
-        let stream =
-            ella_engine::lazy::Lazy::new(Plan::from_bytes(ticket)?, Arc::new(state.backend()))
-                .stream()
-                .await?;
+        let partition = conn.tasks().partition(ticket);
+        // Tickets either refer to a plan issued by `get_flight_info_statement` or contain a plan
+        // built by the client.
+        let (plan, issued) = match conn.tasks().plan(ticket) {
+            Some(raw) => (Plan::from_bytes(&raw)?, true),
+            None if conn.tasks().is_expired(ticket) => {
+                return Err(Status::not_found("ticket expired before it was fetched"))
+            }
+            None => {
+                let plan = Plan::from_bytes(ticket)
+                    .map_err(|_| Status::not_found("ticket does not refer to a known query"))?;
+                (plan, false)
+            }
+        };
+        let plan = match conn.tasks().statement(ticket) {
+            Some(statement) => plan.with_definition(statement),
+            None => plan,
+        };
+        authorize_plan(conn, plan.stub())?;
 
-        let schema = stream.arrow_schema();
+        conn.tasks().set(ticket, TicketStatus::Planning);
+        let state = conn.read();
+        let backend = state.backend();
+        let result = match partition {
+            Some(partition) => backend.stream_partition(&plan, partition).await,
+            None => backend.stream(&plan).await,
+        };
+        let stream = match result {
+            Ok(stream) => stream,
+            Err(err) => {
+                conn.tasks()
+                    .set(ticket, TicketStatus::Failed(err.to_string()));
+                return Err(err.into());
+            }
+        };
+
+        // The query may have been cancelled while it was being planned
+        if conn.tasks().status(ticket).is_done() {
+            return Err(Status::cancelled("query cancelled"));
+        }
+
+        let schema = stream.schema();
+        let max_size = state.config().engine_config().max_message_size();
         let stream = stream
-            .into_inner()
-            .map_err(|err| FlightError::ExternalError(Box::new(err)));
+            .map_ok(move |batch| futures::stream::iter(split_for_flight(batch, max_size)))
+            .map_err(|err| FlightError::ExternalError(Box::new(err)))
+            .try_flatten();
         let stream = FlightDataEncoderBuilder::new()
             .with_schema(schema)
+            .with_max_flight_data_size(max_size)
             .build(stream)
-            .map_err(Into::into);
-        Ok(Response::new(Box::pin(stream)))
+            .map_err(Status::from);
+        let stream = conn.tasks().track(ticket, stream);
+        if issued {
+            let stream = conn.tasks().release_after(ticket, stream);
+            Ok(Response::new(Box::pin(stream)))
+        } else {
+            Ok(Response::new(Box::pin(stream)))
+        }
+    }
+}
+
+/// Split `batch` by rows so that each part can be encoded in about `max_size` bytes.
+///
+/// The size of each row is computed from the column types where possible, so that batches with
+/// large tensor columns are split into small enough parts.
+fn split_for_flight(batch: RecordBatch, max_size: usize) -> Vec<Result<RecordBatch, FlightError>> {
+    let row_size = batch.columns().iter().map(row_size).sum::<usize>().max(1);
+    let rows = (max_size / row_size).max(1);
+    if batch.num_rows() <= rows {
+        return vec![Ok(batch)];
     }
+
+    (0..batch.num_rows())
+        .step_by(rows)
+        .map(|offset| Ok(batch.slice(offset, rows.min(batch.num_rows() - offset))))
+        .collect()
+}
+
+/// Estimated number of bytes needed to encode one row of `array`.
+fn row_size(array: &ArrayRef) -> usize {
+    fn width(dt: &DataType) -> Option<usize> {
+        match dt {
+            DataType::FixedSizeList(inner, size) => {
+                Some(width(inner.data_type())? * *size as usize)
+            }
+            DataType::Boolean => Some(1),
+            dt => dt.primitive_width(),
+        }
+    }
+
+    width(array.data_type())
+        .unwrap_or_else(|| array.get_array_memory_size() / array.len().max(1) + 1)
 }
 
 #[tonic::async_trait]
@@ -88,12 +295,19 @@ impl FlightSqlService for EllaSqlService {
 
     async fn do_handshake(
         &self,
-        _request: Request<Streaming<HandshakeRequest>>,
+        request: Request<Streaming<HandshakeRequest>>,
     ) -> Result<
         Response<Pin<Box<dyn Stream<Item = Result<HandshakeResponse, Status>> + Send>>>,
         Status,
     > {
-        let token = self.connections.handshake()?.into_bytes();
+        // The principal is only trusted once its credentials have been verified, since it
+        // decides which table ACLs apply to the connection
+        let principal = match (&self.credentials, basic_auth(&request)?) {
+            (Some(store), Some((user, password))) if store.verify(&user, &password) => Some(user),
+            (Some(_), _) => return Err(Status::unauthenticated("invalid user name or password")),
+            (None, _) => None,
+        };
+        let token = self.connections.handshake(principal)?.into_bytes();
         let result = HandshakeResponse {
             protocol_version: 0,
             payload: token.into(),
@@ -109,9 +323,9 @@ impl FlightSqlService for EllaSqlService {
         request: Request<Ticket>,
         _message: Any,
     ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
-        let state = connection(&request)?.read();
+        let conn = connection(&request)?;
         let ticket = request.into_inner().ticket;
-        self.execute_plan(&state, &ticket).await
+        self.execute_plan(&conn, &ticket).await
     }
 
     #[tracing::instrument(skip(self, request))]
@@ -120,24 +334,43 @@ impl FlightSqlService for EllaSqlService {
         query: CommandStatementQuery,
         request: Request<FlightDescriptor>,
     ) -> Result<Response<FlightInfo>, Status> {
-        let state = connection(&request)?.read();
+        let conn = connection(&request)?;
+        let state = conn.read();
         let plan = state.query(&query.query).await?;
-        let statement_handle = plan.plan().to_bytes().into();
+        authorize_plan(&conn, plan.plan().stub())?;
+        let raw_plan = plan.plan().to_bytes();
 
-        let ticket = TicketStatementQuery { statement_handle };
-        let endpoint = FlightEndpoint {
-            ticket: Some(Ticket {
-                ticket: ticket.as_any().encode_to_vec().into(),
-            }),
-            location: vec![],
+        let partitions = if state.config().engine_config().partitioned_results() {
+            state.backend().output_partitions(plan.plan()).await?
+        } else {
+            1
         };
+        // Each partition gets its own handle so clients can fetch them in parallel
+        let handles = if partitions > 1 {
+            (0..partitions)
+                .map(|partition| conn.tasks().register_partition(raw_plan.clone(), partition))
+                .collect::<Vec<_>>()
+        } else {
+            vec![conn.tasks().register_plan(raw_plan)]
+        };
+        for handle in &handles {
+            conn.tasks().set(handle, TicketStatus::Ready);
+            conn.tasks().set_statement(handle, query.query.clone());
+        }
 
-        let info = FlightInfo::new()
+        let mut info = FlightInfo::new()
             .try_with_schema(&plan.plan().arrow_schema())
             .map_err(crate::Error::from)?
-            .with_endpoint(endpoint)
-            .with_ordered(true)
+            .with_ordered(handles.len() == 1)
             .with_descriptor(request.into_inner());
+        for handle in &handles {
+            let ticket = TicketStatementQuery {
+                statement_handle: handle.clone().into(),
+            };
+            info = info.with_endpoint(FlightEndpoint::new().with_ticket(Ticket {
+                ticket: ticket.as_any().encode_to_vec().into(),
+            }));
+        }
         Ok(Response::new(info))
     }
 
@@ -152,15 +385,25 @@ impl FlightSqlService for EllaSqlService {
         ))
     }
 
-    #[tracing::instrument(skip(self, _request))]
+    #[tracing::instrument(skip(self, request))]
     async fn get_flight_info_prepared_statement(
         &self,
-        _cmd: CommandPreparedStatementQuery,
-        _request: Request<FlightDescriptor>,
+        cmd: CommandPreparedStatementQuery,
+        request: Request<FlightDescriptor>,
     ) -> Result<Response<FlightInfo>, Status> {
-        Err(Status::unimplemented(
-            "get_flight_info_prepared_statement not implemented",
-        ))
+        let conn = connection(&request)?;
+        let plan = prepared_plan(&conn, &cmd.prepared_statement_handle)?;
+        let endpoint = FlightEndpoint::new().with_ticket(Ticket {
+            ticket: cmd.as_any().encode_to_vec().into(),
+        });
+
+        let info = FlightInfo::new()
+            .try_with_schema(&plan.arrow_schema())
+            .map_err(crate::Error::from)?
+            .with_endpoint(endpoint)
+            .with_ordered(true)
+            .with_descriptor(request.into_inner());
+        Ok(Response::new(info))
     }
 
     #[tracing::instrument(skip(self, request))]
@@ -226,15 +469,25 @@ impl FlightSqlService for EllaSqlService {
         Ok(tonic::Response::new(flight_info))
     }
 
-    #[tracing::instrument(skip(self, _request))]
+    #[tracing::instrument(skip(self, request))]
     async fn get_flight_info_table_types(
         &self,
-        _query: CommandGetTableTypes,
-        _request: Request<FlightDescriptor>,
+        query: CommandGetTableTypes,
+        request: Request<FlightDescriptor>,
     ) -> Result<Response<FlightInfo>, Status> {
-        Err(Status::unimplemented(
-            "get_flight_info_table_types not implemented",
-        ))
+        let flight_descriptor = request.into_inner();
+        let ticket = Ticket {
+            ticket: query.as_any().encode_to_vec().into(),
+        };
+        let endpoint = FlightEndpoint::new().with_ticket(ticket);
+
+        let flight_info = FlightInfo::new()
+            .try_with_schema(&table_types_schema())
+            .map_err(|e| status!("Unable to encode schema", e))?
+            .with_endpoint(endpoint)
+            .with_descriptor(flight_descriptor);
+
+        Ok(tonic::Response::new(flight_info))
     }
 
     #[tracing::instrument(skip(self, request))]
@@ -248,7 +501,7 @@ impl FlightSqlService for EllaSqlService {
         let endpoint = FlightEndpoint::new().with_ticket(ticket);
 
         let flight_info = FlightInfo::new()
-            .try_with_schema(query.into_builder(&SQL_INFO).schema().as_ref())
+            .try_with_schema(query.into_builder(&self.sql_info).schema().as_ref())
             .map_err(|e| status!("Unable to encode schema", e))?
             .with_endpoint(endpoint)
             .with_descriptor(flight_descriptor);
@@ -256,15 +509,25 @@ impl FlightSqlService for EllaSqlService {
         Ok(tonic::Response::new(flight_info))
     }
 
-    #[tracing::instrument(skip(self, _request))]
+    #[tracing::instrument(skip(self, request))]
     async fn get_flight_info_primary_keys(
         &self,
-        _query: CommandGetPrimaryKeys,
-        _request: Request<FlightDescriptor>,
+        query: CommandGetPrimaryKeys,
+        request: Request<FlightDescriptor>,
     ) -> Result<Response<FlightInfo>, Status> {
-        Err(Status::unimplemented(
-            "get_flight_info_primary_keys not implemented",
-        ))
+        let flight_descriptor = request.into_inner();
+        let ticket = Ticket {
+            ticket: query.as_any().encode_to_vec().into(),
+        };
+        let endpoint = FlightEndpoint::new().with_ticket(ticket);
+
+        let flight_info = FlightInfo::new()
+            .try_with_schema(&primary_keys_schema())
+            .map_err(|e| status!("Unable to encode schema", e))?
+            .with_endpoint(endpoint)
+            .with_descriptor(flight_descriptor);
+
+        Ok(tonic::Response::new(flight_info))
     }
 
     #[tracing::instrument(skip(self, _request))]
@@ -317,19 +580,20 @@ impl FlightSqlService for EllaSqlService {
         ticket: TicketStatementQuery,
         request: Request<Ticket>,
     ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
-        let state = connection(&request)?.read();
-        self.execute_plan(&state, &ticket.statement_handle).await
+        let conn = connection(&request)?;
+        self.execute_plan(&conn, &ticket.statement_handle).await
     }
 
-    #[tracing::instrument(skip(self, _request))]
+    #[tracing::instrument(skip(self, request))]
     async fn do_get_prepared_statement(
         &self,
-        _query: CommandPreparedStatementQuery,
-        _request: Request<Ticket>,
+        query: CommandPreparedStatementQuery,
+        request: Request<Ticket>,
     ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
-        Err(Status::unimplemented(
-            "do_get_prepared_statement not implemented",
-        ))
+        let conn = connection(&request)?;
+        prepared_plan(&conn, &query.prepared_statement_handle)?;
+        self.execute_plan(&conn, &query.prepared_statement_handle)
+            .await
     }
 
     #[tracing::instrument(skip(self, request))]
@@ -338,10 +602,14 @@ impl FlightSqlService for EllaSqlService {
         query: CommandGetCatalogs,
         request: Request<Ticket>,
     ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
-        let state = connection(&request)?.read();
+        let conn = connection(&request)?;
+        let state = conn.read();
         let mut builder = query.into_builder();
         for catalog in state.cluster().catalogs() {
-            builder.append(catalog.id().to_string());
+            let name = catalog.id().to_string();
+            if self.catalog_filter.is_visible(&name, conn.principal()) {
+                builder.append(name);
+            }
         }
         let schema = builder.schema();
         let batch = builder.build();
@@ -358,12 +626,25 @@ impl FlightSqlService for EllaSqlService {
         query: CommandGetDbSchemas,
         request: Request<Ticket>,
     ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
-        let state = connection(&request)?.read();
+        let conn = connection(&request)?;
+        let state = conn.read();
+        let catalog_filter = query.catalog.clone();
+        let schema_pattern = query.db_schema_filter_pattern.clone();
         let mut builder = query.into_builder();
 
         for catalog in state.cluster().catalogs() {
+            let name = catalog.id().to_string();
+            if catalog_filter
+                .as_ref()
+                .map_or(false, |filter| *filter != name)
+                || !self.catalog_filter.is_visible(&name, conn.principal())
+            {
+                continue;
+            }
             for schema in catalog.schemas() {
-                builder.append(&schema.id().catalog, &schema.id().schema);
+                if matches_pattern(schema_pattern.as_deref(), schema.id().schema.as_ref()) {
+                    builder.append(&schema.id().catalog, &schema.id().schema);
+                }
             }
         }
 
@@ -382,19 +663,50 @@ impl FlightSqlService for EllaSqlService {
         query: CommandGetTables,
         request: Request<Ticket>,
     ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
-        let state = connection(&request)?.read();
+        let conn = connection(&request)?;
+        let state = conn.read();
+        // Filter before appending so that schemas aren't serialized for tables that are dropped
+        let catalog_filter = query.catalog.clone();
+        let schema_pattern = query.db_schema_filter_pattern.clone();
+        let table_pattern = query.table_name_filter_pattern.clone();
+        let table_types = query.table_types.clone();
+        // The builder drops schemas unless they're requested, so don't resolve them either
+        let include_schema = query.include_schema;
+        let no_schema: SchemaRef = Arc::new(Schema::empty());
         let mut builder = query.into_builder();
         for catalog in state.cluster().catalogs() {
+            let name = catalog.id().to_string();
+            if catalog_filter
+                .as_ref()
+                .map_or(false, |filter| *filter != name)
+                || !self.catalog_filter.is_visible(&name, conn.principal())
+            {
+                continue;
+            }
             for schema in catalog.schemas() {
+                if !matches_pattern(schema_pattern.as_deref(), schema.id().schema.as_ref()) {
+                    continue;
+                }
                 for table in schema.tables() {
                     let id = table.id();
+                    if !matches_pattern(table_pattern.as_deref(), id.table.as_ref())
+                        || (!table_types.is_empty()
+                            && !table_types.iter().any(|kind| kind == table.kind()))
+                    {
+                        continue;
+                    }
+                    let table_schema = if include_schema {
+                        table.schema()
+                    } else {
+                        no_schema.clone()
+                    };
                     builder
                         .append(
                             &id.catalog,
                             &id.schema,
                             &id.table,
                             table.kind(),
-                            &table.schema(),
+                            &table_schema,
                         )
                         .map_err(|e| status!("Failed to serialize table info", e))?;
                 }
@@ -416,7 +728,17 @@ impl FlightSqlService for EllaSqlService {
         _query: CommandGetTableTypes,
         _request: Request<Ticket>,
     ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
-        Err(Status::unimplemented("do_get_table_types not implemented"))
+        let schema = table_types_schema();
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(StringArray::from(EllaTable::KINDS.to_vec()))],
+        )
+        .map_err(|e| status!("Failed to build table types", e))?;
+        let stream = FlightDataEncoderBuilder::new()
+            .with_schema(schema)
+            .build(futures::stream::once(async { Ok(batch) }))
+            .map_err(Status::from);
+        Ok(Response::new(Box::pin(stream)))
     }
 
     #[tracing::instrument(skip(self, _request))]
@@ -425,7 +747,7 @@ impl FlightSqlService for EllaSqlService {
         query: CommandGetSqlInfo,
         _request: Request<Ticket>,
     ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
-        let builder = query.into_builder(&SQL_INFO);
+        let builder = query.into_builder(&self.sql_info);
         let schema = builder.schema();
         let batch = builder.build();
         let stream = FlightDataEncoderBuilder::new()
@@ -435,13 +757,48 @@ impl FlightSqlService for EllaSqlService {
         Ok(Response::new(Box::pin(stream)))
     }
 
-    #[tracing::instrument(skip(self, _request))]
+    #[tracing::instrument(skip(self, request))]
     async fn do_get_primary_keys(
         &self,
-        _query: CommandGetPrimaryKeys,
-        _request: Request<Ticket>,
+        query: CommandGetPrimaryKeys,
+        request: Request<Ticket>,
     ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
-        Err(Status::unimplemented("do_get_primary_keys not implemented"))
+        let state = connection(&request)?.read();
+        let catalog = query
+            .catalog
+            .unwrap_or_else(|| state.default_catalog().to_string());
+        let schema = query
+            .db_schema
+            .unwrap_or_else(|| state.default_schema().to_string());
+        let id = state.resolve(TableReference::full(catalog, schema, query.table).into());
+
+        // The index of a topic is its primary key; other tables have no natural key
+        let index = state
+            .table(id.clone())
+            .and_then(|table| table.as_topic())
+            .map(|topic| topic.info().index().clone())
+            .unwrap_or_default();
+        let rows = index.len();
+        let schema = primary_keys_schema();
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec![id.catalog.to_string(); rows])),
+                Arc::new(StringArray::from(vec![id.schema.to_string(); rows])),
+                Arc::new(StringArray::from(vec![id.table.to_string(); rows])),
+                Arc::new(StringArray::from_iter_values(
+                    index.iter().map(|idx| idx.column.as_str()),
+                )),
+                Arc::new(StringArray::from(vec![None::<&str>; rows])),
+                Arc::new(Int32Array::from_iter_values(1..=rows as i32)),
+            ],
+        )
+        .map_err(|e| status!("Failed to build primary keys", e))?;
+        let stream = FlightDataEncoderBuilder::new()
+            .with_schema(schema)
+            .build(futures::stream::once(async { Ok(batch) }))
+            .map_err(Status::from);
+        Ok(Response::new(Box::pin(stream)))
     }
 
     #[tracing::instrument(skip(self, _request))]
@@ -494,7 +851,8 @@ impl FlightSqlService for EllaSqlService {
         ticket: CommandStatementUpdate,
         request: Request<Streaming<FlightData>>,
     ) -> Result<i64, Status> {
-        let state = connection(&request)?.read();
+        let conn = connection(&request)?;
+        let state = conn.read();
         let session = state.session();
         let stmt = session
             .sql_to_statement(
@@ -503,38 +861,60 @@ impl FlightSqlService for EllaSqlService {
             )
             .map_err(crate::Error::from)?;
 
-        if let Statement::Statement(stmt) = stmt {
-            if let ast::Statement::Insert {
-                source, table_name, ..
-            } = stmt.as_ref()
-            {
-                if let SetExpr::Table(src) = source.body.as_ref() {
-                    if src.schema_name.is_none() && src.table_name.as_deref() == Some("this") {
-                        let mut stream = FlightRecordBatchStream::new_from_flight_data(
-                            request.into_inner().map_err(Into::into),
-                        );
-                        let mut pb = state
-                            .table(state.resolve(table_name.to_string().into()))
-                            .and_then(|t| t.as_topic())
-                            .ok_or_else(|| {
-                                crate::Error::from(EngineError::TableNotFound(
-                                    table_name.to_string(),
-                                ))
-                            })?
-                            .publish();
-
-                        let mut rows = 0;
-                        while let Some(batch) = stream.try_next().await? {
-                            rows += batch.num_rows();
-                            pb.send(batch).await?;
-                        }
-                        pb.flush().await?;
-                        return Ok(rows as i64);
-                    }
-                }
+        if let Some(table_name) = streaming_insert_target(&stmt) {
+            // Use the parsed identifiers so that quoted names are unescaped
+            let table = TableRef::try_from(table_name.clone())?;
+            state.check_writable()?;
+            state.check_user_table(&state.resolve(table.clone()))?;
+            conn.authorize(table.clone(), Permission::Write)?;
+            let mut stream = FlightRecordBatchStream::new_from_flight_data(
+                request.into_inner().map_err(Into::into),
+            );
+            let mut pb = state
+                .table(state.resolve(table))
+                .and_then(|t| t.as_topic())
+                .ok_or_else(|| {
+                    crate::Error::from(EngineError::TableNotFound(table_name.to_string()))
+                })?
+                .publish();
+
+            let mut rows = 0;
+            while let Some(batch) = stream.try_next().await? {
+                rows += batch.num_rows();
+                pb.send(batch).await?;
+            }
+            pb.flush().await?;
+            return Ok(rows as i64);
+        }
+
+        // Any other statement is planned and executed by the engine
+        let plan = match session.statement_to_plan(stmt).await {
+            Ok(plan) => plan,
+            Err(DataFusionError::NotImplemented(msg)) => {
+                return Err(Status::unimplemented(format!(
+                    "unsupported statement '{}': {}",
+                    ticket.query, msg
+                )))
             }
+            Err(err) => return Err(crate::Error::from(err).into()),
+        };
+        if !is_update(&plan) {
+            return Err(Status::unimplemented(format!(
+                "unsupported statement '{}'",
+                ticket.query
+            )));
         }
-        todo!()
+        authorize_plan(&conn, &plan)?;
+        state.check_writable()?;
+        let plan = Plan::from_plan(plan).with_definition(ticket.query.clone());
+        let batches = ella_engine::lazy::Lazy::new(plan, Arc::new(state.backend()))
+            .stream()
+            .await?
+            .into_inner()
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(crate::Error::from)?;
+        Ok(affected_rows(&batches))
     }
 
     #[tracing::instrument(skip(self, _request))]
@@ -548,15 +928,62 @@ impl FlightSqlService for EllaSqlService {
         ))
     }
 
-    #[tracing::instrument(skip(self, _request))]
+    #[tracing::instrument(skip(self, request))]
     async fn do_put_prepared_statement_query(
         &self,
-        _query: CommandPreparedStatementQuery,
-        _request: Request<Streaming<FlightData>>,
+        query: CommandPreparedStatementQuery,
+        request: Request<Streaming<FlightData>>,
     ) -> Result<Response<<Self as FlightService>::DoPutStream>, Status> {
-        Err(Status::unimplemented(
-            "do_put_prepared_statement_query not implemented",
-        ))
+        let conn = connection(&request)?;
+        let handle = query.prepared_statement_handle;
+        let template = conn
+            .tasks()
+            .prepared(&handle)
+            .ok_or_else(|| Status::not_found("prepared statement not found"))?;
+        let template = Plan::from_bytes(&template)?;
+        let expected = parameter_schema(template.stub())?;
+
+        let batches =
+            FlightRecordBatchStream::new_from_flight_data(request.into_inner().map_err(Into::into))
+                .try_collect::<Vec<_>>()
+                .await?;
+        let params = match batches.as_slice() {
+            [batch] if batch.num_rows() == 1 => batch,
+            _ => {
+                return Err(Status::invalid_argument(
+                    "expected a single row of parameter values",
+                ))
+            }
+        };
+        if params.num_columns() != expected.fields().len() {
+            return Err(Status::invalid_argument(format!(
+                "expected {} parameters but got {}",
+                expected.fields().len(),
+                params.num_columns()
+            )));
+        }
+        let mut values = Vec::with_capacity(params.num_columns());
+        for (field, column) in expected.fields().iter().zip(params.columns()) {
+            if field.data_type() != &DataType::Null && field.data_type() != column.data_type() {
+                return Err(Status::invalid_argument(format!(
+                    "parameter {} has type {} but expected {}",
+                    field.name(),
+                    column.data_type(),
+                    field.data_type()
+                )));
+            }
+            values.push(ScalarValue::try_from_array(column, 0).map_err(crate::Error::from)?);
+        }
+
+        // Binding always starts from the unbound plan so re-binding replaces earlier values
+        let plan = template.try_map(|plan| plan.with_param_values(values))?;
+        conn.tasks().bind(&handle, plan.to_bytes());
+
+        let result = PutResult {
+            app_metadata: Default::default(),
+        };
+        let stream = futures::stream::once(async { Ok::<_, Status>(result) });
+        Ok(Response::new(Box::pin(stream)))
     }
 
     #[tracing::instrument(skip(self, _request))]
@@ -570,26 +997,46 @@ impl FlightSqlService for EllaSqlService {
         ))
     }
 
-    #[tracing::instrument(skip(self, _request))]
+    #[tracing::instrument(skip(self, request))]
     async fn do_action_create_prepared_statement(
         &self,
-        _query: ActionCreatePreparedStatementRequest,
-        _request: Request<Action>,
+        query: ActionCreatePreparedStatementRequest,
+        request: Request<Action>,
     ) -> Result<ActionCreatePreparedStatementResult, Status> {
-        Err(Status::unimplemented(
-            "do_action_create_prepared_statement not implemented",
-        ))
+        let conn = connection(&request)?;
+        let plan = conn.read().query(&query.query).await?;
+        authorize_plan(&conn, plan.plan().stub())?;
+        let schema = plan.plan().arrow_schema();
+        let parameters = parameter_schema(plan.plan().stub())?;
+        let handle = conn.tasks().register_prepared(plan.plan().to_bytes());
+        conn.tasks().set_statement(&handle, query.query.clone());
+
+        let options = IpcWriteOptions::default();
+        let IpcMessage(dataset_schema) = SchemaAsIpc::new(&schema, &options)
+            .try_into()
+            .map_err(crate::Error::from)?;
+        let IpcMessage(parameter_schema) = SchemaAsIpc::new(&parameters, &options)
+            .try_into()
+            .map_err(crate::Error::from)?;
+        Ok(ActionCreatePreparedStatementResult {
+            prepared_statement_handle: handle.into(),
+            dataset_schema,
+            parameter_schema,
+        })
     }
 
-    #[tracing::instrument(skip(self, _request))]
+    #[tracing::instrument(skip(self, request))]
     async fn do_action_close_prepared_statement(
         &self,
-        _query: ActionClosePreparedStatementRequest,
-        _request: Request<Action>,
+        query: ActionClosePreparedStatementRequest,
+        request: Request<Action>,
     ) -> Result<(), Status> {
-        Err(Status::unimplemented(
-            "Implement do_action_close_prepared_statement",
-        ))
+        let conn = connection(&request)?;
+        // Closing is idempotent so clients can safely retry
+        if !conn.tasks().remove_plan(&query.prepared_statement_handle) {
+            tracing::debug!("closed unknown prepared statement handle");
+        }
+        Ok(())
     }
 
     #[tracing::instrument(skip(self, _request))]
@@ -641,14 +1088,222 @@ impl FlightSqlService for EllaSqlService {
         Err(Status::unimplemented("Implement do_action_end_savepoint"))
     }
 
-    #[tracing::instrument(skip(self, _request))]
+    #[tracing::instrument(skip(self, request))]
     async fn do_action_cancel_query(
         &self,
-        _query: ActionCancelQueryRequest,
-        _request: Request<Action>,
+        query: ActionCancelQueryRequest,
+        request: Request<Action>,
     ) -> Result<ActionCancelQueryResult, Status> {
-        Err(Status::unimplemented("Implement do_action_cancel_query"))
+        let conn = connection(&request)?;
+        let info = FlightInfo::decode(query.info).map_err(crate::Error::from)?;
+
+        let mut cancelled = false;
+        for endpoint in info.endpoint {
+            let ticket = match endpoint.ticket {
+                Some(ticket) => ticket,
+                None => continue,
+            };
+            let any = Any::decode(ticket.ticket).map_err(crate::Error::from)?;
+            let handle = if let Some(cmd) = any
+                .unpack::<TicketStatementQuery>()
+                .map_err(crate::Error::from)?
+            {
+                cmd.statement_handle
+            } else if let Some(cmd) = any
+                .unpack::<CommandPreparedStatementQuery>()
+                .map_err(crate::Error::from)?
+            {
+                cmd.prepared_statement_handle
+            } else {
+                continue;
+            };
+            cancelled |= conn.tasks().cancel(&handle);
+        }
+
+        let result = if cancelled {
+            CancelResult::Cancelled
+        } else {
+            CancelResult::NotCancellable
+        };
+        Ok(ActionCancelQueryResult {
+            result: result.into(),
+        })
     }
 
     async fn register_sql_info(&self, _id: i32, _result: &SqlInfo) {}
 }
+
+/// Target of an `INSERT INTO <table> TABLE this` statement, whose rows are streamed by the client.
+fn streaming_insert_target(stmt: &Statement) -> Option<&ast::ObjectName> {
+    if let Statement::Statement(stmt) = stmt {
+        if let ast::Statement::Insert {
+            source, table_name, ..
+        } = stmt.as_ref()
+        {
+            if let SetExpr::Table(src) = source.body.as_ref() {
+                if src.schema_name.is_none() && src.table_name.as_deref() == Some("this") {
+                    return Some(table_name);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Whether `plan` is DML or DDL that the engine can execute as an update.
+fn is_update(plan: &LogicalPlan) -> bool {
+    match plan {
+        LogicalPlan::Dml(_) => true,
+        LogicalPlan::Ddl(ddl) => matches!(
+            ddl,
+            DdlStatement::CreateView(_)
+                | DdlStatement::CreateCatalogSchema(_)
+                | DdlStatement::CreateCatalog(_)
+                | DdlStatement::DropTable(_)
+                | DdlStatement::DropView(_)
+                | DdlStatement::DropCatalogSchema(_)
+        ),
+        _ => false,
+    }
+}
+
+/// Number of rows reported by a DML statement's results, or 0 for other statements.
+fn affected_rows(batches: &[RecordBatch]) -> i64 {
+    batches
+        .iter()
+        .filter(|batch| batch.num_columns() == 1)
+        .filter_map(|batch| batch.column(0).as_any().downcast_ref::<UInt64Array>())
+        .flat_map(|counts| counts.iter().flatten())
+        .sum::<u64>() as i64
+}
+
+/// Whether `value` matches a Flight SQL filter pattern, where `%` matches any sequence of
+/// characters and `_` matches any single character. A missing pattern matches everything.
+pub(crate) fn matches_pattern(pattern: Option<&str>, value: &str) -> bool {
+    let pattern = match pattern {
+        Some(pattern) => pattern.chars().collect::<Vec<_>>(),
+        None => return true,
+    };
+    let value = value.chars().collect::<Vec<_>>();
+
+    // matched[j] is true if the pattern processed so far matches the first j characters
+    let mut matched = vec![false; value.len() + 1];
+    matched[0] = true;
+    for p in pattern {
+        if p == '%' {
+            for j in 1..=value.len() {
+                matched[j] |= matched[j - 1];
+            }
+        } else {
+            for j in (1..=value.len()).rev() {
+                matched[j] = matched[j - 1] && (p == '_' || p == value[j - 1]);
+            }
+            matched[0] = false;
+        }
+    }
+    matched[value.len()]
+}
+
+fn table_types_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![Field::new(
+        "table_type",
+        DataType::Utf8,
+        false,
+    )]))
+}
+
+fn primary_keys_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("catalog_name", DataType::Utf8, true),
+        Field::new("db_schema_name", DataType::Utf8, true),
+        Field::new("table_name", DataType::Utf8, false),
+        Field::new("column_name", DataType::Utf8, false),
+        Field::new("key_name", DataType::Utf8, true),
+        Field::new("key_sequence", DataType::Int32, false),
+    ]))
+}
+
+/// Plan stored for the prepared statement `handle`.
+fn prepared_plan(conn: &ConnectionState, handle: &[u8]) -> Result<Plan, Status> {
+    let plan = conn
+        .tasks()
+        .plan(handle)
+        .ok_or_else(|| Status::not_found("prepared statement not found"))?;
+    Ok(Plan::from_bytes(&plan)?)
+}
+
+/// Schema of the placeholders in `plan`, ordered by position.
+///
+/// Parameters whose type can't be inferred have type `Null`.
+fn parameter_schema(plan: &LogicalPlan) -> crate::Result<Schema> {
+    let mut params = plan.get_parameter_types()?.into_iter().collect::<Vec<_>>();
+    params.sort_by_key(|(id, _)| id.trim_start_matches('$').parse::<usize>().unwrap_or(0));
+    Ok(Schema::new(
+        params
+            .into_iter()
+            .map(|(id, data_type)| Field::new(id, data_type.unwrap_or(DataType::Null), true))
+            .collect::<Vec<_>>(),
+    ))
+}
+
+/// Check that the connection is allowed to access every table referenced by `plan`.
+fn authorize_plan(conn: &ConnectionState, plan: &LogicalPlan) -> Result<(), Status> {
+    let mut accesses = Vec::new();
+    table_accesses(&conn.read(), plan, &mut accesses);
+    for (table, permission) in accesses {
+        conn.authorize(table, permission)?;
+    }
+    Ok(())
+}
+
+/// Collect the tables accessed by `plan` along with the permission each access requires.
+///
+/// Tables read by subqueries and by the definitions of views are included, so that neither can
+/// be used to read a table the connection has no access to.
+fn table_accesses(
+    state: &EllaState,
+    plan: &LogicalPlan,
+    accesses: &mut Vec<(TableRef<'static>, Permission)>,
+) {
+    let _ = plan.apply(&mut |node| {
+        let access = match node {
+            LogicalPlan::TableScan(scan) => Some((scan.table_name.clone(), Permission::Read)),
+            LogicalPlan::Dml(dml) => Some((dml.table_name.clone(), Permission::Write)),
+            LogicalPlan::Ddl(DdlStatement::CreateView(cmd)) => {
+                Some((cmd.name.clone(), Permission::Admin))
+            }
+            LogicalPlan::Ddl(DdlStatement::DropTable(cmd)) => {
+                Some((cmd.name.clone(), Permission::Admin))
+            }
+            LogicalPlan::Ddl(DdlStatement::DropView(cmd)) => {
+                Some((cmd.name.clone(), Permission::Admin))
+            }
+            _ => None,
+        };
+        if let Some((table, permission)) = access {
+            accesses.push((TableRef::from(table).into_owned(), permission));
+        }
+
+        if let LogicalPlan::TableScan(scan) = node {
+            let id = state.resolve(TableRef::from(scan.table_name.clone()));
+            if let Some(view) = state.table(id).and_then(|table| table.as_view()) {
+                table_accesses(state, view.plan(), accesses);
+            }
+        }
+        for expr in node.expressions() {
+            let _ = expr.apply(&mut |expr| {
+                let subquery = match expr {
+                    Expr::ScalarSubquery(subquery) => Some(subquery),
+                    Expr::InSubquery(in_subquery) => Some(&in_subquery.subquery),
+                    Expr::Exists(exists) => Some(&exists.subquery),
+                    _ => None,
+                };
+                if let Some(subquery) = subquery {
+                    table_accesses(state, &subquery.subquery, accesses);
+                }
+                Ok(VisitRecursion::Continue)
+            });
+        }
+        Ok(VisitRecursion::Continue)
+    });
+}
@@ -0,0 +1,360 @@
+use std::{
+    fmt::Display,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use dashmap::DashMap;
+use futures::{stream::AbortHandle, Stream, StreamExt};
+use uuid::Uuid;
+
+/// Execution status of the query associated with a flight ticket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TicketStatus {
+    /// No query has been issued for the ticket.
+    Unknown,
+    /// The ticket has been issued but not yet fetched.
+    Ready,
+    /// The query is being planned for execution.
+    Planning,
+    /// Results are being streamed to the client.
+    Streaming,
+    Finished,
+    Failed(String),
+}
+
+impl TicketStatus {
+    pub fn is_done(&self) -> bool {
+        matches!(self, Self::Finished | Self::Failed(_))
+    }
+}
+
+impl Display for TicketStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unknown => write!(f, "unknown"),
+            Self::Ready => write!(f, "ready"),
+            Self::Planning => write!(f, "planning"),
+            Self::Streaming => write!(f, "streaming"),
+            Self::Finished => write!(f, "finished"),
+            Self::Failed(error) => write!(f, "failed: {}", error),
+        }
+    }
+}
+
+/// Tracks the status of the tickets issued to a connection.
+///
+/// Statuses are keyed by the ticket the client fetches: the statement handle for plans issued by
+/// the server, or the serialized plan for tickets built by the client.
+///
+/// Plans issued through Flight SQL statements are stored here so that the statement handle
+/// sent to the client is a small opaque ID rather than the serialized plan. Statement plans are
+/// discarded once their results have been fetched, or once they are older than the tracker's
+/// TTL if they are never fetched; prepared statements live until closed.
+#[derive(Debug, Clone)]
+pub(crate) struct TaskTracker {
+    tasks: Arc<DashMap<Vec<u8>, TicketStatus>>,
+    // Most recent statement handle issued for each plan, so status can also be looked up by plan
+    latest: Arc<DashMap<Vec<u8>, Vec<u8>>>,
+    plans: Arc<DashMap<Vec<u8>, Vec<u8>>>,
+    // Prepared statement plans before any parameters are bound
+    prepared: Arc<DashMap<Vec<u8>, Vec<u8>>>,
+    // Abort handle for the current execution of each ticket, tagged with an execution ID so that
+    // a finished execution of a prepared statement doesn't affect a later one
+    aborts: Arc<DashMap<Vec<u8>, (u64, AbortHandle)>>,
+    executions: Arc<AtomicU64>,
+    // Output partition fetched by handles that refer to part of a query's results
+    partitions: Arc<DashMap<Vec<u8>, usize>>,
+    issued: Arc<DashMap<Vec<u8>, Instant>>,
+    // SQL text that plans were created from, recorded in the audit log when they're executed
+    statements: Arc<DashMap<Vec<u8>, String>>,
+    // When each ticket's query finished, so its status can be discarded after the TTL
+    completed: Arc<DashMap<Vec<u8>, Instant>>,
+    // Handles that expired recently, so fetching them can report why they're missing
+    expired: Arc<DashMap<Vec<u8>, Instant>>,
+    ttl: Duration,
+}
+
+impl TaskTracker {
+    /// Create a tracker that discards unfetched statement plans after `ttl`.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn new(ttl: Duration) -> Self {
+        let tracker = Self {
+            tasks: Default::default(),
+            latest: Default::default(),
+            plans: Default::default(),
+            prepared: Default::default(),
+            aborts: Default::default(),
+            executions: Default::default(),
+            partitions: Default::default(),
+            issued: Default::default(),
+            statements: Default::default(),
+            completed: Default::default(),
+            expired: Default::default(),
+            ttl,
+        };
+
+        let gc = tracker.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(gc.ttl.max(Duration::from_secs(1)));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                // Stop once every other handle to the tracker has been dropped
+                if Arc::strong_count(&gc.plans) == 1 {
+                    break;
+                }
+                gc.evict_expired();
+            }
+        });
+        tracker
+    }
+
+    pub fn status(&self, ticket: &[u8]) -> TicketStatus {
+        if let Some(status) = self.tasks.get(ticket) {
+            return status.value().clone();
+        }
+        self.latest
+            .get(ticket)
+            .and_then(|handle| self.tasks.get(handle.value()).map(|s| s.value().clone()))
+            .unwrap_or(TicketStatus::Unknown)
+    }
+
+    pub fn set(&self, ticket: &[u8], status: TicketStatus) {
+        if status.is_done() {
+            self.completed.insert(ticket.to_vec(), Instant::now());
+        } else {
+            self.completed.remove(ticket);
+        }
+        self.tasks.insert(ticket.to_vec(), status);
+    }
+
+    /// Store a serialized plan and return the handle that refers to it.
+    pub fn register_plan(&self, plan: Vec<u8>) -> Vec<u8> {
+        let handle = self.insert_plan(plan.clone());
+        self.latest.insert(plan, handle.clone());
+        handle
+    }
+
+    /// Store a serialized plan and return a handle that refers to a single output partition of
+    /// its results.
+    pub fn register_partition(&self, plan: Vec<u8>, partition: usize) -> Vec<u8> {
+        let handle = self.insert_plan(plan);
+        self.partitions.insert(handle.clone(), partition);
+        handle
+    }
+
+    fn insert_plan(&self, plan: Vec<u8>) -> Vec<u8> {
+        let handle = Uuid::new_v4().as_bytes().to_vec();
+        self.plans.insert(handle.clone(), plan);
+        self.issued.insert(handle.clone(), Instant::now());
+        handle
+    }
+
+    /// Output partition that `handle` refers to, if it only refers to part of the results.
+    pub fn partition(&self, handle: &[u8]) -> Option<usize> {
+        self.partitions.get(handle).map(|partition| *partition)
+    }
+
+    /// Store the plan of a prepared statement and return the handle that refers to it.
+    pub fn register_prepared(&self, plan: Vec<u8>) -> Vec<u8> {
+        let handle = Uuid::new_v4().as_bytes().to_vec();
+        self.plans.insert(handle.clone(), plan.clone());
+        self.prepared.insert(handle.clone(), plan);
+        handle
+    }
+
+    /// Plan of the prepared statement `handle` without any bound parameters.
+    pub fn prepared(&self, handle: &[u8]) -> Option<Vec<u8>> {
+        self.prepared.get(handle).map(|plan| plan.value().clone())
+    }
+
+    /// Replace the plan executed for the prepared statement `handle`.
+    pub fn bind(&self, handle: &[u8], plan: Vec<u8>) {
+        self.plans.insert(handle.to_vec(), plan);
+    }
+
+    /// Record the SQL text that the plan stored for `handle` was created from.
+    pub fn set_statement(&self, handle: &[u8], statement: String) {
+        self.statements.insert(handle.to_vec(), statement);
+    }
+
+    /// SQL text that the plan stored for `handle` was created from, if known.
+    pub fn statement(&self, handle: &[u8]) -> Option<String> {
+        self.statements
+            .get(handle)
+            .map(|statement| statement.value().clone())
+    }
+
+    /// Serialized plan stored for `handle`, if any.
+    pub fn plan(&self, handle: &[u8]) -> Option<Vec<u8>> {
+        self.plans.get(handle).map(|plan| plan.value().clone())
+    }
+
+    /// Returns `true` if the plan for `handle` was discarded because it wasn't fetched in time.
+    pub fn is_expired(&self, handle: &[u8]) -> bool {
+        self.expired.contains_key(handle)
+    }
+
+    /// Discard statement plans older than the TTL, and the status of queries that finished more
+    /// than the TTL ago.
+    fn evict_expired(&self) {
+        let now = Instant::now();
+        self.issued.retain(|handle, issued| {
+            if now.duration_since(*issued) < self.ttl {
+                return true;
+            }
+            if let Some((_, plan)) = self.plans.remove(handle) {
+                self.statements.remove(handle);
+                self.partitions.remove(handle);
+                self.latest.remove_if(&plan, |_, latest| latest == handle);
+                self.tasks
+                    .remove_if(handle, |_, status| *status == TicketStatus::Ready);
+                self.expired.insert(handle.clone(), now);
+            }
+            false
+        });
+        self.completed.retain(|ticket, completed| {
+            if now.duration_since(*completed) < self.ttl {
+                return true;
+            }
+            self.tasks.remove_if(ticket, |_, status| status.is_done());
+            false
+        });
+        self.latest
+            .retain(|_, handle| self.tasks.contains_key(handle.as_slice()));
+        // Expired handles are only remembered for one more TTL period
+        self.expired
+            .retain(|_, expired| now.duration_since(*expired) < self.ttl);
+    }
+
+    /// Discard the statement plan stored for `handle` once `stream` reading its results is
+    /// dropped, so that each statement handle can only be fetched once.
+    ///
+    /// Prepared statement plans are kept until the statement is closed.
+    pub fn release_after<S: Stream>(
+        &self,
+        handle: &[u8],
+        stream: S,
+    ) -> impl Stream<Item = S::Item> {
+        let release = Release {
+            tasks: self.clone(),
+            handle: handle.to_vec(),
+        };
+        stream.map(move |item| {
+            let _ = &release;
+            item
+        })
+    }
+
+    fn release(&self, handle: &[u8]) {
+        if self.prepared.contains_key(handle) {
+            return;
+        }
+        self.issued.remove(handle);
+        self.statements.remove(handle);
+        self.partitions.remove(handle);
+        self.plans.remove(handle);
+    }
+
+    /// Remove the plan stored for `handle`, returning whether it existed.
+    pub fn remove_plan(&self, handle: &[u8]) -> bool {
+        self.tasks.remove(handle);
+        self.completed.remove(handle);
+        self.prepared.remove(handle);
+        self.issued.remove(handle);
+        self.partitions.remove(handle);
+        self.statements.remove(handle);
+        self.plans.remove(handle).is_some()
+    }
+
+    /// Cancel the query issued for `handle`.
+    ///
+    /// Returns `false` if the query already finished or was never issued. A cancelled statement's
+    /// handle can't be fetched again, but a prepared statement can still be executed until it's
+    /// closed.
+    pub fn cancel(&self, handle: &[u8]) -> bool {
+        let running = self.tasks.get(handle).map_or(false, |status| {
+            matches!(
+                status.value(),
+                TicketStatus::Ready | TicketStatus::Planning | TicketStatus::Streaming
+            )
+        });
+        if !running {
+            return false;
+        }
+
+        if let Some((_, (_, abort))) = self.aborts.remove(handle) {
+            abort.abort();
+        }
+        self.set(handle, TicketStatus::Failed("query cancelled".to_string()));
+        if !self.prepared.contains_key(handle) {
+            self.plans.remove(handle);
+            self.issued.remove(handle);
+            self.partitions.remove(handle);
+            self.statements.remove(handle);
+        }
+        true
+    }
+
+    /// Mark `ticket` as streaming and update its status when `stream` ends or yields an error.
+    pub fn track<S, T, E>(&self, ticket: &[u8], stream: S) -> impl Stream<Item = Result<T, E>>
+    where
+        S: Stream<Item = Result<T, E>>,
+        E: Display,
+    {
+        self.set(ticket, TicketStatus::Streaming);
+        let (stream, abort) = futures::stream::abortable(stream);
+        let execution = self.executions.fetch_add(1, Ordering::Relaxed);
+        self.aborts.insert(ticket.to_vec(), (execution, abort));
+
+        let (errors, done) = (self.clone(), self.clone());
+        let (key, done_key) = (ticket.to_vec(), ticket.to_vec());
+        let stream = stream.map(move |res| {
+            if let Err(error) = &res {
+                if errors.is_current(&key, execution) {
+                    errors.set(&key, TicketStatus::Failed(error.to_string()));
+                }
+            }
+            res
+        });
+        let finish = futures::stream::once(async move {
+            let current = done
+                .aborts
+                .remove_if(&done_key, |_, (id, _)| *id == execution)
+                .is_some();
+            let running = done
+                .tasks
+                .get(&done_key)
+                .map_or(false, |status| !status.is_done());
+            if current && running {
+                done.set(&done_key, TicketStatus::Finished);
+            }
+            None::<Result<T, E>>
+        });
+        stream.chain(finish.filter_map(futures::future::ready))
+    }
+
+    /// Returns `true` if `execution` is the latest execution of `ticket` and hasn't finished.
+    fn is_current(&self, ticket: &[u8], execution: u64) -> bool {
+        self.aborts
+            .get(ticket)
+            .map_or(false, |entry| entry.value().0 == execution)
+    }
+}
+
+/// Discards a fetched statement plan when dropped.
+struct Release {
+    tasks: TaskTracker,
+    handle: Vec<u8>,
+}
+
+impl Drop for Release {
+    fn drop(&mut self) {
+        self.tasks.release(&self.handle);
+    }
+}
@@ -1,5 +1,6 @@
 use datafusion::arrow::datatypes::SchemaRef;
 use ella_engine::{codec::TableStub, registry::TableId, table::info::TableInfo};
+use prost::bytes::Bytes;
 
 use crate::client::{EllaClient, FlightPublisher};
 
@@ -34,6 +35,16 @@ impl RemoteTable {
         FlightPublisher::new(self.client.clone(), self.id.clone())
     }
 
+    /// Publish the rows of an encoded Arrow IPC stream.
+    ///
+    /// The stream is forwarded to the server without decoding its record batches on the client.
+    /// Only the schema message is checked against the table schema.
+    pub async fn publish_ipc(&self, data: impl Into<Bytes>) -> crate::Result<()> {
+        self.client
+            .publish_ipc(&self.id, &self.arrow_schema()?, data.into())
+            .await
+    }
+
     pub fn as_stub(&self) -> crate::Result<TableStub> {
         Ok(TableStub::new(self.id.clone(), self.arrow_schema()?))
     }
@@ -0,0 +1,85 @@
+mod common;
+
+use common::{assert_denied, run, TestServer, Users};
+use ella_engine::table::{Permission, TableAcl, TableConfig};
+
+async fn start() -> ella_server::Result<TestServer> {
+    let users = Users::default()
+        .with("reader", "reader-pw")
+        .with("writer", "writer-pw")
+        .with("outsider", "outsider-pw")
+        .with("root", "root-pw");
+    let server = TestServer::start_with_users(Default::default(), users, Some("root")).await?;
+
+    let acl = TableAcl::new()
+        .grant("reader", Permission::Read)
+        .grant("writer", Permission::Write);
+    let restricted = common::topic().config(TableConfig::default().with_acl(acl));
+    server
+        .ctx
+        .create_topic("restricted", restricted, false, false)
+        .await?;
+    server
+        .ctx
+        .create_topic("public", common::topic(), false, false)
+        .await?;
+    server
+        .ctx
+        .execute("CREATE VIEW leak AS SELECT * FROM restricted")
+        .await?;
+    Ok(server)
+}
+
+#[tokio::test]
+async fn acl_allows_read_and_denies_write() -> ella_server::Result<()> {
+    let server = start().await?;
+    let insert = "INSERT INTO restricted SELECT time, x FROM public";
+
+    let reader = server.connect_as("reader", "reader-pw").await?;
+    run(&reader, "SELECT * FROM restricted").await?;
+    assert_denied(run(&reader, insert).await);
+
+    let writer = server.connect_as("writer", "writer-pw").await?;
+    run(&writer, "SELECT * FROM restricted").await?;
+    run(&writer, insert).await?;
+
+    let outsider = server.connect_as("outsider", "outsider-pw").await?;
+    assert_denied(run(&outsider, "SELECT * FROM restricted").await);
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn acl_applies_to_subqueries_and_views() -> ella_server::Result<()> {
+    let server = start().await?;
+    let outsider = server.connect_as("outsider", "outsider-pw").await?;
+
+    for sql in [
+        "SELECT * FROM public WHERE x IN (SELECT x FROM restricted)",
+        "SELECT * FROM public WHERE EXISTS (SELECT 1 FROM restricted)",
+        "SELECT (SELECT max(x) FROM restricted) AS m",
+        "SELECT * FROM leak",
+    ] {
+        assert_denied(run(&outsider, sql).await);
+    }
+
+    // Reading through the view only needs read access to the underlying table
+    let reader = server.connect_as("reader", "reader-pw").await?;
+    run(&reader, "SELECT * FROM leak").await?;
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn superuser_requires_verified_credentials() -> ella_server::Result<()> {
+    let server = start().await?;
+
+    let root = server.connect_as("root", "root-pw").await?;
+    run(&root, "SELECT * FROM restricted").await?;
+
+    // Claiming to be the superuser without its password is rejected at the handshake
+    assert!(server.connect_as("root", "wrong").await.is_err());
+    assert!(server.connect_as("root", "").await.is_err());
+
+    server.stop().await
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use common::{assert_denied, run, TestServer};
+use ella_engine::table::{Permission, TableAcl, TableConfig};
+use ella_server::{server::EllaServer, tonic::transport::Server};
+
+fn restricted() -> ella_engine::table::info::TopicBuilder {
+    let acl = TableAcl::new().grant("reader", Permission::Read);
+    common::topic().config(TableConfig::default().with_acl(acl))
+}
+
+#[tokio::test]
+async fn anonymous_server_refuses_to_start_with_acls() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    server
+        .ctx
+        .create_topic("restricted", restricted(), false, false)
+        .await?;
+
+    let result = EllaServer::start(Server::builder(), server.ctx.state().clone(), "127.0.0.1:0");
+    assert!(result.is_err());
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn unverified_user_is_anonymous() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    // Created after the server started, so the startup check doesn't apply
+    server
+        .ctx
+        .create_topic("restricted", restricted(), false, false)
+        .await?;
+
+    // Without a credential store the claimed user name is ignored
+    let client = server.connect_as("reader", "anything").await?;
+    assert_denied(run(&client, "SELECT * FROM restricted").await);
+
+    server.stop().await
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use std::sync::Arc;
+
+use arrow_flight::{
+    decode::FlightRecordBatchStream, flight_service_client::FlightServiceClient, FlightData,
+};
+use common::TestServer;
+use datafusion::arrow::{
+    array::{Array, ArrayData, FixedSizeListArray, Float32Array, TimestampNanosecondArray},
+    datatypes::DataType,
+    record_batch::RecordBatch,
+};
+use ella_common::TensorType;
+use ella_engine::{
+    config::EngineConfig,
+    table::{info::TopicBuilder, ColumnBuilder},
+    EllaConfig,
+};
+use futures::{SinkExt, TryStreamExt};
+
+#[tokio::test]
+async fn flight_results_use_configured_batch_size() -> ella_server::Result<()> {
+    let config = EllaConfig::builder()
+        .engine_config(EngineConfig::builder().batch_size(2))
+        .build();
+    let server = TestServer::start(config).await?;
+    let topic = server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    common::publish(&topic, &[1, 2, 3, 4, 5, 6, 7]).await?;
+    server.ctx.flush("t").await?;
+
+    let client = server.connect().await?;
+    let batches = client
+        .query("SELECT x FROM t")
+        .await?
+        .stream()
+        .await?
+        .into_inner()
+        .try_collect::<Vec<_>>()
+        .await?;
+    assert!(batches.len() >= 4, "expected at least 4 batches");
+    assert!(batches.iter().all(|batch| batch.num_rows() <= 2));
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 7);
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn tensor_results_stay_under_max_message_size() -> ella_server::Result<()> {
+    const MAX_SIZE: usize = 100_000;
+    const ELEMENTS: usize = 10_000;
+    const ROWS: usize = 10;
+
+    let config = EllaConfig::builder()
+        .engine_config(EngineConfig::builder().max_message_size(MAX_SIZE))
+        .build();
+    let server = TestServer::start(config).await?;
+    let info = TopicBuilder::new()
+        .column(ColumnBuilder::new("v", TensorType::Float32).row_shape(ELEMENTS));
+    let topic = server.ctx.create_topic("t", info, false, false).await?;
+
+    // Each row is about 40kB, so only two rows fit in a message
+    let schema = topic.info().arrow_schema();
+    let item = match schema.field(1).data_type() {
+        DataType::FixedSizeList(item, _) => item.clone(),
+        dt => panic!("expected a fixed-size list column, got {:?}", dt),
+    };
+    let time = TimestampNanosecondArray::from(
+        (0..ROWS)
+            .map(|_| ella_common::now().timestamp())
+            .collect::<Vec<_>>(),
+    )
+    .with_timezone("+00:00");
+    let values = Float32Array::from(vec![1.0; ROWS * ELEMENTS]);
+    let tensors = ArrayData::builder(DataType::FixedSizeList(item, ELEMENTS as i32))
+        .len(ROWS)
+        .add_child_data(values.to_data())
+        .build()?;
+    let tensors = FixedSizeListArray::from(tensors);
+    let batch = RecordBatch::try_new(schema, vec![Arc::new(time), Arc::new(tensors)])?;
+    let mut publisher = topic.publish();
+    publisher.send(batch).await?;
+    publisher.close().await?;
+    server.ctx.flush("t").await?;
+
+    let (mut flight, token) = server.flight_sql().await?;
+    let info = flight.execute("SELECT * FROM t".to_string(), None).await?;
+    let ticket = info.endpoint[0]
+        .ticket
+        .clone()
+        .expect("endpoint should have a ticket");
+    let messages = FlightServiceClient::new(server.channel().await?)
+        .do_get(common::authorized(&token, ticket))
+        .await
+        .map_err(ella_server::ClientError::from)?
+        .into_inner()
+        .try_collect::<Vec<FlightData>>()
+        .await
+        .map_err(ella_server::ClientError::from)?;
+    assert!(messages.len() > ROWS / 2, "{} messages", messages.len());
+    for message in &messages {
+        let size = message.data_header.len() + message.data_body.len();
+        assert!(size <= MAX_SIZE, "message of {} bytes", size);
+    }
+
+    let batches = FlightRecordBatchStream::new_from_flight_data(futures::stream::iter(
+        messages.into_iter().map(Ok),
+    ))
+    .try_collect::<Vec<_>>()
+    .await?;
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), ROWS);
+
+    server.stop().await
+}
@@ -0,0 +1,136 @@
+mod common;
+
+use arrow_flight::{
+    flight_service_client::FlightServiceClient,
+    sql::{
+        action_cancel_query_result::CancelResult, ActionCancelQueryRequest,
+        ActionCancelQueryResult, Any, ProstMessageExt,
+    },
+    Action, FlightInfo, Ticket,
+};
+use common::TestServer;
+use futures::TryStreamExt;
+use prost::Message;
+
+/// Create a server with a topic `t` containing the values 1, 2 and 3.
+async fn start() -> ella_server::Result<TestServer> {
+    let server = TestServer::start(Default::default()).await?;
+    let topic = server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    server.ctx.flush("t").await?;
+    Ok(server)
+}
+
+/// Send a `CancelQuery` action for `info` and return whether the server cancelled it.
+async fn cancel(server: &TestServer, token: &str, info: &FlightInfo) -> ella_server::Result<bool> {
+    let request = ActionCancelQueryRequest {
+        info: info.encode_to_vec().into(),
+    };
+    let action = Action {
+        r#type: "CancelQuery".to_string(),
+        body: request.as_any().encode_to_vec().into(),
+    };
+    let body = FlightServiceClient::new(server.channel().await?)
+        .do_action(common::authorized(token, action))
+        .await
+        .map_err(ella_server::ClientError::from)?
+        .into_inner()
+        .message()
+        .await
+        .map_err(ella_server::ClientError::from)?
+        .expect("server should respond to CancelQuery")
+        .body;
+    let result = Any::decode(&*body)?
+        .unpack::<ActionCancelQueryResult>()?
+        .expect("response should be a cancel result");
+    Ok(result.result() == CancelResult::Cancelled)
+}
+
+/// A query with enough rows that its results can't be sent before it's cancelled.
+const LARGE_QUERY: &str =
+    "SELECT a.x FROM t a, t b, t c, t d, t e, t f, t g, t h, t i, t j, t k, t l";
+const LARGE_QUERY_ROWS: usize = 531441;
+
+fn ticket(info: &FlightInfo) -> Ticket {
+    info.endpoint[0]
+        .ticket
+        .clone()
+        .expect("endpoint should have a ticket")
+}
+
+#[tokio::test]
+async fn cancel_only_the_requested_statement() -> ella_server::Result<()> {
+    let server = start().await?;
+    let (mut flight, token) = server.flight_sql().await?;
+
+    // Both statements have the same plan but separate handles
+    let first = flight.execute("SELECT x FROM t".to_string(), None).await?;
+    let second = flight.execute("SELECT x FROM t".to_string(), None).await?;
+    assert!(cancel(&server, &token, &first).await?);
+    assert!(!cancel(&server, &token, &first).await?);
+
+    let cancelled = match flight.do_get(ticket(&first)).await {
+        Ok(stream) => stream.try_collect::<Vec<_>>().await.is_err(),
+        Err(_) => true,
+    };
+    assert!(cancelled, "cancelled statement should not be fetchable");
+
+    let batches = flight
+        .do_get(ticket(&second))
+        .await?
+        .try_collect::<Vec<_>>()
+        .await?;
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 3);
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn cancelled_prepared_statements_can_run_again() -> ella_server::Result<()> {
+    let server = start().await?;
+    let (mut flight, token) = server.flight_sql().await?;
+
+    let mut statement = flight.prepare(LARGE_QUERY.to_string(), None).await?;
+    let info = statement.execute().await?;
+    let running = flight.do_get(ticket(&info)).await?;
+    assert!(cancel(&server, &token, &info).await?);
+    drop(running);
+
+    let mut rerun = flight.do_get(ticket(&info)).await?;
+    assert!(rerun.try_next().await?.is_some());
+    drop(rerun);
+
+    statement.close().await?;
+    server.stop().await
+}
+
+#[tokio::test]
+async fn cancel_handle_follows_derived_queries() -> ella_server::Result<()> {
+    let server = start().await?;
+    let client = server.connect().await?;
+
+    // Cancelling before the query runs discards the statement
+    let (lazy, handle) = client.query_cancelable("SELECT x FROM t").await?;
+    assert!(handle.cancel().await?);
+    assert!(lazy.execute().await.is_err());
+
+    // A derived query sends its own plan, which is what the handle cancels
+    let (lazy, handle) = client.query_cancelable(LARGE_QUERY).await?;
+    let mut stream = lazy.skip(1)?.stream().await?.into_inner();
+    let mut rows = stream
+        .try_next()
+        .await?
+        .expect("query should return rows")
+        .num_rows();
+    assert!(handle.cancel().await?);
+    while let Ok(Some(batch)) = stream.try_next().await {
+        rows += batch.num_rows();
+    }
+    assert!(rows < LARGE_QUERY_ROWS - 1, "read all {} rows", rows);
+    assert!(!handle.cancel().await?);
+
+    server.stop().await
+}
@@ -0,0 +1,222 @@
+#![allow(dead_code)]
+
+use std::{collections::HashMap, net::TcpListener, sync::Arc};
+
+use arrow_flight::sql::client::FlightSqlServiceClient;
+use ella_common::{TensorType, Time};
+use ella_engine::{
+    table::{info::TopicBuilder, ColumnBuilder, EllaTopic},
+    EllaConfig, EllaContext,
+};
+use ella_server::{
+    client::EllaClient,
+    server::{CredentialStore, EllaServer, SqlCapabilities},
+    tonic::transport::{Channel, Server},
+};
+use futures::SinkExt;
+use tempfile::TempDir;
+
+/// A datastore in a temporary directory, served on a free local port.
+pub struct TestServer {
+    pub ctx: EllaContext,
+    pub addr: String,
+    server: EllaServer,
+    capabilities: SqlCapabilities,
+    credentials: Option<(Arc<Users>, Option<String>)>,
+    _dir: TempDir,
+}
+
+impl TestServer {
+    /// Start a server that accepts anonymous connections.
+    pub async fn start(config: EllaConfig) -> ella_server::Result<Self> {
+        Self::start_with_capabilities(config, SqlCapabilities::default()).await
+    }
+
+    /// Start a server that accepts anonymous connections and advertises `capabilities`.
+    pub async fn start_with_capabilities(
+        config: EllaConfig,
+        capabilities: SqlCapabilities,
+    ) -> ella_server::Result<Self> {
+        let (dir, ctx) = create(config).await?;
+        let addr = free_addr();
+        let server = EllaServer::start_with_capabilities(
+            Server::builder(),
+            ctx.state().clone(),
+            addr.as_str(),
+            &capabilities,
+        )?;
+        Ok(Self {
+            ctx,
+            addr,
+            server,
+            capabilities,
+            credentials: None,
+            _dir: dir,
+        })
+    }
+
+    /// Start a server that only accepts the users in `users`.
+    pub async fn start_with_users(
+        config: EllaConfig,
+        users: Users,
+        superuser: Option<&str>,
+    ) -> ella_server::Result<Self> {
+        let (dir, ctx) = create(config).await?;
+        let addr = free_addr();
+        let users = Arc::new(users);
+        let capabilities = SqlCapabilities::default();
+        let server = EllaServer::start_with_credentials(
+            Server::builder(),
+            ctx.state().clone(),
+            addr.as_str(),
+            &capabilities,
+            users.clone(),
+            superuser,
+        )?;
+        Ok(Self {
+            ctx,
+            addr,
+            server,
+            capabilities,
+            credentials: Some((users, superuser.map(str::to_string))),
+            _dir: dir,
+        })
+    }
+
+    /// Stop the server and start a new one on the same address and datastore.
+    ///
+    /// Connection tokens issued by the old server are not accepted by the new one.
+    pub async fn restart(&mut self) -> ella_server::Result<()> {
+        self.server.stop().await?;
+        let state = self.ctx.state().clone();
+        self.server = match &self.credentials {
+            Some((users, superuser)) => EllaServer::start_with_credentials(
+                Server::builder(),
+                state,
+                self.addr.as_str(),
+                &self.capabilities,
+                users.clone(),
+                superuser.as_deref(),
+            )?,
+            None => EllaServer::start_with_capabilities(
+                Server::builder(),
+                state,
+                self.addr.as_str(),
+                &self.capabilities,
+            )?,
+        };
+        Ok(())
+    }
+
+    pub fn uri(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub async fn channel(&self) -> ella_server::Result<Channel> {
+        Ok(Channel::from_shared(self.uri())
+            .expect("server URI should be valid")
+            .connect()
+            .await?)
+    }
+
+    pub async fn connect(&self) -> ella_server::Result<EllaClient> {
+        EllaClient::connect(self.channel().await?).await
+    }
+
+    pub async fn connect_as(&self, user: &str, password: &str) -> ella_server::Result<EllaClient> {
+        EllaClient::connect_with_credentials(self.channel().await?, user, password).await
+    }
+
+    /// Connect a Flight SQL client with an anonymous session, returning it with its token.
+    pub async fn flight_sql(
+        &self,
+    ) -> ella_server::Result<(FlightSqlServiceClient<Channel>, String)> {
+        let mut client = FlightSqlServiceClient::new(self.channel().await?);
+        let token = client.handshake("", "").await?;
+        let token = String::from_utf8(token.to_vec()).expect("token should be UTF-8");
+        client.set_token(token.clone());
+        Ok((client, token))
+    }
+
+    pub async fn stop(mut self) -> ella_server::Result<()> {
+        self.server.stop().await?;
+        self.ctx.shutdown().await
+    }
+}
+
+pub async fn create(config: EllaConfig) -> ella_server::Result<(TempDir, EllaContext)> {
+    let dir = tempfile::tempdir()?;
+    let root = dir.path().to_str().expect("temporary path should be UTF-8");
+    let ctx = ella_engine::create(root, config, false).await?;
+    Ok((dir, ctx))
+}
+
+pub fn free_addr() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind local port");
+    listener
+        .local_addr()
+        .expect("bound listener should have an address")
+        .to_string()
+}
+
+/// Credential store with a fixed set of users.
+#[derive(Debug, Default)]
+pub struct Users(HashMap<String, String>);
+
+impl Users {
+    pub fn with(mut self, user: &str, password: &str) -> Self {
+        self.0.insert(user.to_string(), password.to_string());
+        self
+    }
+}
+
+impl CredentialStore for Users {
+    fn verify(&self, user: &str, password: &str) -> bool {
+        self.0
+            .get(user)
+            .map_or(false, |expected| expected == password)
+    }
+}
+
+/// A topic with a time index and a single `Int32` column `x`.
+pub fn topic() -> TopicBuilder {
+    TopicBuilder::new().column(ColumnBuilder::new("x", TensorType::Int32))
+}
+
+/// Publish `values` to a topic created from [`topic`], one row per value.
+pub async fn publish(topic: &EllaTopic, values: &[i32]) -> ella_server::Result<()> {
+    let mut rows = topic.publish().rows::<(Time, i32)>(1)?;
+    for &x in values {
+        rows.send((ella_common::now(), x)).await?;
+    }
+    rows.close().await
+}
+
+/// Run `sql` through `client` and return the number of rows in the result.
+pub async fn run(client: &EllaClient, sql: &str) -> ella_server::Result<usize> {
+    Ok(client.query(sql).await?.execute().await?.nrows())
+}
+
+/// Assert that `result` failed because the connection lacks a table permission.
+pub fn assert_denied<T: std::fmt::Debug>(result: ella_server::Result<T>) {
+    match result {
+        Ok(value) => panic!("expected permission error, got {:?}", value),
+        Err(err) => assert!(
+            err.to_string().contains("permission required"),
+            "expected permission error, got {}",
+            err
+        ),
+    }
+}
+
+/// Wrap `message` in a request authorized with the session `token`.
+pub fn authorized<T>(token: &str, message: T) -> ella_server::tonic::Request<T> {
+    let mut request = ella_server::tonic::Request::new(message);
+    request.metadata_mut().insert(
+        "authorization",
+        format!("Bearer {}", token)
+            .parse()
+            .expect("token should be valid metadata"),
+    );
+    request
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use common::TestServer;
+
+#[tokio::test]
+async fn drop_tables_schemas_and_catalogs() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    server.ctx.create_catalog("c", false).await?;
+    server.ctx.create_schema("c.s", false).await?;
+    let topic = server
+        .ctx
+        .create_topic("c.s.t", common::topic(), false, false)
+        .await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    server.ctx.flush("c.s.t").await?;
+    drop(topic);
+
+    let mut client = server.connect().await?;
+
+    // Non-empty schemas and catalogs are only dropped with cascade
+    assert!(client.drop_schema("c.s", false, false).await.is_err());
+    assert!(client.drop_catalog("c", false, false).await.is_err());
+
+    client.drop_table("c.s.t".into(), false).await?;
+    assert!(client.get_table("c.s.t".into()).await?.is_none());
+    assert!(client.drop_table("c.s.t".into(), false).await.is_err());
+    client.drop_table("c.s.t".into(), true).await?;
+
+    // The dropped topic's data is deleted, so a new topic with the same name starts empty
+    server
+        .ctx
+        .create_topic("c.s.t", common::topic(), false, false)
+        .await?;
+    assert_eq!(common::run(&client, "SELECT * FROM c.s.t").await?, 0);
+
+    client.drop_catalog("c", false, true).await?;
+    assert!(client.list_schemas("c", None).await?.is_empty());
+    client.drop_catalog("c", true, false).await?;
+
+    server.stop().await
+}
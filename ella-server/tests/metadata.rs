@@ -0,0 +1,353 @@
+mod common;
+
+use arrow_flight::{
+    sql::{client::FlightSqlServiceClient, CommandGetPrimaryKeys, CommandGetTables, SqlInfo},
+    FlightInfo, IpcMessage,
+};
+use common::TestServer;
+use datafusion::arrow::{
+    array::{BinaryArray, BooleanArray, StringArray, UnionArray},
+    datatypes::Schema,
+    record_batch::RecordBatch,
+};
+use ella_engine::table::EllaTable;
+use ella_server::{server::SqlCapabilities, tonic::transport::Channel};
+use futures::TryStreamExt;
+use prost::bytes::Bytes;
+
+/// Fetch every batch from the first endpoint of `info`.
+async fn fetch(
+    flight: &mut FlightSqlServiceClient<Channel>,
+    info: FlightInfo,
+) -> ella_server::Result<Vec<RecordBatch>> {
+    let ticket = info.endpoint[0]
+        .ticket
+        .clone()
+        .expect("endpoint should have a ticket");
+    Ok(flight.do_get(ticket).await?.try_collect::<Vec<_>>().await?)
+}
+
+/// Values of the string column `name` across `batches`.
+fn strings(batches: &[RecordBatch], name: &str) -> Vec<String> {
+    batches
+        .iter()
+        .flat_map(|batch| {
+            let column = batch
+                .column_by_name(name)
+                .unwrap_or_else(|| panic!("missing column {}", name))
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .expect("column should be a string array")
+                .clone();
+            column
+                .iter()
+                .map(|value| value.unwrap_or_default().to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn table_types_cover_every_table() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    server
+        .ctx
+        .execute("CREATE VIEW v AS SELECT x FROM t")
+        .await?;
+    let (mut flight, _) = server.flight_sql().await?;
+
+    let info = flight.get_table_types().await?;
+    let types = strings(&fetch(&mut flight, info).await?, "table_type");
+    assert_eq!(types, EllaTable::KINDS);
+
+    let info = flight
+        .get_tables(CommandGetTables {
+            catalog: None,
+            db_schema_filter_pattern: None,
+            table_name_filter_pattern: None,
+            table_types: Vec::new(),
+            include_schema: false,
+        })
+        .await?;
+    let tables = strings(&fetch(&mut flight, info).await?, "table_type");
+    assert_eq!(tables.len(), 2);
+    for kind in tables {
+        assert!(types.contains(&kind), "unexpected table type {}", kind);
+    }
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn primary_keys_report_time_index() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    server
+        .ctx
+        .execute("CREATE VIEW v AS SELECT x FROM t")
+        .await?;
+    let (mut flight, _) = server.flight_sql().await?;
+
+    let keys = |table: &str| CommandGetPrimaryKeys {
+        catalog: None,
+        db_schema: None,
+        table: table.to_string(),
+    };
+    let info = flight.get_primary_keys(keys("t")).await?;
+    let batches = fetch(&mut flight, info).await?;
+    assert_eq!(strings(&batches, "table_name"), ["t"]);
+    assert_eq!(strings(&batches, "column_name"), ["time"]);
+
+    // Views have no natural key, so they report an empty batch instead of an error
+    let info = flight.get_primary_keys(keys("v")).await?;
+    let batches = fetch(&mut flight, info).await?;
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 0);
+
+    server.stop().await
+}
+
+/// Sorted names of the tables listed for `query`.
+async fn table_names(
+    flight: &mut FlightSqlServiceClient<Channel>,
+    query: CommandGetTables,
+) -> ella_server::Result<Vec<String>> {
+    let info = flight.get_tables(query).await?;
+    let mut names = strings(&fetch(flight, info).await?, "table_name");
+    names.sort();
+    Ok(names)
+}
+
+fn get_tables(catalog: Option<&str>, pattern: Option<&str>, types: &[&str]) -> CommandGetTables {
+    CommandGetTables {
+        catalog: catalog.map(str::to_string),
+        db_schema_filter_pattern: None,
+        table_name_filter_pattern: pattern.map(str::to_string),
+        table_types: types.iter().map(|t| t.to_string()).collect(),
+        include_schema: false,
+    }
+}
+
+#[tokio::test]
+async fn table_listing_applies_filters() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    for name in ["raw_a", "raw_b", "clean"] {
+        server
+            .ctx
+            .create_topic(name, common::topic(), false, false)
+            .await?;
+    }
+    server
+        .ctx
+        .execute("CREATE VIEW raw_v AS SELECT x FROM raw_a")
+        .await?;
+    let (mut flight, _) = server.flight_sql().await?;
+
+    let all = table_names(&mut flight, get_tables(None, None, &[])).await?;
+    assert_eq!(all.len(), 4);
+    let raw = table_names(&mut flight, get_tables(None, Some("raw%"), &[])).await?;
+    assert_eq!(raw, ["raw_a", "raw_b", "raw_v"]);
+    let single = table_names(&mut flight, get_tables(None, Some("r_w_a"), &[])).await?;
+    assert_eq!(single, ["raw_a"]);
+    let views = table_names(&mut flight, get_tables(None, Some("raw%"), &["view"])).await?;
+    assert_eq!(views, ["raw_v"]);
+    let topics = table_names(&mut flight, get_tables(None, None, &["topic"])).await?;
+    assert_eq!(topics, ["clean", "raw_a", "raw_b"]);
+    let missing = table_names(&mut flight, get_tables(Some("missing"), None, &[])).await?;
+    assert!(missing.is_empty());
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn table_schemas_are_only_sent_when_requested() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    let (mut flight, _) = server.flight_sql().await?;
+
+    let info = flight.get_tables(get_tables(None, None, &[])).await?;
+    let batches = fetch(&mut flight, info).await?;
+    assert!(batches
+        .iter()
+        .all(|batch| batch.column_by_name("table_schema").is_none()));
+
+    let mut query = get_tables(None, None, &[]);
+    query.include_schema = true;
+    let info = flight.get_tables(query).await?;
+    let batches = fetch(&mut flight, info).await?;
+    let schemas = batches[0]
+        .column_by_name("table_schema")
+        .expect("table schemas should be included")
+        .as_any()
+        .downcast_ref::<BinaryArray>()
+        .expect("table schemas should be binary");
+    let schema = Schema::try_from(IpcMessage(Bytes::copy_from_slice(schemas.value(0))))?;
+    assert!(schema.field_with_name("x").is_ok());
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn schema_listing_applies_filters() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    for name in ["raw_events", "raw_logs", "clean"] {
+        server.ctx.create_schema(name, false).await?;
+    }
+    let client = server.connect().await?;
+    let catalog = client.default_catalog();
+
+    let mut raw = client
+        .list_schemas(catalog.clone(), Some("raw_%"))
+        .await?
+        .into_iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>();
+    raw.sort();
+    assert_eq!(raw, ["raw_events", "raw_logs"]);
+
+    let all = client.list_schemas(catalog, None).await?;
+    assert!(all.len() >= 4, "expected every schema, got {:?}", all);
+    assert!(client.list_schemas("missing", None).await?.is_empty());
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn hidden_catalogs_are_not_listed() -> ella_server::Result<()> {
+    let capabilities = SqlCapabilities::new().catalog_filter(|catalog, _| catalog != "internal");
+    let server = TestServer::start_with_capabilities(Default::default(), capabilities).await?;
+    server.ctx.create_catalog("internal", false).await?;
+    server.ctx.create_schema("internal.audit", false).await?;
+    server
+        .ctx
+        .create_topic("internal.audit.events", common::topic(), false, false)
+        .await?;
+    server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    let client = server.connect().await?;
+
+    let catalogs = client.list_catalogs(None).await?;
+    assert!(!catalogs.is_empty());
+    assert!(catalogs
+        .iter()
+        .all(|catalog| catalog.to_string() != "internal"));
+    assert!(client.list_schemas("internal", None).await?.is_empty());
+    let tables = client.list_tables_matching("%").await?;
+    assert_eq!(tables.len(), 1, "{:?}", tables);
+
+    // Hidden catalogs can still be queried by name
+    assert_eq!(
+        common::run(&client, "SELECT * FROM internal.audit.events").await?,
+        0
+    );
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn client_lists_catalogs_schemas_and_tables() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    server.ctx.create_catalog("lab", false).await?;
+    server.ctx.create_schema("lab.raw", false).await?;
+    for name in ["lab.raw.a", "lab.raw.b", "lab.raw.other"] {
+        server
+            .ctx
+            .create_topic(name, common::topic(), false, false)
+            .await?;
+    }
+    let client = server.connect().await?;
+
+    let catalogs = client.list_catalogs(Some("l%")).await?;
+    assert_eq!(
+        catalogs.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+        ["lab"]
+    );
+    let schemas = client.list_schemas("lab", None).await?;
+    assert!(schemas.iter().any(|s| s.to_string() == "raw"));
+
+    let mut tables = client
+        .list_tables("lab.raw", Some("_"))
+        .await?
+        .into_iter()
+        .map(|t| t.table.to_string())
+        .collect::<Vec<_>>();
+    tables.sort();
+    assert_eq!(tables, ["a", "b"]);
+    assert_eq!(client.list_tables("lab.raw", None).await?.len(), 3);
+
+    server.stop().await
+}
+
+/// The boolean value of `info` advertised by `server`.
+async fn sql_info_flag(server: &TestServer, info: SqlInfo) -> ella_server::Result<bool> {
+    let (mut flight, _) = server.flight_sql().await?;
+    let info = flight.get_sql_info(vec![info]).await?;
+    let batches = fetch(&mut flight, info).await?;
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 1);
+    let value = batches[0]
+        .column_by_name("value")
+        .expect("missing column value")
+        .as_any()
+        .downcast_ref::<UnionArray>()
+        .expect("value should be a union array")
+        .value(0);
+    Ok(value
+        .as_any()
+        .downcast_ref::<BooleanArray>()
+        .expect("value should be a boolean")
+        .value(0))
+}
+
+#[tokio::test]
+async fn sql_info_reflects_capabilities() -> ella_server::Result<()> {
+    for transactions in [false, true] {
+        let capabilities = SqlCapabilities::new().transactions(transactions);
+        let server = TestServer::start_with_capabilities(Default::default(), capabilities).await?;
+        assert_eq!(
+            sql_info_flag(&server, SqlInfo::SqlTransactionsSupported).await?,
+            transactions
+        );
+        assert!(!sql_info_flag(&server, SqlInfo::FlightSqlServerReadOnly).await?);
+        server.stop().await?;
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_tables_matching_prefix() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    for name in ["sensor_a", "sensor_b", "log_a", "sense"] {
+        server
+            .ctx
+            .create_topic(name, common::topic(), false, false)
+            .await?;
+    }
+    server
+        .ctx
+        .execute("CREATE VIEW sensor_view AS SELECT x FROM sensor_a")
+        .await?;
+    let client = server.connect().await?;
+
+    let mut tables = client
+        .list_tables_matching("sensor%")
+        .await?
+        .into_iter()
+        .map(|id| id.table.to_string())
+        .collect::<Vec<_>>();
+    tables.sort();
+    assert_eq!(tables, ["sensor_a", "sensor_b", "sensor_view"]);
+    assert!(client.list_tables_matching("missing%").await?.is_empty());
+
+    server.stop().await
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use common::TestServer;
+use datafusion::sql::sqlparser::ast::{Ident, ObjectName};
+use ella_engine::registry::TableRef;
+use futures::{SinkExt, TryStreamExt};
+
+#[test]
+fn object_names_follow_sql_identifier_rules() {
+    let name = ObjectName(vec![Ident::new("Sensors"), Ident::new("Readings")]);
+    let table = TableRef::try_from(name).unwrap();
+    assert_eq!(
+        table.schema.map(|s| s.to_string()).as_deref(),
+        Some("sensors")
+    );
+    assert_eq!(table.table.to_string(), "readings");
+
+    let name = ObjectName(vec![Ident::with_quote('"', "Raw Readings")]);
+    let table = TableRef::try_from(name).unwrap();
+    assert_eq!(table.table.to_string(), "Raw Readings");
+
+    let name = ObjectName(vec![
+        Ident::new("a"),
+        Ident::new("b"),
+        Ident::new("c"),
+        Ident::new("d"),
+    ]);
+    assert!(TableRef::try_from(name).is_err());
+    assert!(TableRef::try_from(ObjectName(Vec::new())).is_err());
+}
+
+#[tokio::test]
+async fn publish_to_topic_with_quoted_name() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    let topic = server
+        .ctx
+        .create_topic("Raw Readings", common::topic(), false, false)
+        .await?;
+    common::publish(&topic, &[1]).await?;
+    server.ctx.flush("Raw Readings").await?;
+
+    // Reuse a batch read back from the topic so it has the topic's schema
+    let batches = server
+        .ctx
+        .query("SELECT * FROM \"Raw Readings\"")
+        .await?
+        .stream()
+        .await?
+        .into_inner()
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    let client = server.connect().await?;
+    let mut publisher = client.publish("Raw Readings".into()).await?;
+    for batch in batches {
+        publisher.send(batch).await?;
+    }
+    publisher.close().await?;
+    server.ctx.flush("Raw Readings").await?;
+
+    let rows = common::run(&client, "SELECT x FROM \"Raw Readings\"").await?;
+    assert_eq!(rows, 2);
+
+    server.stop().await
+}
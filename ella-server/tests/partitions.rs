@@ -0,0 +1,48 @@
+mod common;
+
+use common::TestServer;
+use ella_engine::{config::EngineConfig, EllaConfig};
+use futures::TryStreamExt;
+
+#[tokio::test]
+async fn partitioned_results_are_split_across_endpoints() -> ella_server::Result<()> {
+    let config = EllaConfig::builder()
+        .engine_config(
+            EngineConfig::builder()
+                .partitioned_results(true)
+                .target_partitions(4),
+        )
+        .build();
+    let server = TestServer::start(config).await?;
+    let topic = server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    let values = (0..100).collect::<Vec<_>>();
+    common::publish(&topic, &values).await?;
+    server.ctx.flush("t").await?;
+
+    // Filters are repartitioned across the planner's target partitions
+    let sql = "SELECT x FROM t WHERE x >= 0";
+    let (mut flight, _) = server.flight_sql().await?;
+    let info = flight.execute(sql.to_string(), None).await?;
+    assert!(info.endpoint.len() > 1, "{} endpoints", info.endpoint.len());
+    assert!(!info.ordered);
+
+    let mut rows = 0;
+    for endpoint in &info.endpoint {
+        let ticket = endpoint
+            .ticket
+            .clone()
+            .expect("endpoint should have a ticket");
+        let batches = flight.do_get(ticket).await?.try_collect::<Vec<_>>().await?;
+        rows += batches.iter().map(|b| b.num_rows()).sum::<usize>();
+    }
+    assert_eq!(rows, values.len());
+
+    // The client fetches every partition when executing the query
+    let client = server.connect().await?;
+    assert_eq!(common::run(&client, sql).await?, values.len());
+
+    server.stop().await
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::{TestServer, Users};
+use ella_engine::EllaConfig;
+use ella_server::{client::EllaPool, tonic::transport::Endpoint};
+
+fn endpoint(server: &TestServer) -> Endpoint {
+    Endpoint::from_shared(server.uri()).expect("server URI should be valid")
+}
+
+#[tokio::test]
+async fn pool_reuses_connections() -> ella_server::Result<()> {
+    let server = TestServer::start(EllaConfig::default()).await?;
+    let pool = EllaPool::new(endpoint(&server), 2);
+
+    let client = pool.get().await?;
+    client.ping().await?;
+    assert_eq!(pool.idle(), 0);
+    drop(client);
+    assert_eq!(pool.idle(), 1);
+
+    // Checking out again takes the idle connection instead of opening a new one
+    let first = pool.get().await?;
+    assert_eq!(pool.idle(), 0);
+    let second = pool.get().await?;
+    drop(first);
+    drop(second);
+    assert_eq!(pool.idle(), 2);
+
+    drop(pool);
+    server.stop().await
+}
+
+#[tokio::test]
+async fn pool_replaces_failed_connection() -> ella_server::Result<()> {
+    let mut server = TestServer::start(EllaConfig::default()).await?;
+    let pool = EllaPool::new(endpoint(&server), 1);
+    drop(pool.get().await?);
+    assert_eq!(pool.idle(), 1);
+
+    // The restarted server rejects the pooled connection's token, so the pool must reconnect
+    server.restart().await?;
+    let client = pool.get().await?;
+    client.ping().await?;
+    drop(client);
+    assert_eq!(pool.idle(), 1);
+
+    drop(pool);
+    server.stop().await
+}
+
+#[tokio::test]
+async fn pool_authenticates_connections() -> ella_server::Result<()> {
+    let mut server = TestServer::start_with_users(
+        EllaConfig::default(),
+        Users::default().with("alice", "secret"),
+        None,
+    )
+    .await?;
+
+    let pool = EllaPool::new(endpoint(&server), 1);
+    assert!(pool.get().await.is_err());
+
+    let pool = EllaPool::with_credentials(endpoint(&server), 1, "alice", "secret");
+    pool.get().await?.ping().await?;
+
+    // Replacement connections authenticate with the same credentials
+    server.restart().await?;
+    pool.get().await?.ping().await?;
+
+    drop(pool);
+    server.stop().await
+}
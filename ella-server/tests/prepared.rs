@@ -0,0 +1,294 @@
+mod common;
+
+use std::sync::Arc;
+
+use arrow_flight::{
+    encode::FlightDataEncoderBuilder,
+    flight_service_client::FlightServiceClient,
+    sql::{
+        client::FlightSqlServiceClient, Any, Command, CommandPreparedStatementQuery,
+        ProstMessageExt,
+    },
+    FlightData, FlightDescriptor, IpcMessage, Ticket,
+};
+use common::TestServer;
+use datafusion::arrow::{
+    array::{ArrayRef, Int32Array, StringArray},
+    datatypes::{DataType, Schema},
+    record_batch::RecordBatch,
+};
+use ella_server::tonic::{transport::Channel, Code, Status};
+use futures::TryStreamExt;
+use prost::{bytes::Bytes, Message};
+
+/// Create a server with a topic `t` containing the values 1, 2 and 3.
+async fn start() -> ella_server::Result<TestServer> {
+    let server = TestServer::start(Default::default()).await?;
+    let topic = server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    server.ctx.flush("t").await?;
+    Ok(server)
+}
+
+/// Fetch the results for `ticket` and return the number of rows.
+async fn fetch(
+    flight: &mut FlightSqlServiceClient<Channel>,
+    ticket: Ticket,
+) -> ella_server::Result<usize> {
+    let batches = flight.do_get(ticket).await?.try_collect::<Vec<_>>().await?;
+    Ok(batches.iter().map(|b| b.num_rows()).sum())
+}
+
+/// Bind `params` to the prepared statement `handle`, bypassing the client's own checks.
+async fn bind(
+    server: &TestServer,
+    token: &str,
+    handle: Bytes,
+    params: RecordBatch,
+) -> ella_server::Result<Result<(), Status>> {
+    let descriptor = FlightDescriptor::new_cmd(
+        CommandPreparedStatementQuery {
+            prepared_statement_handle: handle,
+        }
+        .as_any()
+        .encode_to_vec(),
+    );
+    let messages = FlightDataEncoderBuilder::new()
+        .build(futures::stream::iter([Ok(params)]))
+        .try_collect::<Vec<_>>()
+        .await?;
+    let header = FlightData::new().with_descriptor(descriptor);
+    let stream = futures::stream::iter(std::iter::once(header).chain(messages));
+    let mut client = FlightServiceClient::new(server.channel().await?);
+    let result = match client.do_put(common::authorized(token, stream)).await {
+        Ok(response) => response.into_inner().message().await.map(|_| ()),
+        Err(status) => Err(status),
+    };
+    Ok(result)
+}
+
+fn params(columns: Vec<ArrayRef>) -> RecordBatch {
+    RecordBatch::try_from_iter(
+        columns
+            .into_iter()
+            .enumerate()
+            .map(|(i, column)| (format!("${}", i + 1), column)),
+    )
+    .expect("parameters should form a valid batch")
+}
+
+#[tokio::test]
+async fn prepared_statements_execute_until_closed() -> ella_server::Result<()> {
+    let server = start().await?;
+    let (mut flight, _) = server.flight_sql().await?;
+
+    let mut statement = flight.prepare("SELECT x FROM t".to_string(), None).await?;
+    let info = statement.execute().await?;
+    let ticket = info.endpoint[0]
+        .ticket
+        .clone()
+        .expect("endpoint should have a ticket");
+
+    // Prepared statements can be fetched more than once
+    assert_eq!(fetch(&mut flight, ticket.clone()).await?, 3);
+    assert_eq!(fetch(&mut flight, ticket.clone()).await?, 3);
+
+    statement.close().await?;
+    let err = fetch(&mut flight, ticket)
+        .await
+        .expect_err("closed statement should not be executable");
+    assert!(err.to_string().contains("not found"), "{}", err);
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn prepared_statement_flight_info() -> ella_server::Result<()> {
+    let server = start().await?;
+    let (mut flight, token) = server.flight_sql().await?;
+
+    let mut statement = flight
+        .prepare("SELECT x, x + 1 AS y FROM t".to_string(), None)
+        .await?;
+    let info = statement.execute().await?;
+    let schema = Schema::try_from(IpcMessage(info.schema.clone()))?;
+    assert_eq!(&schema, statement.dataset_schema()?);
+    assert_eq!(info.endpoint.len(), 1);
+
+    // The endpoint's ticket refers back to the prepared statement
+    let ticket = info.endpoint[0]
+        .ticket
+        .clone()
+        .expect("endpoint should have a ticket");
+    let handle = match Command::try_from(Any::decode(&*ticket.ticket)?)? {
+        Command::CommandPreparedStatementQuery(cmd) => cmd.prepared_statement_handle,
+        cmd => panic!("unexpected ticket command {:?}", cmd),
+    };
+    assert_eq!(fetch(&mut flight, ticket).await?, 3);
+
+    statement.close().await?;
+    let descriptor = FlightDescriptor::new_cmd(
+        CommandPreparedStatementQuery {
+            prepared_statement_handle: handle,
+        }
+        .as_any()
+        .encode_to_vec(),
+    );
+    let status = FlightServiceClient::new(server.channel().await?)
+        .get_flight_info(common::authorized(&token, descriptor))
+        .await
+        .expect_err("closed statement should have no flight info");
+    assert_eq!(status.code(), Code::NotFound);
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn closing_prepared_statements_is_idempotent() -> ella_server::Result<()> {
+    let server = start().await?;
+    let client = server.connect().await?;
+
+    let query = client.prepare("SELECT x FROM t").await?;
+    let (retry, closed) = (query.clone(), query.clone());
+    query.close().await?;
+    retry.close().await?;
+
+    let params = RecordBatch::new_empty(closed.parameter_schema().clone());
+    assert!(closed.execute(params).await.is_err());
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn bind_prepared_statement_parameters() -> ella_server::Result<()> {
+    let server = start().await?;
+    let client = server.connect().await?;
+
+    let query = client.prepare("SELECT x FROM t WHERE x > $1").await?;
+    assert_eq!(query.parameter_schema().fields().len(), 1);
+    assert_eq!(
+        query.parameter_schema().field(0).data_type(),
+        &DataType::Int32
+    );
+
+    // Re-binding replaces the earlier value without changing queries already returned
+    let first = query
+        .execute(params(vec![Arc::new(Int32Array::from(vec![1]))]))
+        .await?;
+    let second = query
+        .execute(params(vec![Arc::new(Int32Array::from(vec![2]))]))
+        .await?;
+    assert_eq!(second.execute().await?.nrows(), 1);
+    assert_eq!(first.execute().await?.nrows(), 2);
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn reject_invalid_parameters() -> ella_server::Result<()> {
+    let server = start().await?;
+    let (mut flight, token) = server.flight_sql().await?;
+
+    let mut statement = flight
+        .prepare("SELECT x FROM t WHERE x > $1".to_string(), None)
+        .await?;
+    let info = statement.execute().await?;
+    let ticket = info.endpoint[0]
+        .ticket
+        .clone()
+        .expect("endpoint should have a ticket");
+    let handle = match Command::try_from(Any::decode(&*ticket.ticket)?)? {
+        Command::CommandPreparedStatementQuery(cmd) => cmd.prepared_statement_handle,
+        cmd => panic!("unexpected ticket command {:?}", cmd),
+    };
+
+    let invalid = [
+        params(vec![
+            Arc::new(Int32Array::from(vec![1])),
+            Arc::new(Int32Array::from(vec![2])),
+        ]),
+        params(vec![Arc::new(StringArray::from(vec!["1"]))]),
+        params(vec![Arc::new(Int32Array::from(vec![1, 2]))]),
+    ];
+    for batch in invalid {
+        let status = bind(&server, &token, handle.clone(), batch)
+            .await?
+            .expect_err("invalid parameters should be rejected");
+        assert_eq!(status.code(), Code::InvalidArgument, "{}", status);
+    }
+
+    bind(
+        &server,
+        &token,
+        handle,
+        params(vec![Arc::new(Int32Array::from(vec![2]))]),
+    )
+    .await?
+    .expect("valid parameters should be bound");
+    assert_eq!(fetch(&mut flight, ticket).await?, 1);
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn client_checks_parameters_before_sending() -> ella_server::Result<()> {
+    let server = start().await?;
+    let client = server.connect().await?;
+
+    let query = client.prepare("SELECT x FROM t WHERE x > $1").await?;
+    assert_eq!(
+        query.arrow_schema().fields().len(),
+        1,
+        "{:?}",
+        query.arrow_schema()
+    );
+    for params in [
+        params(vec![Arc::new(StringArray::from(vec!["1"]))]),
+        params(vec![Arc::new(Int32Array::from(vec![1, 2]))]),
+        RecordBatch::new_empty(Arc::new(Schema::empty())),
+    ] {
+        match query.execute(params).await {
+            Err(ella_server::Error::Client(ella_server::ClientError::InvalidParameters(_))) => {}
+            Err(err) => panic!("expected invalid parameters, got {}", err),
+            Ok(_) => panic!("expected invalid parameters"),
+        }
+    }
+
+    // The same statement can be executed many times
+    for (value, rows) in [(0, 3), (1, 2), (3, 0)] {
+        let lazy = query
+            .execute(params(vec![Arc::new(Int32Array::from(vec![value]))]))
+            .await?;
+        assert_eq!(lazy.execute().await?.nrows(), rows);
+    }
+    query.close().await?;
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn closing_the_client_ends_the_session() -> ella_server::Result<()> {
+    let server = start().await?;
+    let client = server.connect().await?;
+    let query = client.prepare("SELECT x FROM t WHERE x > $1").await?;
+    let session = client.clone();
+    client.close().await?;
+
+    // The session's token is no longer accepted
+    let err = common::run(&session, "SELECT x FROM t").await.unwrap_err();
+    assert!(err.to_string().contains("no active connection"), "{}", err);
+    // Its prepared statements were released with it
+    let result = query
+        .execute(params(vec![Arc::new(Int32Array::from(vec![0]))]))
+        .await;
+    assert!(result.is_err());
+
+    // Other sessions are unaffected
+    let client = server.connect().await?;
+    assert_eq!(common::run(&client, "SELECT x FROM t").await?, 3);
+
+    server.stop().await
+}
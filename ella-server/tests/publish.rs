@@ -0,0 +1,206 @@
+mod common;
+
+use std::sync::Arc;
+
+use common::TestServer;
+use datafusion::arrow::{
+    array::StringArray,
+    datatypes::{DataType, Field, Schema},
+    ipc::writer::StreamWriter,
+    record_batch::RecordBatch,
+};
+use ella_server::{tonic::Code, PublishError};
+use futures::{SinkExt, TryStreamExt};
+
+#[tokio::test]
+async fn publish_rows_to_remote_topic() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    let topic = server
+        .ctx
+        .create_topic("source", common::topic(), false, false)
+        .await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    server.ctx.flush("source").await?;
+    server
+        .ctx
+        .create_topic("sink", common::topic(), false, false)
+        .await?;
+
+    // Reuse batches read back from a topic with the same schema
+    let batches = server
+        .ctx
+        .query("SELECT * FROM source")
+        .await?
+        .stream()
+        .await?
+        .into_inner()
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    let client = server.connect().await?;
+    let mut publisher = client.publish("sink".into()).await?;
+    for batch in batches {
+        publisher.send(batch).await?;
+    }
+    publisher.flush().await?;
+    publisher.close().await?;
+    server.ctx.flush("sink").await?;
+    assert_eq!(common::run(&client, "SELECT * FROM sink").await?, 3);
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn publish_requires_existing_topic() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    server
+        .ctx
+        .execute("CREATE VIEW v AS SELECT x FROM t")
+        .await?;
+    let client = server.connect().await?;
+
+    assert!(client.publish("missing".into()).await.is_err());
+    assert!(client.publish("v".into()).await.is_err());
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn publish_ipc_stream_to_remote_topic() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    let topic = server
+        .ctx
+        .create_topic("source", common::topic(), false, false)
+        .await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    server.ctx.flush("source").await?;
+    server
+        .ctx
+        .create_topic("sink", common::topic(), false, false)
+        .await?;
+
+    let client = server.connect().await?;
+    let sink = client
+        .get_table("sink".into())
+        .await?
+        .expect("sink topic should exist");
+    let schema = sink.arrow_schema()?;
+    let batches = server
+        .ctx
+        .query("SELECT * FROM source")
+        .await?
+        .stream()
+        .await?
+        .into_inner()
+        .try_collect::<Vec<_>>()
+        .await?;
+    let mut data = Vec::new();
+    let mut writer = StreamWriter::try_new(&mut data, &schema)?;
+    for batch in batches {
+        writer.write(&RecordBatch::try_new(
+            schema.clone(),
+            batch.columns().to_vec(),
+        )?)?;
+    }
+    writer.finish()?;
+    drop(writer);
+
+    sink.publish_ipc(data).await?;
+    server.ctx.flush("sink").await?;
+    assert_eq!(common::run(&client, "SELECT * FROM sink").await?, 3);
+    assert_eq!(
+        common::run(&client, "SELECT * FROM sink WHERE x IN (1, 2, 3)").await?,
+        3
+    );
+
+    // Streams with a different schema are rejected before anything is sent
+    let other = Arc::new(Schema::new(vec![Field::new("y", DataType::Utf8, false)]));
+    let mut data = Vec::new();
+    let mut writer = StreamWriter::try_new(&mut data, &other)?;
+    writer.write(&RecordBatch::try_new(
+        other.clone(),
+        vec![Arc::new(StringArray::from(vec!["a"]))],
+    )?)?;
+    writer.finish()?;
+    drop(writer);
+    match sink.publish_ipc(data).await {
+        Err(ella_server::Error::Client(ella_server::ClientError::InvalidIpc(_))) => {}
+        res => panic!("expected invalid IPC stream error, got {:?}", res),
+    }
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn publish_rejection_is_not_retryable() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    let client = server.connect().await?;
+
+    let schema = Arc::new(Schema::new(vec![Field::new("y", DataType::Utf8, false)]));
+    let batch = RecordBatch::try_new(schema, vec![Arc::new(StringArray::from(vec!["a"]))])?;
+    let mut publisher = client.publish("t".into()).await?;
+    // The rejection may already be reported when the batch is sent
+    // The rejection may already be reported when the batch is sent
+    let _ = publisher.send(batch).await;
+    let err = publisher.close().await.unwrap_err();
+    match err {
+        ella_server::Error::Client(ella_server::ClientError::Publish(
+            err @ PublishError::Rejected(_),
+        )) => {
+            assert_eq!(err.status().code(), Code::InvalidArgument, "{}", err);
+            assert!(!err.is_retryable());
+        }
+        err => panic!("expected publish rejection, got {:?}", err),
+    }
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn publish_transport_error_is_retryable() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    let topic = server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    common::publish(&topic, &[1]).await?;
+    server.ctx.flush("t").await?;
+    let batches = server
+        .ctx
+        .query("SELECT * FROM t")
+        .await?
+        .stream()
+        .await?
+        .into_inner()
+        .try_collect::<Vec<_>>()
+        .await?;
+    let client = server.connect().await?;
+    let table = client
+        .get_table("t".into())
+        .await?
+        .expect("topic should exist");
+    server.stop().await?;
+
+    let mut publisher = table.publish();
+    for batch in batches {
+        if publisher.send(batch).await.is_err() {
+            break;
+        }
+    }
+    let err = publisher.close().await.unwrap_err();
+    match err {
+        ella_server::Error::Client(ella_server::ClientError::Publish(
+            err @ PublishError::Transport(_),
+        )) => assert!(err.is_retryable()),
+        err => panic!("expected transport error, got {:?}", err),
+    }
+    Ok(())
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use common::TestServer;
+use futures::{SinkExt, TryStreamExt};
+
+fn assert_read_only<T: std::fmt::Debug>(result: ella_server::Result<T>) {
+    match result {
+        Err(ella_server::Error::Client(ella_server::ClientError::ReadOnly)) => {}
+        res => panic!("expected read-only error, got {:?}", res),
+    }
+}
+
+#[tokio::test]
+async fn read_only_connection_rejects_writes() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    let topic = server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    server.ctx.flush("t").await?;
+    let batches = server
+        .ctx
+        .query("SELECT * FROM t")
+        .await?
+        .stream()
+        .await?
+        .into_inner()
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    let mut client = server.connect().await?;
+    let config = client.config();
+    let engine = config
+        .engine_config()
+        .clone()
+        .into_builder()
+        .read_only(true)
+        .build();
+    client
+        .set_config(config.into_builder().engine_config(engine).build(), false)
+        .await?;
+
+    assert_read_only(client.create_schema("other", false).await);
+    assert_read_only(client.drop_table("t".into(), false).await);
+    let mut publisher = client.publish("t".into()).await?;
+    for batch in batches {
+        if publisher.send(batch).await.is_err() {
+            break;
+        }
+    }
+    assert_read_only(publisher.close().await);
+    assert_eq!(common::run(&client, "SELECT x FROM t").await?, 3);
+
+    // Only the connection that asked for it is read-only
+    let mut other = server.connect().await?;
+    other.create_schema("other", false).await?;
+
+    server.stop().await
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{TestServer, Users};
+use ella_server::client::{EllaClient, RetryPolicy};
+
+#[test]
+fn backoff_doubles_up_to_limit() {
+    let policy =
+        RetryPolicy::new(5).with_backoff(Duration::from_millis(100), Duration::from_secs(1));
+    let waits = (0..5)
+        .map(|attempt| policy.backoff(attempt))
+        .collect::<Vec<_>>();
+    assert_eq!(waits, [100, 200, 400, 800, 1000].map(Duration::from_millis));
+    assert_eq!(policy.backoff(64), Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn only_idempotent_requests_are_retried() -> ella_server::Result<()> {
+    let mut server = TestServer::start(Default::default()).await?;
+    let topic = server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    server.ctx.flush("t").await?;
+
+    let policy =
+        RetryPolicy::new(3).with_backoff(Duration::from_millis(10), Duration::from_millis(50));
+    let mut client = EllaClient::connect_with(server.channel().await?, policy).await?;
+    let mut plain = server.connect().await?;
+    assert_eq!(common::run(&client, "SELECT * FROM t").await?, 3);
+
+    // Restarting the server invalidates every session token
+    server.restart().await?;
+
+    // Dropping a table isn't idempotent, so it fails instead of being retried
+    assert!(client.drop_table("t".into(), false).await.is_err());
+    assert!(server
+        .ctx
+        .state()
+        .table(server.ctx.state().resolve("t".into()))
+        .is_some());
+
+    // Queries reconnect and retry, which also refreshes the session for later requests
+    assert_eq!(common::run(&client, "SELECT * FROM t").await?, 3);
+    client.drop_table("t".into(), false).await?;
+
+    // Clients without a policy don't reconnect
+    assert!(common::run(&plain, "SELECT 1").await.is_err());
+    assert!(plain.drop_table("t".into(), true).await.is_err());
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn reconnect_repeats_handshake_with_credentials() -> ella_server::Result<()> {
+    let users = Users::default().with("reader", "reader-pw");
+    let mut server = TestServer::start_with_users(Default::default(), users, None).await?;
+    let topic = server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    server.ctx.flush("t").await?;
+
+    let client = EllaClient::connect_with_credentials_and_policy(
+        server.channel().await?,
+        "reader",
+        "reader-pw",
+        RetryPolicy::new(3).with_backoff(Duration::from_millis(10), Duration::from_millis(50)),
+    )
+    .await?;
+    server.restart().await?;
+    assert_eq!(common::run(&client, "SELECT * FROM t").await?, 3);
+
+    server.stop().await
+}
@@ -0,0 +1,132 @@
+mod common;
+
+use arrow_flight::{
+    sql::{client::FlightSqlServiceClient, Any, Command, ProstMessageExt},
+    Ticket,
+};
+use common::TestServer;
+use futures::TryStreamExt;
+use prost::Message;
+
+/// A query over `t` nested `depth` levels deep, whose plan is much larger than its SQL.
+fn nested_query(depth: usize) -> String {
+    let mut sql = "SELECT x FROM t".to_string();
+    for i in 0..depth {
+        sql = format!(
+            "SELECT x FROM ({}) AS q{} WHERE x > {} UNION ALL SELECT x FROM t WHERE x < 0",
+            sql,
+            i,
+            -1 - i as i64
+        );
+    }
+    sql
+}
+
+#[tokio::test]
+async fn large_plans_have_small_handles() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    let topic = server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    server.ctx.flush("t").await?;
+
+    let sql = nested_query(32);
+    let client = server.connect().await?;
+    let query = client.query(sql.clone()).await?;
+    assert!(query.plan().to_bytes().len() > 4096);
+
+    // Executing the same query twice sends the handle first and the full plan after that
+    assert_eq!(query.clone().execute().await?.nrows(), 3);
+    assert_eq!(query.execute().await?.nrows(), 3);
+
+    let mut flight = FlightSqlServiceClient::new(server.channel().await?);
+    let token = flight.handshake("", "").await?;
+    flight.set_token(String::from_utf8(token.to_vec()).expect("token should be UTF-8"));
+    let info = flight.execute(sql, None).await?;
+    let ticket = info.endpoint[0]
+        .ticket
+        .clone()
+        .expect("endpoint should have a ticket");
+    let handle = match Command::try_from(Any::decode(&*ticket.ticket)?)? {
+        Command::TicketStatementQuery(query) => query.statement_handle,
+        cmd => panic!("unexpected ticket command {:?}", cmd),
+    };
+    assert!(handle.len() <= 16, "handle is {} bytes", handle.len());
+
+    let batches = flight
+        .do_get(ticket.clone())
+        .await?
+        .try_collect::<Vec<_>>()
+        .await?;
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 3);
+
+    // The server discards the plan once its results have been fetched
+    let refetch = Ticket {
+        ticket: arrow_flight::sql::TicketStatementQuery {
+            statement_handle: handle,
+        }
+        .as_any()
+        .encode_to_vec()
+        .into(),
+    };
+    let failed = match flight.do_get(refetch).await {
+        Ok(stream) => stream.try_collect::<Vec<_>>().await.is_err(),
+        Err(_) => true,
+    };
+    assert!(failed, "fetched statement handle should be discarded");
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn remote_query_properties_report_ordering() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    let client = server.connect().await?;
+
+    let props = client
+        .query("SELECT time, x FROM t ORDER BY x DESC")
+        .await?
+        .properties();
+    let ordering = props
+        .ordering()
+        .iter()
+        .map(|index| (index.column.as_str(), index.ascending))
+        .collect::<Vec<_>>();
+    assert_eq!(ordering, [("x", false)]);
+    assert_eq!(props.max_rows(), None);
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn exists_checks_for_matching_rows() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    let topic = server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    server
+        .ctx
+        .create_topic("empty", common::topic(), false, false)
+        .await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    server.ctx.flush("t").await?;
+    let client = server.connect().await?;
+
+    assert!(client.exists("SELECT x FROM t WHERE x > 1").await?);
+    assert!(
+        client
+            .exists("SELECT * FROM t ORDER BY x DESC LIMIT 2")
+            .await?
+    );
+    assert!(!client.exists("SELECT x FROM t WHERE x > 100").await?);
+    assert!(!client.exists("SELECT * FROM empty").await?);
+
+    server.stop().await
+}
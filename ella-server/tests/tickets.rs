@@ -0,0 +1,76 @@
+mod common;
+
+use common::TestServer;
+use ella_common::Duration;
+use ella_engine::{config::EngineConfig, EllaConfig};
+use ella_server::server::TicketStatus;
+use futures::TryStreamExt;
+
+#[tokio::test]
+async fn ticket_status_follows_query() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    let topic = server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    let client = server.connect().await?;
+
+    let query = client.query("SELECT * FROM t").await?;
+    let plan = query.plan().clone();
+    assert_eq!(client.ticket_status(&plan).await?, TicketStatus::Ready);
+
+    query.execute().await?;
+    assert_eq!(client.ticket_status(&plan).await?, TicketStatus::Finished);
+
+    // Plans that were never issued to this connection have no status
+    let other = server.ctx.query("SELECT x FROM t").await?;
+    assert_eq!(
+        client.ticket_status(other.plan()).await?,
+        TicketStatus::Unknown
+    );
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn tickets_expire_after_ttl() -> ella_server::Result<()> {
+    let config = EllaConfig::builder()
+        .engine_config(EngineConfig::builder().ticket_ttl(Duration::seconds(1)))
+        .build();
+    let server = TestServer::start(config).await?;
+    let topic = server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+
+    let client = server.connect().await?;
+    let query = client.query("SELECT * FROM t").await?;
+    let plan = query.plan().clone();
+    query.execute().await?;
+    assert_eq!(client.ticket_status(&plan).await?, TicketStatus::Finished);
+
+    let (mut flight, _) = server.flight_sql().await?;
+    let info = flight.execute("SELECT x FROM t".to_string(), None).await?;
+    let ticket = info.endpoint[0]
+        .ticket
+        .clone()
+        .expect("endpoint should have a ticket");
+
+    tokio::time::sleep(std::time::Duration::from_millis(2500)).await;
+
+    // Both unfetched tickets and the status of finished queries are discarded
+    assert_eq!(client.ticket_status(&plan).await?, TicketStatus::Unknown);
+    let err = match flight.do_get(ticket).await {
+        Ok(stream) => stream
+            .try_collect::<Vec<_>>()
+            .await
+            .expect_err("expired ticket should not be fetchable")
+            .to_string(),
+        Err(err) => err.to_string(),
+    };
+    assert!(err.contains("expired"), "{}", err);
+
+    server.stop().await
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "tls")]
+
+mod common;
+
+use ella_server::{
+    client::{EllaClient, TlsConfig},
+    server::EllaServer,
+    tonic::transport::{Certificate, Identity, Server, ServerTlsConfig},
+};
+
+const CA: &str = include_str!("certs/ca.pem");
+const OTHER_CA: &str = include_str!("certs/other-ca.pem");
+const SERVER_CERT: &str = include_str!("certs/server.pem");
+const SERVER_KEY: &str = include_str!("certs/server.key");
+const CLIENT_CERT: &str = include_str!("certs/client.pem");
+const CLIENT_KEY: &str = include_str!("certs/client.key");
+
+/// Start a TLS server, requiring client certificates signed by the test CA if `mutual` is set.
+async fn start(
+    mutual: bool,
+) -> ella_server::Result<(
+    tempfile::TempDir,
+    ella_engine::EllaContext,
+    EllaServer,
+    String,
+)> {
+    let (dir, ctx) = common::create(Default::default()).await?;
+    let topic = ctx.create_topic("t", common::topic(), false, false).await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    ctx.flush("t").await?;
+
+    let mut tls = ServerTlsConfig::new().identity(Identity::from_pem(SERVER_CERT, SERVER_KEY));
+    if mutual {
+        tls = tls.client_ca_root(Certificate::from_pem(CA));
+    }
+    let addr = common::free_addr();
+    let port = addr.rsplit(':').next().expect("address should have a port");
+    let uri = format!("https://localhost:{}", port);
+    let server = EllaServer::start(
+        Server::builder().tls_config(tls)?,
+        ctx.state().clone(),
+        addr.as_str(),
+    )?;
+    Ok((dir, ctx, server, uri))
+}
+
+#[tokio::test]
+async fn connect_with_tls() -> ella_server::Result<()> {
+    let (_dir, ctx, mut server, uri) = start(false).await?;
+
+    let client = EllaClient::connect_tls(&uri, TlsConfig::new().ca_certificate(CA)).await?;
+    assert_eq!(common::run(&client, "SELECT * FROM t").await?, 3);
+
+    // The server's certificate must be signed by the configured CA and match the domain
+    assert!(
+        EllaClient::connect_tls(&uri, TlsConfig::new().ca_certificate(OTHER_CA))
+            .await
+            .is_err()
+    );
+    let wrong_domain = TlsConfig::new().ca_certificate(CA).domain("example.com");
+    assert!(EllaClient::connect_tls(&uri, wrong_domain).await.is_err());
+
+    server.stop().await?;
+    ctx.shutdown().await
+}
+
+#[tokio::test]
+async fn connect_with_mutual_tls() -> ella_server::Result<()> {
+    let (_dir, ctx, mut server, uri) = start(true).await?;
+
+    let anonymous = TlsConfig::new().ca_certificate(CA);
+    assert!(EllaClient::connect_tls(&uri, anonymous).await.is_err());
+
+    let config = TlsConfig::new()
+        .ca_certificate(CA)
+        .identity(CLIENT_CERT, CLIENT_KEY);
+    let client = EllaClient::connect_tls(&uri, config).await?;
+    assert_eq!(common::run(&client, "SELECT * FROM t").await?, 3);
+
+    server.stop().await?;
+    ctx.shutdown().await
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use arrow_flight::{
+    flight_service_client::FlightServiceClient,
+    sql::{CommandStatementUpdate, ProstMessageExt},
+    FlightData, FlightDescriptor,
+};
+use common::TestServer;
+use ella_server::tonic::{Code, Status};
+use prost::Message;
+
+/// Execute `sql` as an update with a raw Flight client so that the server's status is returned.
+async fn update(
+    server: &TestServer,
+    token: &str,
+    sql: &str,
+) -> ella_server::Result<Result<(), Status>> {
+    let descriptor = FlightDescriptor::new_cmd(
+        CommandStatementUpdate {
+            query: sql.to_string(),
+            transaction_id: None,
+        }
+        .as_any()
+        .encode_to_vec(),
+    );
+    let header = FlightData::new().with_descriptor(descriptor);
+    let stream = futures::stream::iter([header]);
+    let mut client = FlightServiceClient::new(server.channel().await?);
+    let result = match client.do_put(common::authorized(token, stream)).await {
+        Ok(response) => response.into_inner().message().await.map(|_| ()),
+        Err(status) => Err(status),
+    };
+    Ok(result)
+}
+
+#[tokio::test]
+async fn execute_update_statements() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    let topic = server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    server.ctx.flush("t").await?;
+    let (mut flight, _) = server.flight_sql().await?;
+
+    let rows = flight
+        .execute_update("INSERT INTO t SELECT time, x + 10 FROM t".to_string(), None)
+        .await?;
+    assert_eq!(rows, 3);
+    server.ctx.flush("t").await?;
+
+    let rows = flight
+        .execute_update(
+            "INSERT INTO t VALUES \
+            (TIMESTAMP '2023-01-01 00:00:00', 100), \
+            (TIMESTAMP '2023-01-01 00:00:01', 101)"
+                .to_string(),
+            None,
+        )
+        .await?;
+    assert_eq!(rows, 2);
+    server.ctx.flush("t").await?;
+
+    // Errors are reported to the client instead of taking down the server
+    assert!(flight
+        .execute_update(
+            "INSERT INTO missing SELECT time, x FROM t".to_string(),
+            None
+        )
+        .await
+        .is_err());
+
+    let client = server.connect().await?;
+    assert_eq!(common::run(&client, "SELECT * FROM t").await?, 8);
+    assert_eq!(
+        common::run(&client, "SELECT * FROM t WHERE x >= 100").await?,
+        2
+    );
+
+    server.stop().await
+}
+
+#[tokio::test]
+async fn reject_unsupported_statements() -> ella_server::Result<()> {
+    let server = TestServer::start(Default::default()).await?;
+    let topic = server
+        .ctx
+        .create_topic("t", common::topic(), false, false)
+        .await?;
+    common::publish(&topic, &[1, 2, 3]).await?;
+    server.ctx.flush("t").await?;
+    let (_, token) = server.flight_sql().await?;
+
+    for sql in ["SET datafusion.execution.batch_size = 1024", "DESCRIBE t"] {
+        let status = update(&server, &token, sql)
+            .await?
+            .expect_err("statement should be rejected");
+        assert_eq!(status.code(), Code::Unimplemented, "{}", status);
+        assert!(status.message().contains(sql), "{}", status);
+    }
+
+    // The server is still running after rejecting them
+    let client = server.connect().await?;
+    assert_eq!(common::run(&client, "SELECT * FROM t").await?, 3);
+
+    server.stop().await
+}
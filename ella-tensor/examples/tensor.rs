@@ -7,7 +7,7 @@ fn main() {
 
     let mask = Tensor::range(0_i32, 100, 1) % 2;
     let y = y.with_mask(mask.eq(0));
-    let z = x.unsqueeze(0) * y.unsqueeze(-1);
+    let z = x.unsqueeze(0).unwrap() * y.unsqueeze(-1).unwrap();
     println!("{:?}", z);
 
     let s1 = tensor::tensor!["A".to_string(), "B".to_string(), "C".to_string()];
@@ -16,7 +16,7 @@ fn main() {
     println!("{:?}", s1.eq(s2));
 
     let df = tensor::frame!(
-        x = Tensor::linspace(0_f32, 10., 100).cos().reshape((10, 10)),
+        x = Tensor::linspace(0_f32, 10., 100).cos().reshape((10, 10)).unwrap(),
         y = Tensor::linspace(0_f32, 10., 10).sin(),
     );
     println!("{}", df.pretty_print());
@@ -183,8 +183,12 @@ where
     S: Shape,
 {
     if T::TENSOR_TYPE.to_arrow() == col.tensor_type().to_arrow() {
+        let data = col.data();
+        if !T::NULLABLE && data.null_count() > 0 {
+            return Err(crate::Error::UnexpectedNull(T::TENSOR_TYPE));
+        }
         Ok(Tensor::new(
-            T::from_array_data(col.data()),
+            T::from_array_data(data),
             S::from_shape(&col.shape())?,
             S::from_shape(&col.strides())?,
         ))
@@ -6,7 +6,7 @@ use arrow::{
 };
 use ella_common::row::RowFormat;
 
-use crate::{tensor_schema, NamedColumn, Shape, Tensor, TensorValue};
+use crate::{tensor_schema, MaskedValue, NamedColumn, Shape, Tensor, TensorValue};
 
 use super::{batch_to_columns, frame_to_batch, print::print_frames, Frame};
 
@@ -17,6 +17,10 @@ pub struct DataFrame {
 }
 
 impl DataFrame {
+    /// Returns the column `name` as a tensor.
+    ///
+    /// Columns containing nulls must be collected using a masked type (e.g. `Option<f64>`),
+    /// otherwise an error is returned. Use [`DataFrame::col_or`] to replace nulls with a fill value.
     pub fn col<T, S>(&self, name: &str) -> crate::Result<Tensor<T, S>>
     where
         T: TensorValue,
@@ -30,6 +34,16 @@ impl DataFrame {
         Err(crate::Error::ColumnLookup(name.to_string()))
     }
 
+    /// Returns the column `name` as a tensor with any null values replaced by `fill`.
+    pub fn col_or<T, S>(&self, name: &str, fill: T) -> crate::Result<Tensor<T, S>>
+    where
+        T: TensorValue,
+        T::Masked: MaskedValue<Unmasked = T>,
+        S: Shape,
+    {
+        Ok(self.col::<T::Masked, S>(name)?.fill_masked(fill))
+    }
+
     pub fn icol<T, S>(&self, col: usize) -> crate::Result<Tensor<T, S>>
     where
         T: TensorValue,
@@ -131,3 +145,39 @@ impl FromIterator<NamedColumn> for DataFrame {
         Self { columns, rows }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use arrow::{
+        array::Float64Array,
+        datatypes::{DataType, Field, Schema},
+        record_batch::RecordBatch,
+    };
+
+    use crate::{Const, DataFrame, Tensor};
+
+    #[test]
+    fn test_nullable_column() {
+        let schema = Schema::new(vec![Field::new("x", DataType::Float64, true)]);
+        let values = Float64Array::from(vec![Some(1.0), None, Some(3.0)]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+        let df = DataFrame::try_from(batch).unwrap();
+
+        let masked = df.col::<Option<f64>, Const<1>>("x").unwrap();
+        crate::assert_tensor_eq!(
+            Tensor::from(masked.mask()),
+            crate::tensor![true, false, true]
+        );
+        crate::assert_tensor_eq!(masked.fill_masked(-1.0), crate::tensor![1.0, -1.0, 3.0]);
+
+        let filled = df.col_or::<f64, Const<1>>("x", 0.0).unwrap();
+        crate::assert_tensor_eq!(filled, crate::tensor![1.0, 0.0, 3.0]);
+
+        assert!(matches!(
+            df.col::<f64, Const<1>>("x"),
+            Err(crate::Error::UnexpectedNull(_))
+        ));
+    }
+}
@@ -15,6 +15,9 @@ pub use column::{tensor_schema, Column, ColumnRef, NamedColumn};
 pub use ella_common::shape;
 pub use frame::{DataFrame, Frame};
 pub use mask::Mask;
+#[cfg(feature = "rand")]
+pub use ops::Distribution;
+pub use ops::PadMode;
 pub use shape::{Axis, Const, Dyn, IntoShape, RemoveAxis, Shape};
 pub use slice::{NewAxis, Slice};
 pub use tensor::{Tensor, Tensor1, Tensor2, Tensor3, Tensor4, TensorD};
@@ -6,13 +6,21 @@ mod combine;
 mod constructors;
 mod convert;
 mod index;
+mod linalg;
 mod masked;
+#[cfg(feature = "rand")]
+mod random;
 mod reduce;
 mod scatter;
 mod shape;
+mod signal;
 mod slice;
 mod unary_arith;
 
+#[cfg(feature = "rand")]
+pub use random::Distribution;
+pub use shape::PadMode;
+
 use crate::{shape::NdimMax, Shape, Tensor, TensorValue};
 use ella_common::ops::{TensorOp, TensorUnaryOp};
 
@@ -45,7 +53,7 @@ where
             Tensor::from_trusted_len_iter(a.iter().zip(b.iter()).map(|(a, b)| op(a, b)), shape)
         }
     } else {
-        let (a, b) = a.broadcast_with(b).unwrap();
+        let (a, b) = a.broadcast_with(b).unwrap_or_else(|err| panic!("{}", err));
         let shape = a.shape().clone();
         unsafe {
             Tensor::from_trusted_len_iter(a.iter().zip(b.iter()).map(|(a, b)| op(a, b)), shape)
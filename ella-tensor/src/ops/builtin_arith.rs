@@ -176,3 +176,52 @@ impl_binary_op!(
     [Div    / [int float]]
     [Rem    % [int float]]
 );
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn test_broadcast_ops() {
+        let x = crate::tensor![[1, 2, 3], [4, 5, 6]];
+        crate::assert_tensor_eq!(
+            &x + crate::tensor![10, 20, 30],
+            crate::tensor![[11, 22, 33], [14, 25, 36]]
+        );
+        crate::assert_tensor_eq!(
+            &x - crate::tensor![[1], [4]],
+            crate::tensor![[0, 1, 2], [0, 1, 2]]
+        );
+        crate::assert_tensor_eq!(
+            crate::tensor![[1.0], [2.0]] * crate::tensor![1.0, 2.0, 3.0],
+            crate::tensor![[1.0, 2.0, 3.0], [2.0, 4.0, 6.0]]
+        );
+    }
+
+    #[test]
+    fn test_scalar_ops() {
+        let x = crate::tensor![[1, 2, 3], [4, 5, 6]];
+        crate::assert_tensor_eq!(&x * 2, crate::tensor![[2, 4, 6], [8, 10, 12]]);
+        crate::assert_tensor_eq!(10 - &x, crate::tensor![[9, 8, 7], [6, 5, 4]]);
+        crate::assert_tensor_eq!(crate::tensor![1.0, 2.0] / 2.0, crate::tensor![0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_masked_ops() {
+        let x = crate::tensor![1, 2, 3].with_mask(crate::tensor![true, false, true]);
+        let y = &x + crate::tensor![[10], [20]];
+        assert_eq!(y.shape().as_ref(), &[2, 3]);
+        assert_eq!(
+            y.iter().collect::<Vec<_>>(),
+            vec![Some(11), None, Some(13), Some(21), None, Some(23)]
+        );
+        assert_eq!(
+            (x * 2).iter().collect::<Vec<_>>(),
+            vec![Some(2), None, Some(6)]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_broadcast_incompatible() {
+        let _ = crate::tensor![1, 2, 3] + crate::tensor![1, 2];
+    }
+}
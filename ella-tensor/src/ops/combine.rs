@@ -37,10 +37,11 @@ where
             return Err(crate::Error::EmptyList);
         }
         let mut shape = tensors[0].shape().clone();
-        let ax = axis.index(&shape);
-        if ax >= shape.ndim() {
+        let ndim = shape.ndim() as isize;
+        if axis.0 >= ndim || axis.0 < -ndim {
             return Err(crate::Error::AxisOutOfBounds(axis.0, shape.ndim()));
         }
+        let ax = axis.index(&shape);
         let common_shape = shape.remove_axis(axis);
         if tensors
             .iter()
@@ -70,10 +71,12 @@ where
             return Err(crate::Error::EmptyList);
         }
         let common_shape = tensors[0].shape();
-        let ax = axis.insert_index(common_shape);
-        if ax > common_shape.ndim() {
+        // The new axis can be inserted after the last existing axis
+        let ndim = common_shape.ndim() as isize;
+        if axis.0 > ndim || axis.0 < -(ndim + 1) {
             return Err(crate::Error::AxisOutOfBounds(axis.0, common_shape.ndim()));
         }
+        let ax = axis.insert_index(common_shape);
         let mut shape = common_shape.insert_axis(axis);
         if tensors.iter().any(|t| t.shape() != common_shape) {
             return Err(crate::ShapeError::incompatible(common_shape.as_ref()).into());
@@ -222,7 +225,7 @@ where
 
 #[cfg(test)]
 mod test {
-    use crate::{Axis, Tensor};
+    use crate::{Axis, Const, Tensor};
 
     #[test]
     fn test_stack() {
@@ -265,7 +268,63 @@ mod test {
         assert!(x.eq(&c).all(), "{:?} != {:?}", x, c);
 
         let c = crate::concat!(-1, x.index_axis(Axis(1), 0), x.index_axis(Axis(1), 1));
-        let reshaped = x.reshape((3, 6));
+        let reshaped = x.reshape((3, 6)).unwrap();
         assert!(reshaped.eq(&c).all(), "{:?} != {:?}", reshaped, c);
     }
+
+    #[test]
+    fn test_concat_edge_cases() {
+        let x = crate::tensor![[1, 2, 3], [4, 5, 6]];
+        let y = crate::tensor![[7, 8], [9, 10]];
+
+        let res = Tensor::<i32, Const<2>>::concat(Axis(0), &[]);
+        assert!(matches!(res, Err(crate::Error::EmptyList)), "{:?}", res);
+
+        let c = Tensor::concat(Axis(1), &[x.clone()]).unwrap();
+        assert!(x.eq(&c).all(), "{:?} != {:?}", x, c);
+
+        let c = Tensor::concat(Axis(1), &[x.clone(), y.clone()]).unwrap();
+        assert_eq!(c.shape().slice(), &[2, 5]);
+
+        let res = Tensor::concat(Axis(0), &[x.clone(), y]);
+        assert!(matches!(res, Err(crate::Error::Shape(_))), "{:?}", res);
+
+        for ax in [2, -3] {
+            let res = Tensor::concat(Axis(ax), &[x.clone(), x.clone()]);
+            assert!(
+                matches!(res, Err(crate::Error::AxisOutOfBounds(a, 2)) if a == ax),
+                "{:?}",
+                res
+            );
+        }
+    }
+
+    #[test]
+    fn test_stack_edge_cases() {
+        let x = crate::tensor![[1, 2, 3], [4, 5, 6]];
+        let y = crate::tensor![[7, 8, 9], [10, 11, 12]];
+
+        let res = Tensor::<i32, Const<2>>::stack(Axis(0), &[]);
+        assert!(matches!(res, Err(crate::Error::EmptyList)), "{:?}", res);
+
+        let c = Tensor::stack(Axis(2), &[x.clone(), y.clone()]).unwrap();
+        assert_eq!(c.shape().slice(), &[2, 3, 2]);
+        assert!(c.index_axis(Axis(2), 1).eq(&y).all(), "{:?}", c);
+
+        let c = Tensor::stack(Axis(-3), &[x.clone(), y]).unwrap();
+        assert_eq!(c.shape().slice(), &[2, 2, 3]);
+
+        let ragged = crate::tensor![[1, 2], [3, 4]];
+        let res = Tensor::stack(Axis(0), &[x.clone(), ragged]);
+        assert!(matches!(res, Err(crate::Error::Shape(_))), "{:?}", res);
+
+        for ax in [3, -4] {
+            let res = Tensor::stack(Axis(ax), &[x.clone(), x.clone()]);
+            assert!(
+                matches!(res, Err(crate::Error::AxisOutOfBounds(a, 2)) if a == ax),
+                "{:?}",
+                res
+            );
+        }
+    }
 }
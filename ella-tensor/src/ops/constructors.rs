@@ -35,6 +35,22 @@ where
         Self::full(shape, T::one())
     }
 
+    /// Collect `iter` into a tensor with the given shape.
+    ///
+    /// Returns an error if the number of elements doesn't match the size of `shape`.
+    pub fn from_iter_shape<It, I>(iter: It, shape: I) -> crate::Result<Self>
+    where
+        It: IntoIterator<Item = T>,
+        I: IntoShape<Shape = S>,
+    {
+        let shape = shape.into_shape();
+        let values = iter.into_iter().collect::<Vec<_>>();
+        if values.len() != shape.size() {
+            return Err(crate::ShapeError::array_size(values.len(), shape.as_ref()).into());
+        }
+        Ok(unsafe { Tensor::from_trusted_len_iter(values, shape) })
+    }
+
     pub(crate) unsafe fn from_trusted_len_iter<I>(iter: I, shape: S) -> Self
     where
         I: IntoIterator<Item = T>,
@@ -50,16 +66,43 @@ impl<T> Tensor<T, Const<1>>
 where
     T: TensorValue,
 {
+    /// `steps` evenly spaced values from `start` to `end`, inclusive.
     pub fn linspace(start: T, end: T, steps: usize) -> Self
     where
         T: Float,
     {
-        let step_size = (end - start) / T::from(steps).unwrap();
-        let values = (0..steps).map(|i| start + step_size * T::from(i).unwrap());
+        let step_size = if steps > 1 {
+            (end - start) / T::from(steps - 1).unwrap()
+        } else {
+            T::zero()
+        };
+        let values = (0..steps).map(|i| {
+            if i + 1 == steps && steps > 1 {
+                end
+            } else {
+                start + step_size * T::from(i).unwrap()
+            }
+        });
         let shape = Const([steps]);
         unsafe { Tensor::from_trusted_len_iter(values, shape) }
     }
 
+    /// Values from `start` up to but excluding `stop`, spaced by `step`.
+    ///
+    /// `step` may be negative to produce a decreasing sequence. A zero step produces an empty tensor.
+    pub fn arange(start: T, stop: T, step: T) -> Self
+    where
+        T: Num,
+    {
+        let increasing = step > T::zero();
+        if step == T::zero() {
+            return Vec::<T>::new().into();
+        }
+        let values = std::iter::successors(Some(start), |x| Some(x.clone() + step.clone()))
+            .take_while(|x| if increasing { *x < stop } else { *x > stop });
+        values.collect()
+    }
+
     pub fn range(start: T, end: T, step: T) -> Self
     where
         T: Num,
@@ -91,6 +134,25 @@ where
             .map(|Const([r, c])| if r == c { T::one() } else { T::zero() });
         unsafe { Tensor::from_trusted_len_iter(iter, shape) }
     }
+
+    /// A matrix with the same shape as `self` with ones on a diagonal and zeros elsewhere.
+    ///
+    /// An `offset` of 0 selects the main diagonal, a positive offset a diagonal above it and
+    /// a negative offset a diagonal below it.
+    pub fn eye_like(&self, offset: isize) -> Self
+    where
+        T: One + Zero,
+    {
+        let shape = self.shape().clone();
+        let iter = shape.indices().map(|Const([r, c])| {
+            if c as isize - r as isize == offset {
+                T::one()
+            } else {
+                T::zero()
+            }
+        });
+        unsafe { Tensor::from_trusted_len_iter(iter, shape) }
+    }
 }
 
 impl<T> From<Vec<T>> for Tensor<T, Const<1>>
@@ -156,3 +218,61 @@ where
         Tensor::new(values, shape, strides)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::Tensor;
+
+    #[test]
+    fn test_from_iter_shape() {
+        let x = Tensor::from_iter_shape(0..6, [2, 3]).unwrap();
+        assert!(x.is_standard_layout());
+        assert_eq!(x.strides().as_ref(), &[3, 1]);
+        crate::assert_tensor_eq!(x, crate::tensor![[0, 1, 2], [3, 4, 5]]);
+
+        assert!(Tensor::from_iter_shape(0..5, [2, 3]).is_err());
+        assert!(Tensor::from_iter_shape(0..7, [2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_eye() {
+        let x = Tensor::<f64, _>::eye(3);
+        for r in 0..3 {
+            for c in 0..3 {
+                assert_eq!(x.index([r, c]), if r == c { 1.0 } else { 0.0 });
+            }
+        }
+
+        let x = Tensor::<i32, _>::zeros((2, 3));
+        crate::assert_tensor_eq!(x.eye_like(0), crate::tensor![[1, 0, 0], [0, 1, 0]]);
+        crate::assert_tensor_eq!(x.eye_like(1), crate::tensor![[0, 1, 0], [0, 0, 1]]);
+        crate::assert_tensor_eq!(x.eye_like(-1), crate::tensor![[0, 0, 0], [1, 0, 0]]);
+    }
+
+    #[test]
+    fn test_arange() {
+        crate::assert_tensor_eq!(Tensor::arange(0, 10, 2), crate::tensor![0, 2, 4, 6, 8]);
+        crate::assert_tensor_eq!(Tensor::arange(5, 0, -2), crate::tensor![5, 3, 1]);
+        assert_eq!(Tensor::arange(0, 0, 1).size(), 0);
+        assert_eq!(Tensor::arange(0, 10, 0).size(), 0);
+    }
+
+    #[test]
+    fn test_linspace() {
+        let x = Tensor::linspace(0.0, 1.0, 5);
+        assert_eq!(x.size(), 5);
+        assert_eq!(x.index(0), 0.0);
+        assert_eq!(x.index(2), 0.5);
+        assert_eq!(x.index(4), 1.0);
+
+        crate::assert_tensor_eq!(
+            Tensor::<f64, _>::zeros([2, 2]),
+            crate::tensor![[0.0, 0.0], [0.0, 0.0]]
+        );
+        crate::assert_tensor_eq!(Tensor::<i32, _>::ones(3), crate::tensor![1, 1, 1]);
+        crate::assert_tensor_eq!(
+            Tensor::<u8, _>::full((2, 1), 7),
+            crate::tensor![[7_u8], [7]]
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use ella_common::TensorValue;
 
-use crate::{Const, Tensor};
+use crate::{Const, IntoShape, Shape, Tensor};
 
 impl<T: TensorValue> Tensor<T, Const<0>> {
     pub fn scalar(&self) -> T {
@@ -9,8 +9,54 @@ impl<T: TensorValue> Tensor<T, Const<0>> {
     }
 }
 
+impl<T, S> Tensor<T, S>
+where
+    T: TensorValue,
+    S: Shape,
+{
+    /// Create a tensor from flat `data` in standard (row-major) layout.
+    ///
+    /// Takes ownership of `data` where the element type allows it. Returns an error if the
+    /// length of `data` doesn't match the size of `shape`.
+    pub fn from_vec<I>(data: Vec<T>, shape: I) -> crate::Result<Self>
+    where
+        I: IntoShape<Shape = S>,
+    {
+        let shape = shape.into_shape();
+        if data.len() != shape.size() {
+            return Err(crate::ShapeError::array_size(data.len(), shape.as_ref()).into());
+        }
+        let strides = shape.default_strides();
+        Ok(Tensor::new(T::from_vec(data), shape, strides))
+    }
+
+    /// Flatten the tensor into a `Vec` in standard (row-major) layout.
+    pub fn into_vec(self) -> Vec<T> {
+        self.iter().collect()
+    }
+}
+
 impl<T: TensorValue> From<Tensor<T, Const<1>>> for Vec<T> {
     fn from(value: Tensor<T, Const<1>>) -> Self {
         value.into_iter().collect::<Vec<_>>()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::Tensor;
+
+    #[test]
+    fn test_vec_roundtrip() {
+        let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let x = Tensor::from_vec(data.clone(), [2, 3]).unwrap();
+        assert_eq!(x.shape().as_ref(), &[2, 3]);
+        crate::assert_tensor_eq!(x.clone(), crate::tensor![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        assert_eq!(x.clone().into_vec(), data);
+
+        let y = Tensor::from_vec(x.t().into_vec(), [3, 2]).unwrap();
+        crate::assert_tensor_eq!(y, crate::tensor![[1.0, 4.0], [2.0, 5.0], [3.0, 6.0]]);
+
+        assert!(Tensor::from_vec(data, [4, 2]).is_err());
+    }
+}
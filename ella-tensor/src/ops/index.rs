@@ -1,5 +1,5 @@
 use crate::{
-    shape::{IndexValue, Indexer},
+    shape::{stride_offset_checked, IndexValue, Indexer},
     Axis, RemoveAxis, Shape, Tensor, TensorValue,
 };
 
@@ -18,6 +18,26 @@ where
         };
         unsafe { self.values().value_unchecked(idx) }
     }
+
+    /// Get the element at `index`.
+    ///
+    /// Returns `None` if `index` is out of bounds or doesn't have one entry per dimension.
+    pub fn get(&self, index: &[usize]) -> Option<T> {
+        if index.len() != self.ndim() {
+            return None;
+        }
+        let mut offset = 0;
+        let iter = self
+            .shape()
+            .slice()
+            .iter()
+            .zip(self.strides().slice())
+            .zip(index);
+        for ((&dim, &stride), &i) in iter {
+            offset += stride_offset_checked(dim, stride, i)?;
+        }
+        Some(unsafe { self.values().value_unchecked(offset) })
+    }
 }
 
 impl<T, S> Tensor<T, S>
@@ -32,3 +52,25 @@ where
         Tensor::new(this.into_values(), shape, strides)
     }
 }
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn test_get() {
+        let x = crate::tensor![[1, 2, 3], [4, 5, 6]];
+        assert_eq!(x.get(&[0, 0]), Some(1));
+        assert_eq!(x.get(&[1, 2]), Some(6));
+        assert_eq!(x.t().get(&[2, 1]), Some(6));
+        assert_eq!(x.t().get(&[0, 1]), Some(4));
+        assert_eq!(x.invert_axis(1).get(&[0, 0]), Some(3));
+
+        // out of bounds
+        assert_eq!(x.get(&[2, 0]), None);
+        assert_eq!(x.get(&[0, 3]), None);
+
+        // wrong rank
+        assert_eq!(x.get(&[0]), None);
+        assert_eq!(x.get(&[0, 0, 0]), None);
+        assert_eq!(x.as_dyn().get(&[1]), None);
+    }
+}
@@ -0,0 +1,108 @@
+use std::ops::Mul;
+
+use num_traits::Zero;
+
+use crate::{Const, Shape, Tensor, TensorValue};
+
+impl<T> Tensor<T, Const<3>>
+where
+    T: TensorValue + Zero + Mul<Output = T>,
+{
+    /// Batched matrix multiply over the leading axis.
+    ///
+    /// Multiplies a `[b, n, k]` tensor by a `[b, k, m]` tensor to produce a `[b, n, m]` tensor.
+    pub fn bmm(&self, rhs: &Tensor<T, Const<3>>) -> crate::Result<Tensor<T, Const<3>>> {
+        let (batch, n, k) = (self.shape()[0], self.shape()[1], self.shape()[2]);
+        let (rhs_batch, rhs_k, m) = (rhs.shape()[0], rhs.shape()[1], rhs.shape()[2]);
+        if batch != rhs_batch || k != rhs_k {
+            return Err(
+                crate::ShapeError::matmul(self.shape().as_ref(), rhs.shape().as_ref()).into(),
+            );
+        }
+
+        let lhs = self.iter().collect::<Vec<_>>();
+        let rhs = rhs.iter().collect::<Vec<_>>();
+        let mut out = vec![T::zero(); batch * n * m];
+        for b in 0..batch {
+            let (lhs, rhs) = (&lhs[b * n * k..], &rhs[b * k * m..]);
+            let out = &mut out[b * n * m..(b + 1) * n * m];
+            for i in 0..n {
+                for p in 0..k {
+                    let x = lhs[i * k + p].clone();
+                    for j in 0..m {
+                        out[i * m + j] =
+                            out[i * m + j].clone() + x.clone() * rhs[p * m + j].clone();
+                    }
+                }
+            }
+        }
+        let shape = Const([batch, n, m]);
+        Ok(unsafe { Tensor::from_trusted_len_iter(out, shape) })
+    }
+}
+
+impl<T> Tensor<T, Const<2>>
+where
+    T: TensorValue + Mul<Output = T>,
+{
+    /// Kronecker product of two matrices.
+    ///
+    /// The product of an `[m, n]` and a `[p, q]` matrix has shape `[m * p, n * q]`.
+    pub fn kron(&self, other: &Tensor<T, Const<2>>) -> Tensor<T, Const<2>> {
+        let (m, n) = (self.shape()[0], self.shape()[1]);
+        let (p, q) = (other.shape()[0], other.shape()[1]);
+
+        let lhs = self.iter().collect::<Vec<_>>();
+        let rhs = other.iter().collect::<Vec<_>>();
+        let shape = Const([m * p, n * q]);
+        let iter = shape.indices().map(|Const([r, c])| {
+            lhs[(r / p) * n + c / q].clone() * rhs[(r % p) * q + c % q].clone()
+        });
+        unsafe { Tensor::from_trusted_len_iter(iter, shape) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Axis, Tensor};
+
+    #[test]
+    fn test_bmm() {
+        let a = Tensor::range(0.0, 24.0, 1.0).reshape([4, 2, 3]).unwrap();
+        let b = Tensor::range(0.0, 60.0, 1.0).reshape([4, 3, 5]).unwrap();
+        let c = a.bmm(&b).unwrap();
+        assert_eq!(c.shape().as_ref(), &[4, 2, 5]);
+
+        // Each batch should match the product of its 2-D slices
+        for batch in 0..4 {
+            let (a, b) = (a.index_axis(Axis(0), batch), b.index_axis(Axis(0), batch));
+            for i in 0..2 {
+                for j in 0..5 {
+                    let expected = (0..3)
+                        .map(|k| a.index([i, k]) * b.index([k, j]))
+                        .sum::<f64>();
+                    assert_eq!(c.index([batch, i, j]), expected);
+                }
+            }
+        }
+        assert_eq!(c.index([0, 0, 0]), 25.0);
+
+        let bad = Tensor::<f64, _>::zeros([3, 3, 5]);
+        assert!(a.bmm(&bad).is_err());
+        let bad = Tensor::<f64, _>::zeros([4, 2, 5]);
+        assert!(a.bmm(&bad).is_err());
+    }
+
+    #[test]
+    fn test_kron() {
+        let a = crate::tensor![[1, 2], [3, 4]];
+        let b = crate::tensor![[0, 5], [6, 7]];
+        let expected = crate::tensor![
+            [0, 5, 0, 10],
+            [6, 7, 12, 14],
+            [0, 15, 0, 20],
+            [18, 21, 24, 28],
+        ];
+        crate::assert_tensor_eq!(a.kron(&b), expected);
+    }
+}
@@ -0,0 +1,75 @@
+use num_traits::Float;
+use rand::Rng;
+
+use crate::{IntoShape, Shape, Tensor, TensorValue};
+
+/// Distribution sampled by [`Tensor::random`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distribution {
+    /// Uniform distribution over `[low, high)`.
+    Uniform { low: f64, high: f64 },
+    /// Normal distribution with the given mean and standard deviation.
+    Normal { mean: f64, std: f64 },
+}
+
+impl Distribution {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        match *self {
+            Self::Uniform { low, high } => low + (high - low) * rng.gen::<f64>(),
+            Self::Normal { mean, std } => {
+                // Box-Muller transform; `1 - u` keeps the logarithm finite
+                let u1 = 1.0 - rng.gen::<f64>();
+                let u2 = rng.gen::<f64>();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                mean + std * z
+            }
+        }
+    }
+}
+
+impl<T, S> Tensor<T, S>
+where
+    T: TensorValue + Float,
+    S: Shape,
+{
+    /// Sample a tensor with the given shape from `distribution` using `rng`.
+    ///
+    /// Pass a seeded RNG (e.g. [`rand::rngs::StdRng`]) for reproducible results.
+    pub fn random<I, R>(shape: I, distribution: Distribution, rng: &mut R) -> Self
+    where
+        I: IntoShape<Shape = S>,
+        R: Rng + ?Sized,
+    {
+        let shape = shape.into_shape();
+        let values = (0..shape.size()).map(|_| T::from(distribution.sample(rng)).unwrap());
+        unsafe { Tensor::from_trusted_len_iter(values, shape) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::Distribution;
+    use crate::Tensor;
+
+    #[test]
+    fn test_random_seed() {
+        let normal = Distribution::Normal {
+            mean: 0.0,
+            std: 1.0,
+        };
+        let a = Tensor::<f64, _>::random([4, 8], normal, &mut StdRng::seed_from_u64(7));
+        let b = Tensor::<f64, _>::random([4, 8], normal, &mut StdRng::seed_from_u64(7));
+        let c = Tensor::<f64, _>::random([4, 8], normal, &mut StdRng::seed_from_u64(8));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+
+        let uniform = Distribution::Uniform {
+            low: -1.0,
+            high: 1.0,
+        };
+        let x = Tensor::<f32, _>::random(100, uniform, &mut StdRng::seed_from_u64(7));
+        assert!(x.iter().all(|v| (-1.0..1.0).contains(&v)));
+    }
+}
@@ -1 +1,222 @@
+use num_traits::ToPrimitive;
 
+use crate::{Axis, RemoveAxis, Shape, Tensor, TensorValue};
+
+/// Running mean and variance of a lane, updated using Welford's algorithm.
+#[derive(Debug, Clone, Copy, Default)]
+struct Moments {
+    count: usize,
+    mean: f64,
+    m2: f64,
+}
+
+impl Moments {
+    fn push(&mut self, x: f64) {
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    fn mean(&self) -> f64 {
+        if self.count == 0 {
+            f64::NAN
+        } else {
+            self.mean
+        }
+    }
+
+    fn var(&self, ddof: usize) -> f64 {
+        if self.count <= ddof {
+            f64::NAN
+        } else {
+            self.m2 / (self.count - ddof) as f64
+        }
+    }
+}
+
+impl<T, S> Tensor<T, S>
+where
+    T: TensorValue,
+    T::Unmasked: ToPrimitive,
+    S: Shape + RemoveAxis,
+{
+    /// Mean of the valid elements along `axis`.
+    pub fn mean_axis<A: Into<Axis>>(&self, axis: A) -> Tensor<f64, S::Smaller> {
+        self.moments_axis(axis.into(), Moments::mean)
+    }
+
+    /// Variance of the valid elements along `axis`.
+    ///
+    /// The sum of squared deviations is divided by `N - ddof`, where `N` is the number of valid elements.
+    pub fn var_axis<A: Into<Axis>>(&self, axis: A, ddof: usize) -> Tensor<f64, S::Smaller> {
+        self.moments_axis(axis.into(), |m| m.var(ddof))
+    }
+
+    /// Standard deviation of the valid elements along `axis`.
+    ///
+    /// See [`Tensor::var_axis`] for the meaning of `ddof`.
+    pub fn std_axis<A: Into<Axis>>(&self, axis: A, ddof: usize) -> Tensor<f64, S::Smaller> {
+        self.moments_axis(axis.into(), |m| m.var(ddof).sqrt())
+    }
+
+    /// Mean of the valid elements along `axis`, keeping the reduced axis with length 1.
+    ///
+    /// The result has the same number of dimensions as `self`, so it can be broadcast against it.
+    pub fn mean_axis_keepdims<A: Into<Axis>>(&self, axis: A) -> Tensor<f64, S> {
+        let axis = self.normalize_axis(axis.into());
+        self.keep_axis(self.mean_axis(axis), axis)
+    }
+
+    /// Variance of the valid elements along `axis`, keeping the reduced axis with length 1.
+    pub fn var_axis_keepdims<A: Into<Axis>>(&self, axis: A, ddof: usize) -> Tensor<f64, S> {
+        let axis = self.normalize_axis(axis.into());
+        self.keep_axis(self.var_axis(axis, ddof), axis)
+    }
+
+    /// Standard deviation of the valid elements along `axis`, keeping the reduced axis with length 1.
+    pub fn std_axis_keepdims<A: Into<Axis>>(&self, axis: A, ddof: usize) -> Tensor<f64, S> {
+        let axis = self.normalize_axis(axis.into());
+        self.keep_axis(self.std_axis(axis, ddof), axis)
+    }
+
+    /// Standardize the valid elements along `axis` to zero mean and unit standard deviation.
+    ///
+    /// The population standard deviation (`ddof = 0`) is used. Lanes with zero variance
+    /// are mapped to zeros rather than NaN. Masked elements are returned as NaN.
+    pub fn zscore<A: Into<Axis>>(&self, axis: A) -> Tensor<f64, S> {
+        let axis = self.normalize_axis(axis.into());
+        let expand = |t: Tensor<f64, S>| t.broadcast_to(self.shape().clone()).unwrap();
+        let mean = expand(self.mean_axis_keepdims(axis));
+        let std = expand(self.std_axis_keepdims(axis, 0));
+        let mask = self.mask_inner();
+
+        let values = self
+            .iter()
+            .zip(mask.iter())
+            .zip(mean.iter().zip(std.iter()))
+            .map(|((x, valid), (mean, std))| {
+                if !valid {
+                    f64::NAN
+                } else if std == 0.0 {
+                    0.0
+                } else {
+                    (to_f64::<T>(x) - mean) / std
+                }
+            });
+        unsafe { Tensor::from_trusted_len_iter(values, self.shape().clone()) }
+    }
+
+    fn normalize_axis(&self, axis: Axis) -> Axis {
+        Axis(axis.index(self.shape()) as isize)
+    }
+
+    fn keep_axis(&self, reduced: Tensor<f64, S::Smaller>, axis: Axis) -> Tensor<f64, S> {
+        reduced
+            .unsqueeze(axis)
+            .and_then(|t| t.as_shape::<S>())
+            .unwrap()
+    }
+
+    fn moments_axis<F>(&self, axis: Axis, f: F) -> Tensor<f64, S::Smaller>
+    where
+        F: Fn(&Moments) -> f64,
+    {
+        let shape = self.shape().remove_axis(axis);
+        let mut moments = vec![Moments::default(); shape.size()];
+        for lane in self.axis_iter(axis) {
+            let mask = lane.mask_inner();
+            for ((m, x), valid) in moments.iter_mut().zip(lane.iter()).zip(mask.iter()) {
+                if valid {
+                    m.push(to_f64::<T>(x));
+                }
+            }
+        }
+        unsafe { Tensor::from_trusted_len_iter(moments.iter().map(f), shape) }
+    }
+}
+
+fn to_f64<T>(x: T) -> f64
+where
+    T: TensorValue,
+    T::Unmasked: ToPrimitive,
+{
+    T::to_unmasked(x).to_f64().unwrap_or(f64::NAN)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Axis, Shape, Tensor};
+
+    fn assert_close<S: Shape>(a: Tensor<f64, S>, b: Tensor<f64, S>) {
+        assert_eq!(a.shape().slice(), b.shape().slice());
+        for (x, y) in a.iter().zip(b.iter()) {
+            assert!((x - y).abs() < 1e-9, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_var() {
+        let x = crate::tensor![[1.0, 2.0, 3.0, 4.0], [2.0, 4.0, 6.0, 8.0]];
+
+        assert_close(x.mean_axis(Axis(1)), crate::tensor![2.5, 5.0]);
+        assert_close(x.var_axis(Axis(1), 0), crate::tensor![1.25, 5.0]);
+        assert_close(
+            x.var_axis(Axis(1), 1),
+            crate::tensor![5.0 / 3.0, 20.0 / 3.0],
+        );
+        assert_close(x.var_axis(Axis(0), 0), crate::tensor![0.25, 1.0, 2.25, 4.0]);
+        assert_close(x.var_axis(Axis(0), 1), crate::tensor![0.5, 2.0, 4.5, 8.0]);
+        assert_close(
+            x.std_axis(Axis(-1), 1),
+            crate::tensor![(5.0_f64 / 3.0).sqrt(), (20.0_f64 / 3.0).sqrt()],
+        );
+
+        let x = crate::tensor![1_i32, 2, 3, 100].with_mask(crate::tensor![true, true, true, false]);
+        assert!((x.var_axis(Axis(0), 0).scalar() - 2.0 / 3.0).abs() < 1e-9);
+        assert!((x.var_axis(Axis(0), 1).scalar() - 1.0).abs() < 1e-9);
+        assert!(x.var_axis(Axis(0), 3).scalar().is_nan());
+    }
+
+    #[test]
+    fn test_zscore() {
+        let x = crate::tensor![
+            [1.0, 2.0, 3.0, 4.0],
+            [10.0, 40.0, 20.0, 30.0],
+            [5.0, 5.0, 5.0, 5.0]
+        ];
+        let z = x.zscore(Axis(1));
+        assert_eq!(z.shape().slice(), x.shape().slice());
+
+        assert_close(z.mean_axis(Axis(1)), crate::tensor![0.0, 0.0, 0.0]);
+        assert_close(z.std_axis(Axis(1), 0), crate::tensor![1.0, 1.0, 0.0]);
+        assert_close(z.index_axis(Axis(0), 2), Tensor::zeros(4));
+
+        let z = x.zscore(Axis(0));
+        assert_close(z.mean_axis(Axis(0)), Tensor::zeros(4));
+    }
+
+    #[test]
+    fn test_keepdims() {
+        let x = crate::tensor![[1.0, 2.0, 3.0], [4.0, 6.0, 8.0]];
+        let mean = x.mean_axis_keepdims(Axis(1));
+        assert_close(mean.clone(), crate::tensor![[2.0], [6.0]]);
+        assert_close(
+            &x - mean,
+            crate::tensor![[-1.0, 0.0, 1.0], [-2.0, 0.0, 2.0]],
+        );
+
+        assert_close(
+            x.mean_axis_keepdims(Axis(0)),
+            crate::tensor![[2.5, 4.0, 5.5]],
+        );
+        assert_close(
+            x.var_axis_keepdims(Axis(-1), 0),
+            crate::tensor![[2.0 / 3.0], [8.0 / 3.0]],
+        );
+        assert_close(
+            x.std_axis_keepdims(Axis(-1), 1),
+            crate::tensor![[1.0], [2.0]],
+        );
+    }
+}
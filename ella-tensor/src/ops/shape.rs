@@ -3,6 +3,17 @@ use crate::{
     Axis, Const, Dyn, IntoShape, RemoveAxis, Shape, Tensor, TensorValue,
 };
 
+/// How [`Tensor::pad`] fills the elements added around the tensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PadMode {
+    /// Fill with a constant value.
+    Constant,
+    /// Repeat the first or last element along each axis.
+    Edge,
+    /// Mirror the elements next to the edge, not including the edge itself.
+    Reflect,
+}
+
 impl<T, S> Tensor<T, S>
 where
     T: TensorValue,
@@ -16,34 +27,133 @@ where
         )
     }
 
-    pub fn reshape<I>(&self, shape: I) -> Tensor<T, I::Shape>
+    /// Returns an error if `shape` doesn't have the same number of elements as the tensor.
+    pub fn reshape<I>(&self, shape: I) -> crate::Result<Tensor<T, I::Shape>>
     where
         I: IntoShape,
     {
         let shape = shape.into_shape();
+        if self.shape().size() != shape.size() {
+            return Err(crate::ShapeError::array_size(self.size(), shape.as_ref()).into());
+        }
         let strides = shape.default_strides();
-        assert_eq!(self.shape().size(), shape.size());
-
         let values = self.clone().to_standard_layout().into_values();
 
-        Tensor::new(values, shape, strides)
+        Ok(Tensor::new(values, shape, strides))
+    }
+
+    /// Reshape the tensor, computing the length of at most one axis marked with `-1` from the
+    /// number of elements.
+    ///
+    /// Returns an error if more than one axis is `-1`, any other length is negative, or the
+    /// known lengths don't evenly divide the number of elements.
+    pub fn reshape_infer(&self, shape: &[isize]) -> crate::Result<Tensor<T, Dyn>> {
+        let size = self.size();
+        let invalid = || crate::Error::from(crate::ShapeError::infer_shape(shape, size));
+        let mut inferred = None;
+        let mut known = 1;
+        for (i, &len) in shape.iter().enumerate() {
+            match len {
+                -1 if inferred.is_none() => inferred = Some(i),
+                len if len >= 0 => known *= len as usize,
+                _ => return Err(invalid()),
+            }
+        }
+
+        let mut out = shape.iter().map(|&len| len as usize).collect::<Vec<_>>();
+        if let Some(i) = inferred {
+            if known == 0 || size % known != 0 {
+                return Err(invalid());
+            }
+            out[i] = size / known;
+        }
+        self.reshape(out)
     }
 
     #[inline]
     pub fn flatten(&self) -> Tensor<T, Const<1>> {
-        self.reshape(Const([self.shape().size()]))
+        let shape = Const([self.shape().size()]);
+        let strides = shape.default_strides();
+        let values = self.clone().to_standard_layout().into_values();
+        Tensor::new(values, shape, strides)
     }
 
-    pub fn unsqueeze<A>(&self, axis: A) -> Tensor<T, S::Larger>
+    /// Merge the axes from `start` to `end` (inclusive) into a single axis.
+    ///
+    /// Returns a view if the merged axes are contiguous, otherwise the values are copied.
+    /// Returns an error if either axis is out of range or `start` comes after `end`.
+    pub fn flatten_axes<A1, A2>(&self, start: A1, end: A2) -> crate::Result<Tensor<T, Dyn>>
+    where
+        A1: Into<Axis>,
+        A2: Into<Axis>,
+    {
+        let ndim = self.ndim() as isize;
+        let (start, end) = (start.into(), end.into());
+        for axis in [start, end] {
+            if axis.0 < -ndim || axis.0 >= ndim {
+                return Err(crate::Error::AxisOutOfBounds(axis.0, self.ndim()));
+            }
+        }
+        let start = start.index(self.shape());
+        let end = end.index(self.shape());
+        if start > end {
+            return Err(crate::ShapeError::flatten_axes(start, end).into());
+        }
+
+        let shape = self.shape().slice();
+        let strides = self.strides().slice();
+        let mut flat = shape[..start].to_vec();
+        flat.push(shape[start..=end].iter().product());
+        flat.extend_from_slice(&shape[end + 1..]);
+
+        // Axes of length 1 can have any stride, so only the others need to line up
+        let merged = (start..=end)
+            .filter(|&ax| shape[ax] != 1)
+            .collect::<Vec<_>>();
+        let contiguous = merged
+            .windows(2)
+            .all(|w| strides[w[0]] == strides[w[1]].wrapping_mul(shape[w[1]]));
+        if !contiguous {
+            return self.reshape(flat);
+        }
+        let mut flat_strides = strides[..start].to_vec();
+        flat_strides.push(merged.last().map_or(1, |&ax| strides[ax]));
+        flat_strides.extend_from_slice(&strides[end + 1..]);
+        Ok(Tensor::new(
+            self.values().clone(),
+            Dyn::from(flat),
+            Dyn::from(flat_strides),
+        ))
+    }
+
+    /// Remove every axis of length 1.
+    pub fn squeeze_all(&self) -> Tensor<T, Dyn> {
+        let (shape, strides): (Vec<_>, Vec<_>) = self
+            .shape()
+            .slice()
+            .iter()
+            .zip(self.strides().slice())
+            .filter(|(len, _)| **len != 1)
+            .map(|(&len, &stride)| (len, stride))
+            .unzip();
+        Tensor::new(self.values().clone(), Dyn::from(shape), Dyn::from(strides))
+    }
+
+    /// Insert an axis of length 1 at `axis`.
+    pub fn unsqueeze<A>(&self, axis: A) -> crate::Result<Tensor<T, S::Larger>>
     where
         A: Into<Axis>,
     {
         let axis = axis.into();
-        Tensor::new(
+        let ndim = self.ndim() as isize;
+        if axis.0 < -(ndim + 1) || axis.0 > ndim {
+            return Err(crate::Error::AxisOutOfBounds(axis.0, self.ndim()));
+        }
+        Ok(Tensor::new(
             self.values().clone(),
             self.shape().insert_axis(axis),
             self.strides().insert_axis(axis),
-        )
+        ))
     }
 
     pub fn swap_axes<A1, A2>(&self, ax1: A1, ax2: A2) -> Self
@@ -61,6 +171,60 @@ where
         Tensor::new(self.values().clone(), shape, strides)
     }
 
+    /// Reorder the axes so that axis `i` of the result is axis `order[i]` of the tensor.
+    ///
+    /// Returns an error if `order` isn't a permutation of `0..ndim`.
+    pub fn permute(&self, order: &[usize]) -> crate::Result<Self> {
+        let ndim = self.ndim();
+        let mut seen = vec![false; ndim];
+        if order.len() != ndim
+            || !order
+                .iter()
+                .all(|&ax| ax < ndim && !std::mem::replace(&mut seen[ax], true))
+        {
+            return Err(crate::ShapeError::permutation(order, ndim).into());
+        }
+
+        let mut shape = self.shape().clone();
+        let mut strides = self.strides().clone();
+        for (i, &ax) in order.iter().enumerate() {
+            shape[i] = self.shape()[ax];
+            strides[i] = self.strides()[ax];
+        }
+        Ok(Tensor::new(self.values().clone(), shape, strides))
+    }
+
+    /// Move axis `src` to position `dst`, shifting the axes in between.
+    ///
+    /// Returns an error if either axis is out of range.
+    pub fn moveaxis<A1, A2>(&self, src: A1, dst: A2) -> crate::Result<Self>
+    where
+        A1: Into<Axis>,
+        A2: Into<Axis>,
+    {
+        let ndim = self.ndim() as isize;
+        let (src, dst) = (src.into(), dst.into());
+        for axis in [src, dst] {
+            if axis.0 < -ndim || axis.0 >= ndim {
+                return Err(crate::Error::AxisOutOfBounds(axis.0, self.ndim()));
+            }
+        }
+        let src = src.index(self.shape());
+        let dst = dst.index(self.shape());
+
+        let mut order = (0..self.ndim()).collect::<Vec<_>>();
+        let ax = order.remove(src);
+        order.insert(dst, ax);
+        self.permute(&order)
+    }
+
+    /// Reverse the order of the axes.
+    pub fn transpose(&self) -> Self {
+        let order = (0..self.ndim()).rev().collect::<Vec<_>>();
+        self.permute(&order)
+            .expect("reversed axes should always be a valid permutation")
+    }
+
     pub fn as_shape<S2>(&self) -> crate::Result<Tensor<T, S2>>
     where
         S2: Shape,
@@ -106,6 +270,20 @@ where
         Ok(Tensor::new(self.values().clone(), to, new_stride))
     }
 
+    /// Broadcast the tensor to `shape` and copy the result into a new standard-layout tensor.
+    ///
+    /// Unlike [`Tensor::broadcast_to`], which returns a view with zero strides along the
+    /// broadcast axes, every element of the result is stored separately.
+    pub fn broadcast_materialize<I, O>(&self, shape: I) -> crate::Result<Tensor<T, O>>
+    where
+        O: Shape,
+        I: IntoShape<Shape = O>,
+    {
+        let view: Tensor<T, O> = self.broadcast_to(shape)?;
+        let shape = view.shape().clone();
+        Ok(unsafe { Tensor::from_trusted_len_iter(view.iter(), shape) })
+    }
+
     #[allow(clippy::type_complexity)]
     pub fn broadcast_with<T2, S2>(
         &self,
@@ -135,6 +313,94 @@ where
         Ok((t1, t2))
     }
 
+    /// Copy the tensor into a larger tensor, adding `pad_width[i].0` elements before and
+    /// `pad_width[i].1` elements after axis `i`.
+    ///
+    /// `value` is only used by [`PadMode::Constant`]. Returns an error if `pad_width` doesn't
+    /// have one entry per axis, or if an axis is too short for the padding mode.
+    pub fn pad(
+        &self,
+        pad_width: &[(usize, usize)],
+        mode: PadMode,
+        value: T,
+    ) -> crate::Result<Self> {
+        if pad_width.len() != self.ndim() {
+            return Err(crate::ShapeError::ndim(self.ndim(), pad_width.len()).into());
+        }
+        let mut shape = self.shape().clone();
+        for (ax, &(before, after)) in pad_width.iter().enumerate() {
+            let len = self.shape()[ax];
+            let width = before.max(after);
+            let max_width = match mode {
+                PadMode::Constant => usize::MAX,
+                PadMode::Edge if len == 0 => 0,
+                PadMode::Edge => usize::MAX,
+                PadMode::Reflect => len.saturating_sub(1),
+            };
+            if width > max_width {
+                return Err(crate::ShapeError::pad_width(ax, len, width).into());
+            }
+            shape[ax] = len + before + after;
+        }
+
+        let values = shape.indices().map(|mut index| {
+            for (ax, i) in index.slice_mut().iter_mut().enumerate() {
+                let len = self.shape()[ax] as isize;
+                let pos = *i as isize - pad_width[ax].0 as isize;
+                *i = if (0..len).contains(&pos) {
+                    pos as usize
+                } else {
+                    match mode {
+                        PadMode::Constant => return value.clone(),
+                        PadMode::Edge => pos.clamp(0, len - 1) as usize,
+                        PadMode::Reflect if pos < 0 => (-pos) as usize,
+                        PadMode::Reflect => (2 * (len - 1) - pos) as usize,
+                    }
+                };
+            }
+            self.index(index)
+        });
+        Ok(unsafe { Tensor::from_trusted_len_iter(values, shape) })
+    }
+
+    /// Repeat the whole tensor `reps[i]` times along axis `i`.
+    ///
+    /// Unlike [`Tensor::repeat`], the tensor is repeated block-wise: tiling `[1, 2]` twice gives
+    /// `[1, 2, 1, 2]`. Returns an error if `reps` doesn't have one entry per axis.
+    pub fn tile(&self, reps: &[usize]) -> crate::Result<Self> {
+        if reps.len() != self.ndim() {
+            return Err(crate::ShapeError::ndim(self.ndim(), reps.len()).into());
+        }
+        let mut shape = self.shape().clone();
+        for (ax, &n) in reps.iter().enumerate() {
+            shape[ax] *= n;
+        }
+
+        let values = shape.indices().map(|mut index| {
+            for (ax, i) in index.slice_mut().iter_mut().enumerate() {
+                *i %= self.shape()[ax];
+            }
+            self.index(index)
+        });
+        Ok(unsafe { Tensor::from_trusted_len_iter(values, shape) })
+    }
+
+    /// Repeat each element `n` times along `axis`.
+    ///
+    /// Unlike [`Tensor::tile`], elements are repeated individually: repeating `[1, 2]` twice gives
+    /// `[1, 1, 2, 2]`.
+    pub fn repeat<A: Into<Axis>>(&self, axis: A, n: usize) -> Self {
+        let ax = axis.into().index(self.shape());
+        let mut shape = self.shape().clone();
+        shape[ax] *= n;
+
+        let values = shape.indices().map(|mut index| {
+            index[ax] /= n;
+            self.index(index)
+        });
+        unsafe { Tensor::from_trusted_len_iter(values, shape) }
+    }
+
     pub fn invert_axis<A: Into<Axis>>(&self, axis: A) -> Self {
         let axis: Axis = axis.into();
         let ax = axis.index(self.shape());
@@ -151,23 +417,21 @@ where
         Tensor::new(values, self.shape().clone(), strides)
     }
 
-    pub fn roll(&self, axis: Axis, roll: isize) -> Self
-    where
-        S: RemoveAxis,
-    {
-        let len = self.shape().axis(axis);
-        let shift = -roll.checked_rem(len as isize).unwrap_or_default();
-        if roll == 0 {
-            return self.clone();
-        }
-        Tensor::concat(
-            axis,
-            &[
-                self.slice_axis(axis, shift..),
-                self.slice_axis(axis, ..shift),
-            ],
-        )
-        .unwrap()
+    /// Shift the elements along `axis` by `roll` positions, wrapping elements that fall off
+    /// the end back to the start. Negative values roll towards the start.
+    pub fn roll<A: Into<Axis>>(&self, axis: A, roll: isize) -> Self {
+        let ax = axis.into().index(self.shape());
+        let len = self.shape()[ax];
+        let shift = match roll.checked_rem_euclid(len as isize) {
+            Some(0) | None => return self.clone(),
+            Some(shift) => shift as usize,
+        };
+
+        let values = self.shape().indices().map(|mut index| {
+            index[ax] = (index[ax] + len - shift) % len;
+            self.index(index)
+        });
+        unsafe { Tensor::from_trusted_len_iter(values, self.shape().clone()) }
     }
 }
 
@@ -177,13 +441,20 @@ where
     T: TensorValue,
     S: Shape + RemoveAxis,
 {
-    pub fn squeeze(&self, axis: Axis) -> Tensor<T, S::Smaller> {
-        debug_assert!(axis.index(self.shape()) < self.shape().ndim());
-        debug_assert!(self.shape()[axis.index(self.shape())] <= 1);
+    /// Remove `axis`, which must have length 1 (or 0).
+    pub fn squeeze(&self, axis: Axis) -> crate::Result<Tensor<T, S::Smaller>> {
+        let ndim = self.ndim() as isize;
+        if axis.0 < -ndim || axis.0 >= ndim {
+            return Err(crate::Error::AxisOutOfBounds(axis.0, self.ndim()));
+        }
+        let ax = axis.index(self.shape());
+        if self.shape()[ax] > 1 {
+            return Err(crate::ShapeError::squeeze(ax, self.shape()[ax]).into());
+        }
 
         let shape = self.shape().remove_axis(axis);
         let strides = self.strides().remove_axis(axis);
-        Tensor::new(self.values().clone(), shape, strides)
+        Ok(Tensor::new(self.values().clone(), shape, strides))
     }
 }
 
@@ -199,7 +470,197 @@ where
 
 #[cfg(test)]
 mod test {
-    use crate::Axis;
+    use crate::{Axis, PadMode, Tensor};
+
+    #[test]
+    fn test_reshape() {
+        let x = crate::tensor![[1, 2, 3], [4, 5, 6]];
+        crate::assert_tensor_eq!(
+            x.reshape((3, 2)).unwrap(),
+            crate::tensor![[1, 2], [3, 4], [5, 6]]
+        );
+        crate::assert_tensor_eq!(x.t().flatten(), crate::tensor![1, 4, 2, 5, 3, 6]);
+
+        assert!(x.reshape((4, 2)).is_err());
+        assert!(Tensor::range(0, 10, 1).reshape(3).is_err());
+    }
+
+    #[test]
+    fn test_reshape_infer() {
+        let x = Tensor::range(0, 24, 1);
+
+        let y = x.reshape_infer(&[-1, 4]).unwrap();
+        assert_eq!(y.shape().slice(), &[6, 4]);
+        crate::assert_tensor_eq!(y, x.reshape((6, 4)).unwrap().as_dyn());
+
+        let y = x.reshape_infer(&[2, -1, 3]).unwrap();
+        assert_eq!(y.shape().slice(), &[2, 4, 3]);
+
+        assert_eq!(x.reshape_infer(&[4, 6]).unwrap().shape().slice(), &[4, 6]);
+        assert!(x.reshape_infer(&[-1, -1]).is_err());
+        assert!(x.reshape_infer(&[-1, 5]).is_err());
+        assert!(x.reshape_infer(&[-2, 12]).is_err());
+        assert!(x.reshape_infer(&[0, -1]).is_err());
+        assert!(x.reshape_infer(&[5, 5]).is_err());
+    }
+
+    #[test]
+    fn test_flatten_axes() {
+        let x = Tensor::range(0, 24, 1).reshape((2, 3, 4)).unwrap();
+
+        let y = x.flatten_axes(Axis(1), Axis(2)).unwrap();
+        assert_eq!(y.shape().slice(), &[2, 12]);
+        crate::assert_tensor_eq!(y, x.reshape((2, 12)).unwrap().as_dyn());
+
+        let y = x.flatten_axes(Axis(0), Axis(-2)).unwrap();
+        assert_eq!(y.shape().slice(), &[6, 4]);
+        crate::assert_tensor_eq!(y, x.reshape((6, 4)).unwrap().as_dyn());
+
+        let y = x.transpose().flatten_axes(Axis(1), Axis(1)).unwrap();
+        assert_eq!(y.shape().slice(), &[4, 3, 2]);
+        crate::assert_tensor_eq!(y, x.transpose().as_dyn());
+
+        // Merging transposed axes requires a copy
+        let y = x.transpose().flatten_axes(Axis(0), Axis(1)).unwrap();
+        assert_eq!(y.shape().slice(), &[12, 2]);
+        assert!(y.is_standard_layout());
+        crate::assert_tensor_eq!(y, x.transpose().reshape((12, 2)).unwrap().as_dyn());
+
+        // Slicing the middle axis keeps the trailing axes contiguous
+        let z = x.slice_axis(Axis(1), 1..3);
+        let y = z.flatten_axes(Axis(1), Axis(2)).unwrap();
+        assert_eq!(y.strides().slice(), &[12, 1]);
+        crate::assert_tensor_eq!(y, z.reshape((2, 8)).unwrap().as_dyn());
+
+        assert!(x.flatten_axes(Axis(2), Axis(1)).is_err());
+        assert!(x.flatten_axes(Axis(0), Axis(3)).is_err());
+        assert!(x.flatten_axes(Axis(-4), Axis(0)).is_err());
+    }
+
+    #[test]
+    fn test_broadcast_materialize() {
+        let x = crate::tensor![1, 2, 3];
+
+        let view = x.broadcast_to((2, 3)).unwrap();
+        assert_eq!(view.strides().slice(), &[0, 1]);
+
+        let y = x.broadcast_materialize((2, 3)).unwrap();
+        assert!(y.is_standard_layout());
+        assert_eq!(y.shape().slice(), &[2, 3]);
+        assert_eq!(y.strides().slice(), &[3, 1]);
+        crate::assert_tensor_eq!(y, crate::tensor![[1, 2, 3], [1, 2, 3]]);
+
+        assert!(x.broadcast_materialize((2, 4)).is_err());
+    }
+
+    #[test]
+    fn test_squeeze() {
+        let x = crate::tensor![[1, 2, 3]];
+        crate::assert_tensor_eq!(x.squeeze(Axis(0)).unwrap(), crate::tensor![1, 2, 3]);
+        assert!(x.squeeze(Axis(1)).is_err());
+        assert!(x.squeeze(Axis(2)).is_err());
+
+        let z = x.unsqueeze(-1).unwrap().squeeze_all();
+        assert_eq!(z.shape().slice(), &[3]);
+        crate::assert_tensor_eq!(z, crate::tensor![1, 2, 3].as_dyn());
+
+        let y = crate::tensor![1, 2, 3];
+        assert_eq!(y.squeeze_all().shape().slice(), &[3]);
+        assert_eq!(y.unsqueeze(-1).unwrap().shape().as_ref(), &[3, 1]);
+        assert!(y.unsqueeze(2).is_err());
+        assert!(y.unsqueeze(-3).is_err());
+    }
+
+    #[test]
+    fn test_permute() {
+        let x = Tensor::range(0, 24, 1).reshape((2, 3, 4)).unwrap();
+
+        let y = x.permute(&[2, 0, 1]).unwrap();
+        assert_eq!(y.shape().slice(), &[4, 2, 3]);
+        assert_eq!(y.index([3, 1, 2]), x.index([1, 2, 3]));
+        crate::assert_tensor_eq!(y.permute(&[1, 2, 0]).unwrap(), x.clone());
+
+        let t = x.transpose();
+        assert_eq!(t.shape().slice(), &[4, 3, 2]);
+        crate::assert_tensor_eq!(t, x.swap_axes(0, 2));
+
+        assert!(x.permute(&[0, 1]).is_err());
+        assert!(x.permute(&[0, 1, 1]).is_err());
+        assert!(x.permute(&[0, 1, 3]).is_err());
+    }
+
+    #[test]
+    fn test_moveaxis() {
+        let x = Tensor::range(0, 24, 1).reshape((2, 3, 4)).unwrap();
+
+        let y = x.moveaxis(0, -1).unwrap();
+        assert_eq!(y.shape().slice(), &[3, 4, 2]);
+        assert_eq!(y.index([2, 3, 1]), x.index([1, 2, 3]));
+        crate::assert_tensor_eq!(y, x.permute(&[1, 2, 0]).unwrap());
+        crate::assert_tensor_eq!(y.moveaxis(-1, 0).unwrap(), x.clone());
+        crate::assert_tensor_eq!(x.moveaxis(1, 1).unwrap(), x.clone());
+
+        assert!(x.moveaxis(3, 0).is_err());
+        assert!(x.moveaxis(0, -4).is_err());
+    }
+
+    #[test]
+    fn test_pad() {
+        let x = crate::tensor![[1, 2, 3], [4, 5, 6]];
+
+        let y = x.pad(&[(1, 0), (0, 2)], PadMode::Constant, 0).unwrap();
+        crate::assert_tensor_eq!(
+            y,
+            crate::tensor![[0, 0, 0, 0, 0], [1, 2, 3, 0, 0], [4, 5, 6, 0, 0]]
+        );
+
+        let y = x.pad(&[(0, 0), (2, 1)], PadMode::Edge, 0).unwrap();
+        crate::assert_tensor_eq!(y, crate::tensor![[1, 1, 1, 2, 3, 3], [4, 4, 4, 5, 6, 6]]);
+
+        let y = x.pad(&[(1, 1), (2, 2)], PadMode::Reflect, 0).unwrap();
+        crate::assert_tensor_eq!(
+            y,
+            crate::tensor![
+                [6, 5, 4, 5, 6, 5, 4],
+                [3, 2, 1, 2, 3, 2, 1],
+                [6, 5, 4, 5, 6, 5, 4],
+                [3, 2, 1, 2, 3, 2, 1],
+            ]
+        );
+        assert!(y.is_standard_layout());
+
+        assert!(x.pad(&[(1, 1)], PadMode::Constant, 0).is_err());
+        assert!(x.pad(&[(2, 0), (0, 0)], PadMode::Reflect, 0).is_err());
+        assert!(x.t().pad(&[(0, 1), (0, 0)], PadMode::Edge, 0).is_ok());
+    }
+
+    #[test]
+    fn test_tile() {
+        let x = crate::tensor![[1, 2], [3, 4]];
+
+        crate::assert_tensor_eq!(
+            x.tile(&[1, 2]).unwrap(),
+            crate::tensor![[1, 2, 1, 2], [3, 4, 3, 4]]
+        );
+        crate::assert_tensor_eq!(
+            x.tile(&[2, 1]).unwrap(),
+            crate::tensor![[1, 2], [3, 4], [1, 2], [3, 4]]
+        );
+        assert_eq!(x.tile(&[0, 3]).unwrap().shape().slice(), &[0, 6]);
+        assert!(x.tile(&[2]).is_err());
+    }
+
+    #[test]
+    fn test_repeat() {
+        let x = crate::tensor![[1, 2], [3, 4]];
+
+        crate::assert_tensor_eq!(x.repeat(1, 2), crate::tensor![[1, 1, 2, 2], [3, 3, 4, 4]]);
+        crate::assert_tensor_eq!(
+            x.repeat(-2, 2),
+            crate::tensor![[1, 2], [1, 2], [3, 4], [3, 4]]
+        );
+        crate::assert_tensor_eq!(x.t().repeat(0, 2), x.repeat(1, 2).t());
+    }
 
     #[test]
     fn test_invert_axis() {
@@ -235,5 +696,13 @@ mod test {
             x.roll(Axis(-1), 1),
             crate::tensor![[3, 1, 2], [6, 4, 5], [9, 7, 8]]
         );
+
+        let y = crate::tensor![1, 2, 3, 4, 5];
+        crate::assert_tensor_eq!(y.roll(0, -7), crate::tensor![3, 4, 5, 1, 2]);
+        crate::assert_tensor_eq!(y.roll(0, 10), y.clone());
+        crate::assert_tensor_eq!(
+            x.t().roll(1, 1),
+            crate::tensor![[7, 1, 4], [8, 2, 5], [9, 3, 6]]
+        );
     }
 }
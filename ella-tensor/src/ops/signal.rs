@@ -0,0 +1,160 @@
+use std::cmp::Ordering;
+
+use crate::{Axis, Const, Shape, Tensor, TensorValue};
+
+impl<T, S> Tensor<T, S>
+where
+    T: TensorValue,
+    S: Shape,
+{
+    /// Apply `f` to each window of `size` elements along `axis`, starting a new window every
+    /// `step` elements.
+    ///
+    /// The length of `axis` in the result is the number of complete windows. Trailing elements
+    /// that don't fill a window are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` or `step` is zero.
+    pub fn window_map<A, U, F>(&self, axis: A, size: usize, step: usize, f: F) -> Tensor<U, S>
+    where
+        A: Into<Axis>,
+        U: TensorValue,
+        F: Fn(Tensor<T, Const<1>>) -> U,
+    {
+        assert!(size > 0, "window size must be positive");
+        assert!(step > 0, "window step must be positive");
+
+        let ax = axis.into().index(self.shape());
+        let len = self.shape()[ax];
+        let windows = if len >= size {
+            (len - size) / step + 1
+        } else {
+            0
+        };
+
+        let mut shape = self.shape().clone();
+        shape[ax] = windows;
+        let mut strides = self.strides().clone();
+        strides[ax] = self.strides()[ax].wrapping_mul(step);
+        let window_strides = Const([self.strides()[ax]]);
+
+        let values = shape.indices().map(|index| {
+            let offset = S::stride_offset(&index, &strides);
+            f(Tensor::new(
+                self.values().offset(offset),
+                Const([size]),
+                window_strides.clone(),
+            ))
+        });
+        unsafe { Tensor::from_trusted_len_iter(values, shape) }
+    }
+}
+
+impl<T> Tensor<T, Const<1>>
+where
+    T: TensorValue,
+{
+    /// Find the indices of local maxima that exceed `threshold`.
+    ///
+    /// Peaks closer than `min_distance` samples to a taller peak are dropped.
+    /// The returned indices are sorted in ascending order. They are `u64` rather than `usize`
+    /// because tensors are backed by Arrow arrays, which have no pointer-sized integer type.
+    pub fn find_peaks(&self, threshold: T, min_distance: usize) -> Tensor<u64, Const<1>> {
+        let values = self.iter().collect::<Vec<_>>();
+        let n = values.len();
+
+        let mut candidates = (0..n)
+            .filter(|&i| {
+                let x = &values[i];
+                *x > threshold
+                    && (i == 0 || *x > values[i - 1])
+                    && (i + 1 == n || *x >= values[i + 1])
+            })
+            .collect::<Vec<_>>();
+
+        if min_distance > 1 {
+            // Visit the tallest peaks first so that they suppress their smaller neighbors.
+            let mut order = candidates.clone();
+            order.sort_by(|&a, &b| {
+                values[b]
+                    .partial_cmp(&values[a])
+                    .unwrap_or(Ordering::Equal)
+                    .then(a.cmp(&b))
+            });
+
+            let mut keep = vec![false; n];
+            let mut suppressed = vec![false; n];
+            for i in order {
+                if suppressed[i] {
+                    continue;
+                }
+                keep[i] = true;
+                let start = i.saturating_sub(min_distance - 1);
+                let end = (i + min_distance).min(n);
+                for s in suppressed.iter_mut().take(end).skip(start) {
+                    *s = true;
+                }
+            }
+            candidates.retain(|&i| keep[i]);
+        }
+
+        candidates.into_iter().map(|i| i as u64).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Axis, Tensor};
+
+    #[test]
+    fn test_find_peaks() {
+        let x = crate::tensor![0.0, 1.0, 0.0, 3.0, 0.0, 2.5, 0.0, 0.0, 0.0, 4.0, 0.0];
+
+        let peaks = x.find_peaks(0.5, 1);
+        crate::assert_tensor_eq!(peaks, crate::tensor![1_u64, 3, 5, 9]);
+
+        let peaks = x.find_peaks(2.0, 1);
+        crate::assert_tensor_eq!(peaks, crate::tensor![3_u64, 5, 9]);
+
+        // The smaller peak at index 5 is within 3 samples of the peak at index 3
+        let peaks = x.find_peaks(0.5, 3);
+        crate::assert_tensor_eq!(peaks, crate::tensor![3_u64, 9]);
+
+        let empty = Tensor::<f64, _>::zeros(8).find_peaks(0.0, 2);
+        assert_eq!(empty.size(), 0);
+    }
+
+    #[test]
+    fn test_window_map() {
+        let x = crate::tensor![
+            [1.0, 3.0, 2.0, 5.0, 4.0, 0.0],
+            [6.0, 1.0, 1.0, 2.0, 7.0, 3.0]
+        ];
+        let max = |w: Tensor<f64, _>| w.iter().fold(f64::MIN, f64::max);
+
+        let y = x.window_map(Axis(1), 3, 1, max);
+        assert_eq!(y.shape().slice(), &[2, 4]);
+        crate::assert_tensor_eq!(
+            y,
+            crate::tensor![[3.0, 5.0, 5.0, 5.0], [6.0, 2.0, 7.0, 7.0]]
+        );
+
+        // The last element doesn't fill a window and is dropped
+        let y = x.window_map(Axis(1), 2, 2, max);
+        crate::assert_tensor_eq!(y, crate::tensor![[3.0, 5.0, 4.0], [6.0, 2.0, 7.0]]);
+        let y = x.window_map(Axis(1), 4, 3, max);
+        crate::assert_tensor_eq!(y, crate::tensor![[5.0], [6.0]]);
+
+        let y = x.window_map(Axis(0), 2, 1, max);
+        crate::assert_tensor_eq!(y, crate::tensor![[6.0, 3.0, 2.0, 5.0, 7.0, 3.0]]);
+        let y = x.t().window_map(Axis(0), 3, 1, max);
+        crate::assert_tensor_eq!(
+            y,
+            crate::tensor![[3.0, 5.0, 5.0, 5.0], [6.0, 2.0, 7.0, 7.0]].t()
+        );
+
+        let y = x.window_map(Axis(1), 8, 1, max);
+        assert_eq!(y.shape().slice(), &[2, 0]);
+    }
+}
@@ -37,6 +37,51 @@ where
         Tensor::new(values, shape, self.strides().clone())
     }
 
+    /// Split the tensor along `axis` into consecutive views with lengths `sizes`.
+    ///
+    /// Returns an error if `sizes` doesn't add up to the length of the axis.
+    pub fn split<A: Into<Axis>>(&self, axis: A, sizes: &[usize]) -> crate::Result<Vec<Self>> {
+        let axis: Axis = axis.into();
+        let ndim = self.ndim() as isize;
+        if axis.0 < -ndim || axis.0 >= ndim {
+            return Err(crate::Error::AxisOutOfBounds(axis.0, self.ndim()));
+        }
+        let len = self.shape()[axis.index(self.shape())];
+        let total = sizes.iter().sum::<usize>();
+        if total != len {
+            return Err(crate::ShapeError::split(len, total).into());
+        }
+
+        let mut start = 0;
+        Ok(sizes
+            .iter()
+            .map(|size| {
+                let part = self.slice_axis(axis, start..start + size);
+                start += size;
+                part
+            })
+            .collect())
+    }
+
+    /// Split the tensor along `axis` into `n` views whose lengths differ by at most one.
+    ///
+    /// The first `len % n` views are one longer than the rest.
+    pub fn chunks<A: Into<Axis>>(&self, axis: A, n: usize) -> crate::Result<Vec<Self>> {
+        let axis: Axis = axis.into();
+        let ndim = self.ndim() as isize;
+        if axis.0 < -ndim || axis.0 >= ndim {
+            return Err(crate::Error::AxisOutOfBounds(axis.0, self.ndim()));
+        }
+        let len = self.shape()[axis.index(self.shape())];
+        if n == 0 {
+            return Err(crate::ShapeError::split(len, 0).into());
+        }
+        let sizes = (0..n)
+            .map(|i| len / n + usize::from(i < len % n))
+            .collect::<Vec<_>>();
+        self.split(axis, &sizes)
+    }
+
     pub fn slice<I: SliceShape<S>>(&self, slice: I) -> Tensor<T, I::Out> {
         let mut this = self.clone();
         let mut shape = <I::Out as Shape>::zeros(slice.out_ndim());
@@ -67,6 +112,34 @@ where
         Tensor::new(this.into_values(), shape, strides)
     }
 
+    /// Write `value` into the region of the tensor selected by `slice`.
+    ///
+    /// `value` is broadcast to the shape of the selected region. Returns an error if it can't
+    /// be broadcast. The tensor's values are always copied since Arrow buffers are immutable.
+    pub fn slice_assign<I, S2>(&mut self, slice: I, value: &Tensor<T, S2>) -> crate::Result<()>
+    where
+        I: SliceShape<S>,
+        S2: Shape,
+    {
+        // Slice a tensor of flat indices to find the positions of the selected elements
+        let size = self.size();
+        let indices: Tensor<u64, S> =
+            unsafe { Tensor::from_trusted_len_iter(0..size as u64, self.shape().clone()) };
+        let target = indices.slice(slice);
+        let value = value.broadcast_to(target.shape().clone())?;
+
+        let mut updates = vec![None; size];
+        for (idx, x) in target.iter().zip(value.iter()) {
+            updates[idx as usize] = Some(x);
+        }
+        let values = self
+            .iter()
+            .zip(updates)
+            .map(|(old, new)| new.unwrap_or(old));
+        *self = unsafe { Tensor::from_trusted_len_iter(values, self.shape().clone()) };
+        Ok(())
+    }
+
     pub fn diag(&self) -> Tensor<T, Const<1>> {
         let len = self.shape().slice().iter().copied().min().unwrap_or(1);
         let stride = self.strides().slice().iter().sum();
@@ -77,7 +150,7 @@ where
 
 #[cfg(test)]
 mod test {
-    use crate::{Axis, NewAxis};
+    use crate::{Axis, NewAxis, Slice};
 
     #[test]
     fn test_slice_axis() {
@@ -86,6 +159,51 @@ mod test {
         crate::assert_tensor_eq!(x.slice_axis(Axis(1), 2..), crate::tensor![[3, 4], [7, 8]]);
         crate::assert_tensor_eq!(x.slice_axis(Axis(1), 1..-2), crate::tensor![[2], [6]]);
         crate::assert_tensor_eq!(x.slice_axis(Axis(0), 1..), crate::tensor![[5, 6, 7, 8]]);
+
+        // Every other element, forwards and backwards
+        let y = crate::tensor![1, 2, 3, 4, 5, 6, 7];
+        crate::assert_tensor_eq!(
+            y.slice_axis(Axis(0), Slice::from(1..6).step_by(2)),
+            crate::tensor![2, 4, 6]
+        );
+        crate::assert_tensor_eq!(
+            y.slice_axis(Axis(0), Slice::from(..).step_by(-2)),
+            crate::tensor![7, 5, 3, 1]
+        );
+
+        // Out-of-range bounds are clamped to the axis
+        crate::assert_tensor_eq!(x.slice_axis(Axis(1), 2..10), crate::tensor![[3, 4], [7, 8]]);
+        crate::assert_tensor_eq!(x.slice_axis(Axis(1), -10..1), crate::tensor![[1], [5]]);
+        assert_eq!(x.slice_axis(Axis(1), 6..).shape().slice(), &[2, 0]);
+    }
+
+    #[test]
+    fn test_split() {
+        let x = crate::tensor![[1, 2, 3, 4, 5], [6, 7, 8, 9, 10],];
+
+        let parts = x.split(1, &[2, 0, 3]).unwrap();
+        assert_eq!(parts.len(), 3);
+        crate::assert_tensor_eq!(parts[0].clone(), crate::tensor![[1, 2], [6, 7]]);
+        assert_eq!(parts[1].shape().slice(), &[2, 0]);
+        crate::assert_tensor_eq!(parts[2].clone(), crate::tensor![[3, 4, 5], [8, 9, 10]]);
+
+        let parts = x.split(-2, &[1, 1]).unwrap();
+        crate::assert_tensor_eq!(parts[1].clone(), crate::tensor![[6, 7, 8, 9, 10]]);
+
+        assert!(x.split(1, &[2, 2]).is_err());
+        assert!(x.split(2, &[2]).is_err());
+    }
+
+    #[test]
+    fn test_chunks() {
+        let x = crate::tensor![1, 2, 3, 4, 5, 6, 7];
+
+        let parts = x.chunks(0, 3).unwrap();
+        crate::assert_tensor_eq!(parts[0].clone(), crate::tensor![1, 2, 3]);
+        crate::assert_tensor_eq!(parts[1].clone(), crate::tensor![4, 5]);
+        crate::assert_tensor_eq!(parts[2].clone(), crate::tensor![6, 7]);
+
+        assert!(x.chunks(0, 0).is_err());
     }
 
     #[test]
@@ -98,4 +216,27 @@ mod test {
             crate::tensor![[[5, 7], [1, 3]]]
         );
     }
+
+    #[test]
+    fn test_slice_assign() {
+        let mut x = crate::Tensor::arange(0, 12, 1).reshape((3, 4)).unwrap();
+        x.slice_assign(crate::slice![1, ..], &crate::tensor![10, 20, 30, 40])
+            .unwrap();
+        crate::assert_tensor_eq!(
+            x.clone(),
+            crate::tensor![[0, 1, 2, 3], [10, 20, 30, 40], [8, 9, 10, 11]]
+        );
+
+        // Broadcast a single value over a column
+        x.slice_assign(crate::slice![.., 0], &crate::tensor![-1])
+            .unwrap();
+        crate::assert_tensor_eq!(
+            x.clone(),
+            crate::tensor![[-1, 1, 2, 3], [-1, 20, 30, 40], [-1, 9, 10, 11]]
+        );
+
+        assert!(x
+            .slice_assign(crate::slice![0, ..], &crate::tensor![1, 2])
+            .is_err());
+    }
 }
@@ -266,15 +266,15 @@ pub(crate) fn do_slice(axis: &mut usize, stride: &mut usize, slice: Slice) -> is
     offset
 }
 
+/// Resolve `slice` to absolute `(start, end, step)` for an axis of length `axis_len`.
+///
+/// Bounds outside the axis are clamped to its ends, so slices never extend past the axis.
 fn to_abs_slice(axis_len: usize, slice: Slice) -> (usize, usize, isize) {
     let Slice { start, end, step } = slice;
-    let start = start.abs_index(axis_len);
-    let mut end = end.unwrap_or(axis_len as isize).abs_index(axis_len);
-    if end < start {
-        end = start;
-    }
-    assert!(start <= axis_len, "slice start index > axis length");
-    assert!(end <= axis_len, "slice end index > axis length");
+    let len = axis_len as isize;
+    let clamp = |idx: isize| (if idx < 0 { idx + len } else { idx }).clamp(0, len) as usize;
+    let start = clamp(start);
+    let end = clamp(end.unwrap_or(len)).max(start);
     assert_ne!(step, 0, "slice step must not be zero");
     (start, end, step)
 }
@@ -9,7 +9,10 @@ pub use iter::TensorIter;
 use crate::{Column, Const, Dyn, Mask, Shape, TensorValue};
 use arrow::array::{make_array, Array, ArrayData, ArrayRef, FixedSizeListArray};
 use ella_common::array::flatten;
-use std::sync::Arc;
+use std::{
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
 
 pub type Tensor1<T> = Tensor<T, Const<1>>;
 pub type Tensor2<T> = Tensor<T, Const<2>>;
@@ -98,6 +101,15 @@ where
     }
 
     pub fn into_arrow(self) -> ArrayRef {
+        self.into_arrow_copied().0
+    }
+
+    /// Convert the tensor to an Arrow array, also returning whether the values had to be copied.
+    ///
+    /// Arrow arrays are always in standard layout, so the values are only copied if the tensor
+    /// isn't (e.g. after a transpose or broadcast).
+    pub fn into_arrow_copied(self) -> (ArrayRef, bool) {
+        let copied = !self.is_standard_layout();
         let this = self.to_standard_layout();
         let dtype = this.arrow_type();
         if this.shape().ndim() > 1 {
@@ -107,7 +119,7 @@ where
                     .len(this.shape[0])
                     .build_unchecked()
             };
-            Arc::new(FixedSizeListArray::from(data))
+            (Arc::new(FixedSizeListArray::from(data)), copied)
         } else {
             let data = unsafe {
                 this.values
@@ -117,7 +129,7 @@ where
                     .data_type(dtype)
                     .build_unchecked()
             };
-            make_array(data)
+            (make_array(data), copied)
         }
     }
 
@@ -126,6 +138,7 @@ where
             self
         } else {
             self.values = unsafe { T::from_trusted_len_iter(self.iter()).into() };
+            self.strides = self.shape.default_strides();
             self
         }
     }
@@ -159,3 +172,91 @@ macro_rules! tensor {
         $crate::Tensor::from(vec![$($x,)*])
     }};
 }
+
+/// Tensors are equal if they have the same shape and elements, regardless of memory layout.
+impl<T, S> PartialEq for Tensor<T, S>
+where
+    T: TensorValue,
+    S: Shape,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.shape.slice() == other.shape.slice() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T, S> Eq for Tensor<T, S>
+where
+    T: TensorValue + Eq,
+    S: Shape,
+{
+}
+
+/// Hashes the shape and elements of the tensor in logical order, consistent with [`PartialEq`].
+impl<T, S> Hash for Tensor<T, S>
+where
+    T: TensorValue + Hash,
+    S: Shape,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.shape.slice().hash(state);
+        for x in self.iter() {
+            x.hash(state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::hash_map::DefaultHasher,
+        hash::{Hash, Hasher},
+    };
+
+    fn hash<T: Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_to_standard_layout() {
+        let x = crate::tensor![[1, 2, 3], [4, 5, 6]];
+
+        // The copied values are in standard order, so the strides must be too
+        let y = x.t().to_standard_layout();
+        assert!(y.is_standard_layout());
+        assert_eq!(y.strides().slice(), &[2, 1]);
+        crate::assert_tensor_eq!(y, crate::tensor![[1, 4], [2, 5], [3, 6]]);
+    }
+
+    #[test]
+    fn test_hash_layout() {
+        let x = crate::tensor![[1, 2, 3], [4, 5, 6]];
+
+        let standard = x.clone().to_standard_layout();
+        assert_eq!(x, standard);
+        assert_eq!(hash(&x), hash(&standard));
+
+        // Same logical contents as `x` but with transposed strides
+        let restored = x.t().to_standard_layout().t();
+        assert!(!restored.is_standard_layout());
+        assert_eq!(x, restored);
+        assert_eq!(hash(&x), hash(&restored));
+
+        let other = crate::tensor![[1, 2], [3, 4], [5, 6]];
+        assert_ne!(x.as_dyn(), other.as_dyn());
+    }
+
+    #[test]
+    fn test_arrow_copy() {
+        let x = crate::tensor![[1, 2, 3], [4, 5, 6]];
+
+        let (array, copied) = x.clone().into_arrow_copied();
+        assert!(!copied);
+        assert_eq!(array.len(), 2);
+
+        let (array, copied) = x.t().into_arrow_copied();
+        assert!(copied);
+        assert_eq!(array.len(), 3);
+    }
+}
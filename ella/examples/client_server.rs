@@ -34,7 +34,7 @@ async fn main() -> ella::Result<()> {
 
         for _ in 0..10 {
             a.feed(ella::row!(
-                Tensor::linspace(0_f32, 1_f32, 100).reshape((10, 10))
+                Tensor::linspace(0_f32, 1_f32, 100).reshape((10, 10))?
             ))
             .await?;
         }
@@ -82,7 +82,7 @@ impl Ella {
                 ctx.shutdown().await?;
                 res
             }
-            Remote(_) => Ok(()),
+            Remote(client) => client.close().await,
         }
     }
 